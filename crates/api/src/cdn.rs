@@ -0,0 +1,82 @@
+//! Uploads to the CDN, ported from `src/util/util/cdn.ts`.
+
+use anyhow::{bail, Result};
+use config::Config;
+use reqwest::{
+    multipart::{Form, Part},
+    Body,
+};
+use serde::Deserialize;
+
+/// CDN address used when `cdn.endpointPrivate` is not configured.
+const DEFAULT_ENDPOINT: &str = "http://localhost:3001";
+
+/// A file sent along with a request, passed on to the CDN as it arrives.
+#[derive(Debug)]
+pub struct Upload {
+    pub filename: String,
+    pub content_type: Option<String>,
+    pub body: Body,
+}
+
+/// A file stored by the CDN.
+#[derive(Debug, Deserialize)]
+pub struct UploadedFile {
+    pub id: String,
+    pub content_type: String,
+    pub filename: String,
+    pub size: u64,
+    pub url: String,
+    /// Where the CDN stores the file, relative to its endpoint.
+    pub path: String,
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+}
+
+/// Store `file` as an attachment of the channel `channel_id`.
+pub async fn upload_attachment(
+    config: &Config,
+    channel_id: &str,
+    file: Upload,
+) -> Result<UploadedFile> {
+    let mut part = Part::stream(file.body).file_name(file.filename);
+    if let Some(content_type) = &file.content_type {
+        part = part.mime_str(content_type)?;
+    }
+
+    let res = reqwest::Client::new()
+        .post(format!("{}/attachments/{channel_id}", endpoint(config)))
+        .header("signature", &config.security.request_signature)
+        .multipart(Form::new().part("file", part))
+        .send()
+        .await?;
+    if !res.status().is_success() {
+        bail!("CDN rejected the upload with {}", res.status());
+    }
+    Ok(res.json().await?)
+}
+
+/// Remove `file`, such as when the message it was uploaded for could not be
+/// stored.
+pub async fn delete_attachment(config: &Config, file: &UploadedFile) -> Result<()> {
+    let res = reqwest::Client::new()
+        .delete(format!("{}/{}", endpoint(config), file.path))
+        .header("signature", &config.security.request_signature)
+        .send()
+        .await?;
+    if !res.status().is_success() {
+        bail!("CDN rejected the deletion with {}", res.status());
+    }
+    Ok(())
+}
+
+/// Address the API reaches the CDN at.
+fn endpoint(config: &Config) -> &str {
+    config
+        .cdn
+        .endpoint
+        .endpoint_private
+        .as_deref()
+        .unwrap_or(DEFAULT_ENDPOINT)
+        .trim_end_matches('/')
+}
@@ -0,0 +1,173 @@
+//! Channel rows and the channel objects sent to clients.
+
+use serde_json::{json, Value};
+use util::PermissionOverwrite;
+use util_db::{
+    entities::{
+        Channel, Guild, Recipient, User, CHANNEL_GUILD_ANNOUNCEMENT, CHANNEL_GUILD_CATEGORY,
+        CHANNEL_GUILD_FORUM, CHANNEL_GUILD_STAGE_VOICE, CHANNEL_GUILD_TEXT, CHANNEL_GUILD_VOICE,
+    },
+    types::{Bool, Json, Timestamp},
+    DbPool,
+};
+
+use crate::{
+    error::ApiError,
+    guild::merge,
+    models::{channel::ChannelCreateRequest, user::MinimalPublicUser},
+    AppState,
+};
+
+/// Channel types that may be created in a guild.
+pub const GUILD_CHANNEL_TYPES: &[i32] = &[
+    CHANNEL_GUILD_TEXT,
+    CHANNEL_GUILD_VOICE,
+    CHANNEL_GUILD_CATEGORY,
+    CHANNEL_GUILD_ANNOUNCEMENT,
+    CHANNEL_GUILD_STAGE_VOICE,
+    CHANNEL_GUILD_FORUM,
+];
+
+/// Channel row for `request` in the guild `guild_id`.
+pub fn new_channel(
+    guild_id: &str,
+    id: String,
+    parent_id: Option<String>,
+    request: &ChannelCreateRequest,
+    overwrites: Vec<PermissionOverwrite>,
+) -> Channel {
+    let voice = matches!(
+        request.kind,
+        CHANNEL_GUILD_VOICE | CHANNEL_GUILD_STAGE_VOICE
+    );
+    Channel {
+        id,
+        created_at: Timestamp::now(),
+        name: Some(request.name.clone()),
+        icon: request.icon.clone(),
+        kind: request.kind,
+        last_message_id: None,
+        guild_id: Some(guild_id.to_string()),
+        parent_id,
+        owner_id: None,
+        last_pin_timestamp: None,
+        default_auto_archive_duration: request.default_auto_archive_duration,
+        permission_overwrites: Some(Json(overwrites)),
+        video_quality_mode: request.video_quality_mode,
+        bitrate: request.bitrate.or(voice.then_some(64000)),
+        user_limit: request.user_limit.or(voice.then_some(0)),
+        nsfw: Bool(request.nsfw.unwrap_or(false)),
+        rate_limit_per_user: Some(request.rate_limit_per_user.unwrap_or(0)),
+        topic: request.topic.clone(),
+        retention_policy_id: None,
+        flags: request.flags.unwrap_or(0),
+        default_thread_rate_limit_per_user: request.default_thread_rate_limit_per_user.unwrap_or(0),
+    }
+}
+
+/// `channel` as sent to clients, with its position in the guild `ordering`.
+pub fn channel_json(channel: &Channel, ordering: &[String]) -> Value {
+    let position = ordering
+        .iter()
+        .position(|id| *id == channel.id)
+        .unwrap_or(0);
+    merge(json!(channel), json!({ "position": position }))
+}
+
+/// Direct message `channel` as sent to clients, with its `recipients`.
+pub fn dm_json(channel: &Channel, recipients: &[User]) -> Value {
+    let recipients: Vec<MinimalPublicUser> =
+        recipients.iter().map(MinimalPublicUser::from).collect();
+    merge(json!(channel), json!({ "recipients": recipients }))
+}
+
+/// `channel` as sent to clients, with its position if it belongs to a guild
+/// or its recipients if it is a direct message.
+pub async fn load_json(db: &DbPool, channel: &Channel) -> Result<Value, ApiError> {
+    if let Some(guild_id) = &channel.guild_id {
+        let ordering = Guild::find(db, guild_id)
+            .await?
+            .map(|guild| guild.channel_ordering.0)
+            .unwrap_or_default();
+        return Ok(channel_json(channel, &ordering));
+    }
+    let mut users = Vec::new();
+    for recipient in Recipient::find_by_channel(db, &channel.id).await? {
+        if let Some(user) = User::find(db, &recipient.user_id).await? {
+            users.push(user);
+        }
+    }
+    Ok(dm_json(channel, &users))
+}
+
+/// Check that a channel of type `kind` may be placed in the category
+/// `parent_id` of the guild `guild_id`, and that the category has room for
+/// it. Returns the category.
+pub async fn check_parent(
+    state: &AppState,
+    guild_id: &str,
+    kind: i32,
+    parent_id: &str,
+) -> Result<Channel, ApiError> {
+    let parent = Channel::find(&state.db, parent_id).await?;
+    let Some(parent) = parent.filter(|parent| {
+        parent.guild_id.as_deref() == Some(guild_id) && parent.kind == CHANNEL_GUILD_CATEGORY
+    }) else {
+        return Err(ApiError::field(
+            "parent_id",
+            "CHANNEL_PARENT_INVALID",
+            format!("Channel parent {parent_id} is not a category of this guild"),
+        ));
+    };
+    if kind == CHANNEL_GUILD_CATEGORY {
+        return Err(ApiError::field(
+            "parent_id",
+            "CHANNEL_PARENT_INVALID",
+            "Categories cannot have a parent",
+        ));
+    }
+
+    let max = state.config.get().limits.guild.max_channels_in_category;
+    if Channel::count_by_parent(&state.db, parent_id).await? >= i64::from(max) {
+        return Err(ApiError::field(
+            "parent_id",
+            "CHANNEL_PARENT_MAX_CHANNELS",
+            format!("Maximum number of channels in category reached ({max})"),
+        ));
+    }
+    Ok(parent)
+}
+
+/// Check that `topic` fits in the configured limit.
+pub fn check_topic(state: &AppState, topic: &str) -> Result<(), ApiError> {
+    let max = state.config.get().limits.channel.max_topic;
+    if topic.chars().count() > max as usize {
+        return Err(ApiError::field(
+            "topic",
+            "BASE_TYPE_BAD_LENGTH",
+            format!("Must be {max} or fewer in length."),
+        ));
+    }
+    Ok(())
+}
+
+/// Move the channel `id` within the guild `ordering`: to `position` if
+/// given, otherwise right after its category `parent_id`, or to the end for
+/// channels without a category.
+pub fn place(
+    ordering: &mut Vec<String>,
+    id: &str,
+    position: Option<usize>,
+    parent_id: Option<&str>,
+) {
+    ordering.retain(|other| other != id);
+    let index = match (position, parent_id) {
+        (Some(position), _) => position.min(ordering.len()),
+        (None, Some(parent_id)) => ordering
+            .iter()
+            .position(|other| other == parent_id)
+            .map_or(ordering.len(), |index| index + 1),
+        (None, None) => ordering.len(),
+    };
+    ordering.insert(index, id.to_string());
+}
@@ -0,0 +1,51 @@
+//! Gateway events published by route handlers. The change they announce is
+//! already stored when they are sent, so a failure to publish is logged
+//! rather than failing the request.
+
+use events::{emit_event, Event};
+use serde_json::Value;
+use util_db::entities::Channel;
+
+async fn publish(event: Event) {
+    let name = event.event.clone();
+    if let Err(e) = emit_event(event).await {
+        eprintln!("[API] Failed to publish {name}: {e}");
+    }
+}
+
+/// Send `event` to every member of the guild `guild_id`.
+pub async fn to_guild(guild_id: &str, event: &str, data: Value) {
+    publish(Event {
+        event: event.into(),
+        data,
+        guild_id: Some(guild_id.to_string()),
+        channel_id: None,
+        user_id: None,
+    })
+    .await;
+}
+
+/// Send `event` to the sessions of the user `user_id`.
+pub async fn to_user(user_id: &str, event: &str, data: Value) {
+    publish(Event {
+        event: event.into(),
+        data,
+        guild_id: None,
+        channel_id: None,
+        user_id: Some(user_id.to_string()),
+    })
+    .await;
+}
+
+/// Send `event` to everyone who can see `channel`: the members of its guild,
+/// or the recipients of a direct message.
+pub async fn to_channel(channel: &Channel, event: &str, data: Value) {
+    publish(Event {
+        event: event.into(),
+        data,
+        guild_id: channel.guild_id.clone(),
+        channel_id: Some(channel.id.clone()),
+        user_id: None,
+    })
+    .await;
+}
@@ -0,0 +1,99 @@
+//! Error responses in the formats of the TypeScript server.
+
+use axum::{
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde_json::{json, Map, Value};
+
+/// Error returned by a route handler.
+#[derive(Debug)]
+pub enum ApiError {
+    /// `{ "code": <status>, "message": ... }`, like `HTTPError`.
+    Http { status: StatusCode, message: String },
+    /// `{ "code": <code>, "message": ... }`, like `DiscordApiErrors`.
+    Api {
+        status: StatusCode,
+        code: u32,
+        message: String,
+    },
+    /// `400 Invalid Form Body` listing `(field, code, message)` errors, like
+    /// `FieldErrors`.
+    Fields(Vec<(&'static str, &'static str, String)>),
+    /// A body other than the usual error shapes, such as a captcha challenge.
+    Raw { status: StatusCode, body: Value },
+    /// An unexpected failure, logged and reported as a 500.
+    Internal(anyhow::Error),
+}
+
+impl ApiError {
+    pub fn http(status: StatusCode, message: impl Into<String>) -> Self {
+        Self::Http {
+            status,
+            message: message.into(),
+        }
+    }
+
+    pub fn api(status: StatusCode, code: u32, message: impl Into<String>) -> Self {
+        Self::Api {
+            status,
+            code,
+            message: message.into(),
+        }
+    }
+
+    /// A single invalid field.
+    pub fn field(field: &'static str, code: &'static str, message: impl Into<String>) -> Self {
+        Self::Fields(vec![(field, code, message.into())])
+    }
+}
+
+impl<E: Into<anyhow::Error>> From<E> for ApiError {
+    fn from(error: E) -> Self {
+        Self::Internal(error.into())
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let (status, body) = match self {
+            Self::Http { status, message } => (
+                status,
+                json!({ "code": status.as_u16(), "message": message }),
+            ),
+            Self::Api {
+                status,
+                code,
+                message,
+            } => (status, json!({ "code": code, "message": message })),
+            Self::Fields(fields) => {
+                let mut errors = Map::new();
+                for (field, code, message) in fields {
+                    let entry = errors
+                        .entry(field)
+                        .or_insert_with(|| json!({ "_errors": [] }));
+                    if let Some(list) = entry["_errors"].as_array_mut() {
+                        list.push(json!({ "code": code, "message": message }));
+                    }
+                }
+                let body = json!({
+                    "code": 50035,
+                    "message": "Invalid Form Body",
+                    "errors": errors,
+                });
+                (StatusCode::BAD_REQUEST, body)
+            }
+            Self::Raw { status, body } => (status, body),
+            Self::Internal(e) => {
+                eprintln!("[API] {e:?}");
+                let status = StatusCode::INTERNAL_SERVER_ERROR;
+                (
+                    status,
+                    json!({ "code": 500, "message": "Internal Server Error" }),
+                )
+            }
+        };
+        (status, Json(body)).into_response()
+    }
+}
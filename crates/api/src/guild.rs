@@ -0,0 +1,443 @@
+//! Guild creation and the guild objects sent to clients.
+
+use std::collections::HashMap;
+
+use axum::http::StatusCode;
+use serde_json::{json, Value};
+use util::{PermissionOverwrite, Permissions, Snowflake};
+use util_db::{
+    entities::{Channel, Guild, Member, Role, User, CHANNEL_GUILD_CATEGORY, CHANNEL_GUILD_TEXT},
+    types::{Bool, Json, SimpleArray, Timestamp},
+    Dialect,
+};
+
+use crate::{
+    channel::{channel_json, new_channel, GUILD_CHANNEL_TYPES},
+    dispatch,
+    error::ApiError,
+    models::{
+        channel::ChannelCreateRequest,
+        guild::{GuildCreateRequest, GuildCreateRole},
+        user::MinimalPublicUser,
+    },
+    AppState,
+};
+
+/// Permissions of the `@everyone` role of a new guild, the value the
+/// TypeScript server grants.
+const EVERYONE_PERMISSIONS: u64 = 2251804225;
+
+/// `system_channel_flags` of a new guild, suppressing the setup tips.
+const SYSTEM_CHANNEL_FLAGS: i32 = 4;
+
+/// A guild with its roles and channels, as stored by [`create_guild`].
+pub struct CreatedGuild {
+    pub guild: Guild,
+    pub roles: Vec<Role>,
+    pub channels: Vec<Channel>,
+}
+
+/// Create a guild owned by `owner_id` and add the owner as its first member.
+pub async fn create_guild(
+    state: &AppState,
+    owner_id: &str,
+    payload: GuildCreateRequest,
+) -> Result<CreatedGuild, ApiError> {
+    let config = state.config.get();
+    let limits = &config.limits.guild;
+    let guild_id = Snowflake::generate().to_string();
+
+    if payload.roles.len() > limits.max_roles as usize {
+        return Err(ApiError::api(
+            StatusCode::BAD_REQUEST,
+            30005,
+            format!(
+                "Maximum number of guild roles reached ({})",
+                limits.max_roles
+            ),
+        ));
+    }
+    let (roles, role_ids) = build_roles(&guild_id, &payload.roles);
+
+    let requests = if payload.channels.is_empty() {
+        default_channels()
+    } else {
+        payload.channels
+    };
+    if requests.len() > limits.max_channels as usize {
+        return Err(ApiError::api(
+            StatusCode::BAD_REQUEST,
+            30013,
+            format!(
+                "Maximum number of guild channels reached ({})",
+                limits.max_channels
+            ),
+        ));
+    }
+    let (channels, channel_ids) = build_channels(&guild_id, &requests, &role_ids)?;
+    let ordering = channel_ordering(&channels);
+
+    let defaults = &config.defaults.guild;
+    let system_channel_id = match &payload.system_channel_id {
+        Some(placeholder) => channel_ids.get(placeholder).cloned(),
+        None => channels
+            .iter()
+            .find(|channel| channel.kind == CHANNEL_GUILD_TEXT)
+            .map(|channel| channel.id.clone()),
+    };
+    let guild = Guild {
+        id: guild_id.clone(),
+        afk_channel_id: payload
+            .afk_channel_id
+            .as_ref()
+            .and_then(|placeholder| channel_ids.get(placeholder).cloned()),
+        afk_timeout: Some(payload.afk_timeout.unwrap_or(defaults.afk_timeout as i32)),
+        banner: None,
+        default_message_notifications: Some(
+            payload
+                .default_message_notifications
+                .unwrap_or(defaults.default_message_notifications as i32),
+        ),
+        description: None,
+        discovery_splash: None,
+        explicit_content_filter: Some(
+            payload
+                .explicit_content_filter
+                .unwrap_or(defaults.explicit_content_filter as i32),
+        ),
+        features: SimpleArray(config.guild.default_features.clone()),
+        primary_category_id: None,
+        icon: None,
+        large: Bool(false),
+        max_members: Some(i32::try_from(limits.max_members).unwrap_or(i32::MAX)),
+        max_presences: Some(defaults.max_presences as i32),
+        max_video_channel_users: Some(defaults.max_video_channel_users as i32),
+        // Counted up by adding the owner below.
+        member_count: Some(0),
+        presence_count: Some(0),
+        template_id: None,
+        mfa_level: Some(0),
+        name: payload.name.trim().to_string(),
+        owner_id: Some(owner_id.to_string()),
+        preferred_locale: Some("en-US".into()),
+        premium_subscription_count: Some(0),
+        premium_tier: 0,
+        public_updates_channel_id: None,
+        rules_channel_id: None,
+        region: Some(
+            payload
+                .region
+                .unwrap_or_else(|| config.regions.default.clone()),
+        ),
+        splash: None,
+        system_channel_id,
+        system_channel_flags: Some(payload.system_channel_flags.unwrap_or(SYSTEM_CHANNEL_FLAGS)),
+        unavailable: Bool(false),
+        verification_level: Some(payload.verification_level.unwrap_or(0)),
+        welcome_screen: Json(json!({
+            "enabled": false,
+            "description": "",
+            "welcome_channels": [],
+        })),
+        widget_channel_id: None,
+        widget_enabled: Bool(true),
+        nsfw_level: Some(0),
+        nsfw: Bool(false),
+        parent: None,
+        premium_progress_bar_enabled: Some(Bool(false)),
+        channel_ordering: SimpleArray(ordering),
+    };
+
+    let dialect = Dialect::of(&state.db);
+    let mut tx = state.db.begin().await?;
+    // Roles and channels refer to the guild, so it goes first.
+    guild.insert(&mut *tx, dialect).await?;
+    for role in &roles {
+        role.insert(&mut *tx, dialect).await?;
+    }
+    for channel in &channels {
+        channel.insert(&mut *tx, dialect).await?;
+    }
+    // The owner joins in the same transaction, so a guild never exists
+    // without them.
+    let mut owner = new_member(&guild_id, owner_id);
+    owner.index = owner.insert(&mut *tx, dialect).await?;
+    // Every member holds the `@everyone` role, whose ID is the guild ID.
+    Member::add_role(&mut *tx, dialect, owner.index, &guild_id).await?;
+    Guild::add_member_count(&mut *tx, dialect, &guild_id, 1).await?;
+    tx.commit().await?;
+
+    let mut created = CreatedGuild {
+        guild,
+        roles,
+        channels,
+    };
+    created.guild.member_count = Some(1);
+    announce_member(state, &created, &owner).await?;
+    Ok(created)
+}
+
+/// The `@everyone` role, configured by the first of `templates`, followed by
+/// the other roles, along with the IDs given to their placeholders.
+fn build_roles(
+    guild_id: &str,
+    templates: &[GuildCreateRole],
+) -> (Vec<Role>, HashMap<String, String>) {
+    let mut ids = HashMap::new();
+    let mut roles = Vec::with_capacity(templates.len().max(1));
+    let mut templates = templates.iter();
+
+    let everyone = templates.next();
+    if let Some(placeholder) = everyone.and_then(|role| role.id.clone()) {
+        ids.insert(placeholder, guild_id.to_string());
+    }
+    roles.push(Role {
+        id: guild_id.to_string(),
+        guild_id: guild_id.to_string(),
+        color: 0,
+        hoist: Bool(false),
+        managed: Bool(false),
+        mentionable: Bool(false),
+        name: "@everyone".into(),
+        permissions: everyone
+            .and_then(|role| role.permissions)
+            .unwrap_or(Permissions::from_bits_retain(EVERYONE_PERMISSIONS))
+            .to_string(),
+        position: 0,
+        icon: None,
+        unicode_emoji: None,
+        tags: None,
+        flags: 0,
+    });
+
+    for (position, template) in templates.enumerate() {
+        let id = Snowflake::generate().to_string();
+        if let Some(placeholder) = &template.id {
+            ids.insert(placeholder.clone(), id.clone());
+        }
+        roles.push(Role {
+            id,
+            guild_id: guild_id.to_string(),
+            color: template.color.unwrap_or(0),
+            hoist: Bool(template.hoist.unwrap_or(false)),
+            managed: Bool(false),
+            mentionable: Bool(template.mentionable.unwrap_or(false)),
+            name: template.name.clone().unwrap_or_else(|| "new role".into()),
+            permissions: template.permissions.unwrap_or_default().to_string(),
+            position: position as i32 + 1,
+            icon: None,
+            unicode_emoji: template.unicode_emoji.clone(),
+            tags: None,
+            flags: 0,
+        });
+    }
+    (roles, ids)
+}
+
+/// A text channel in a category, for guilds created without channels.
+fn default_channels() -> Vec<ChannelCreateRequest> {
+    vec![
+        ChannelCreateRequest {
+            id: Some("1".into()),
+            name: "Text Channels".into(),
+            kind: CHANNEL_GUILD_CATEGORY,
+            ..Default::default()
+        },
+        ChannelCreateRequest {
+            id: Some("2".into()),
+            name: "general".into(),
+            kind: CHANNEL_GUILD_TEXT,
+            parent_id: Some("1".into()),
+            ..Default::default()
+        },
+    ]
+}
+
+/// Channels of a new guild, with their placeholders replaced by snowflakes.
+/// Overwrites referring to role placeholders are mapped through `role_ids`.
+fn build_channels(
+    guild_id: &str,
+    requests: &[ChannelCreateRequest],
+    role_ids: &HashMap<String, String>,
+) -> Result<(Vec<Channel>, HashMap<String, String>), ApiError> {
+    let ids: HashMap<String, String> = requests
+        .iter()
+        .filter_map(|request| request.id.clone())
+        .map(|placeholder| (placeholder, Snowflake::generate().to_string()))
+        .collect();
+
+    let mut channels = Vec::with_capacity(requests.len());
+    for request in requests {
+        if !GUILD_CHANNEL_TYPES.contains(&request.kind) {
+            return Err(ApiError::field(
+                "channels",
+                "CHANNEL_TYPE_INVALID",
+                format!("Channel type {} cannot be created in a guild", request.kind),
+            ));
+        }
+        let parent_id = match &request.parent_id {
+            Some(placeholder) => {
+                let parent = requests
+                    .iter()
+                    .find(|other| other.id.as_ref() == Some(placeholder))
+                    .filter(|other| other.kind == CHANNEL_GUILD_CATEGORY);
+                if parent.is_none() || request.kind == CHANNEL_GUILD_CATEGORY {
+                    return Err(ApiError::field(
+                        "channels",
+                        "CHANNEL_PARENT_INVALID",
+                        format!("Channel parent {placeholder} is not a category"),
+                    ));
+                }
+                ids.get(placeholder).cloned()
+            }
+            None => None,
+        };
+        let overwrites = request
+            .permission_overwrites
+            .iter()
+            .flatten()
+            .filter_map(|overwrite| {
+                let id = overwrite.id.as_ref()?;
+                Some(PermissionOverwrite {
+                    allow: overwrite.allow,
+                    deny: overwrite.deny,
+                    id: role_ids.get(id).unwrap_or(id).clone(),
+                    kind: overwrite.kind,
+                })
+            })
+            .collect();
+        let id = match &request.id {
+            Some(placeholder) => ids[placeholder].clone(),
+            None => Snowflake::generate().to_string(),
+        };
+        channels.push(new_channel(guild_id, id, parent_id, request, overwrites));
+    }
+    Ok((channels, ids))
+}
+
+/// Order of the channels of a new guild: every channel without a parent in
+/// the order given, each followed by its children.
+fn channel_ordering(channels: &[Channel]) -> Vec<String> {
+    let mut ordering = Vec::with_capacity(channels.len());
+    for channel in channels
+        .iter()
+        .filter(|channel| channel.parent_id.is_none())
+    {
+        ordering.push(channel.id.clone());
+        ordering.extend(
+            channels
+                .iter()
+                .filter(|child| child.parent_id.as_ref() == Some(&channel.id))
+                .map(|child| child.id.clone()),
+        );
+    }
+    ordering
+}
+
+/// The membership of `user_id` in `guild_id`, not stored yet.
+fn new_member(guild_id: &str, user_id: &str) -> Member {
+    Member {
+        index: 0,
+        id: user_id.to_string(),
+        guild_id: guild_id.to_string(),
+        nick: None,
+        joined_at: Timestamp::now(),
+        premium_since: None,
+        deaf: Bool(false),
+        mute: Bool(false),
+        pending: Bool(false),
+        settings: Json(json!({
+            "guild_id": null,
+            "mute_config": null,
+            "mute_scheduled_events": false,
+            "flags": 0,
+            "hide_muted_channels": false,
+            "notify_highlights": 0,
+            "channel_overrides": {},
+            "message_notifications": 0,
+            "mobile_push": true,
+            "muted": false,
+            "suppress_everyone": false,
+            "suppress_roles": false,
+            "version": 0,
+        })),
+        last_message_id: None,
+        joined_by: None,
+        avatar: None,
+        banner: None,
+        bio: String::new(),
+        theme_colors: None,
+        pronouns: None,
+        communication_disabled_until: None,
+    }
+}
+
+/// Tell the guild about `member`, who just joined the guild that was just
+/// created, and send them the guild.
+async fn announce_member(
+    state: &AppState,
+    created: &CreatedGuild,
+    member: &Member,
+) -> Result<(), ApiError> {
+    let guild_id = &created.guild.id;
+    let user_id = &member.id;
+    let user = User::find(&state.db, user_id)
+        .await?
+        .ok_or_else(|| ApiError::api(StatusCode::NOT_FOUND, 10013, "Unknown User"))?;
+    let member = member_json(member, &user, &[]);
+    dispatch::to_guild(
+        guild_id,
+        "GUILD_MEMBER_ADD",
+        merge(member.clone(), json!({ "guild_id": guild_id })),
+    )
+    .await;
+
+    let mut data = guild_json(&created.guild, &created.roles, &created.channels);
+    data["member_count"] = json!(created.guild.member_count.unwrap_or(0));
+    data["joined_at"] = member["joined_at"].clone();
+    data["members"] = json!([member]);
+    data["guild_hashes"] = json!({});
+    data["guild_scheduled_events"] = json!([]);
+    data["presences"] = json!([]);
+    data["stage_instances"] = json!([]);
+    data["threads"] = json!([]);
+    data["embedded_activities"] = json!([]);
+    dispatch::to_user(user_id, "GUILD_CREATE", data).await;
+    Ok(())
+}
+
+/// `guild` as sent to clients, with its roles and channels.
+pub fn guild_json(guild: &Guild, roles: &[Role], channels: &[Channel]) -> Value {
+    let channels: Vec<Value> = channels
+        .iter()
+        .map(|channel| channel_json(channel, &guild.channel_ordering))
+        .collect();
+    merge(
+        json!(guild),
+        json!({
+            "roles": roles,
+            "channels": channels,
+            "emojis": [],
+            "stickers": [],
+        }),
+    )
+}
+
+/// `member` as sent to clients. `role_ids` excludes `@everyone`.
+pub fn member_json(member: &Member, user: &User, role_ids: &[String]) -> Value {
+    merge(
+        json!(member),
+        json!({
+            "roles": role_ids,
+            "user": MinimalPublicUser::from(user),
+        }),
+    )
+}
+
+/// `base` with the fields of `extra` added.
+pub(crate) fn merge(mut base: Value, extra: Value) -> Value {
+    if let (Some(base), Value::Object(extra)) = (base.as_object_mut(), extra) {
+        base.extend(extra);
+    }
+    base
+}
@@ -1,27 +1,42 @@
 //! API service entry point using Axum.
 
-use std::{net::SocketAddr, sync::Arc, thread::available_parallelism};
+use std::{net::SocketAddr, thread::available_parallelism};
 
 use anyhow::Result;
-use axum::{middleware::from_fn, serve};
-use config::Config;
+use axum::{
+    middleware::{from_fn, from_fn_with_state},
+    serve,
+};
+use config::{Config, ConfigHandle};
 use dotenvy::dotenv;
 use sentry_tower::{NewSentryLayer, SentryHttpLayer};
 use tokio::{net::TcpListener, signal};
 use tower::limit::ConcurrencyLimitLayer;
 
 use events::init_event;
-use util_db::{init_database, DbPool};
-
+use util::Email;
+use util_db::{init_config, init_database, watch_config, DbPool};
+
+mod captcha;
+mod cdn;
+mod channel;
+mod dispatch;
+mod error;
+mod guild;
+mod message;
 mod middleware;
 mod models;
+mod permissions;
 mod routes;
+mod session;
+#[cfg(test)]
+mod testing;
 
 /// Shared application state.
 #[derive(Clone)]
 pub struct AppState {
     pub db: DbPool,
-    pub config: Arc<Config>,
+    pub config: ConfigHandle,
 }
 
 /// Primary server structure.
@@ -30,16 +45,18 @@ pub struct SpacebarServer;
 impl SpacebarServer {
     /// Initialise configuration, database, events, sentry and HTTP routes.
     pub async fn start() -> Result<()> {
-        // Load configuration file
-        let config = Config::init().await;
-
         // Initialise database connection pool
         let database_url =
             std::env::var("DATABASE_URL").unwrap_or_else(|_| "sqlite::memory:".into());
         let db = init_database(&database_url).await?;
 
-        // Initialise event system
+        // Load configuration from the database
+        let config = init_config(&db).await?;
+
+        // Initialise event system and follow configuration changes
         init_event().await?;
+        watch_config(db.clone());
+        tokio::spawn(Email::follow(Config::handle()));
 
         // Configure Sentry if enabled
         let _sentry = if config.sentry.enabled {
@@ -53,14 +70,20 @@ impl SpacebarServer {
             None
         };
 
-        let state = AppState { db, config };
+        let state = AppState {
+            db,
+            config: Config::handle(),
+        };
 
         // Build routes and attach middleware
         let app = routes::create_router()
+            .layer(from_fn_with_state(
+                state.clone(),
+                middleware::authentication,
+            ))
             .with_state(state)
             .layer(from_fn(middleware::cors))
             .layer(from_fn(middleware::translation))
-            .layer(from_fn(middleware::authentication))
             .layer(ConcurrencyLimitLayer::new(100))
             .layer(NewSentryLayer::new_from_top())
             .layer(SentryHttpLayer::new().enable_transaction());
@@ -0,0 +1,217 @@
+//! Mentions in message content and the message objects sent to clients.
+
+use serde_json::{json, Value};
+use util::Permissions;
+use util_db::{
+    entities::{
+        Attachment, Member, Message, Reaction, Role, User, CHANNEL_DM, CHANNEL_GROUP_DM,
+        CHANNEL_GUILD_ANNOUNCEMENT, CHANNEL_GUILD_STAGE_VOICE, CHANNEL_GUILD_TEXT,
+        CHANNEL_GUILD_VOICE,
+    },
+    DbPool,
+};
+
+use crate::{
+    error::ApiError,
+    guild::{member_json, merge},
+    models::{message::AllowedMentions, user::MinimalPublicUser},
+};
+
+/// Channel types messages can be sent to.
+pub const TEXT_CHANNEL_TYPES: &[i32] = &[
+    CHANNEL_GUILD_TEXT,
+    CHANNEL_DM,
+    CHANNEL_GUILD_VOICE,
+    CHANNEL_GROUP_DM,
+    CHANNEL_GUILD_ANNOUNCEMENT,
+    CHANNEL_GUILD_STAGE_VOICE,
+];
+
+/// Mentions written in the content of a message, outside of code.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct ParsedMentions {
+    pub users: Vec<String>,
+    pub roles: Vec<String>,
+    /// Whether the content mentions `@everyone` or `@here`.
+    pub everyone: bool,
+}
+
+impl ParsedMentions {
+    pub fn parse(content: &str) -> Self {
+        let mut mentions = Self::default();
+        // Text between backticks is code, whose mentions do not count.
+        for text in content.split('`').step_by(2) {
+            mentions.everyone |= text.contains("@everyone") || text.contains("@here");
+            let mut rest = text;
+            while let Some(start) = rest.find("<@") {
+                rest = &rest[start + 2..];
+                let (ids, body) = match rest.strip_prefix('&') {
+                    Some(body) => (&mut mentions.roles, body),
+                    None => (&mut mentions.users, rest.strip_prefix('!').unwrap_or(rest)),
+                };
+                let Some(end) = body.find('>') else {
+                    break;
+                };
+                let id = &body[..end];
+                if !id.is_empty()
+                    && id.bytes().all(|b| b.is_ascii_digit())
+                    && !ids.iter().any(|other| other == id)
+                {
+                    ids.push(id.to_string());
+                }
+            }
+        }
+        mentions
+    }
+}
+
+/// Mentions of a message that notify, as stored with it.
+#[derive(Debug, Default)]
+pub struct Mentions {
+    pub users: Vec<String>,
+    pub roles: Vec<String>,
+    pub everyone: bool,
+}
+
+/// Resolve the mentions in `content` of a message sent with `granted`
+/// permissions to a channel of the guild `guild_id`, keeping those
+/// `allowed` and that refer to existing users and roles. `replied_author`
+/// is the author of the message replied to.
+pub async fn resolve_mentions(
+    db: &DbPool,
+    guild_id: Option<&str>,
+    content: &str,
+    allowed: Option<&AllowedMentions>,
+    granted: Permissions,
+    replied_author: Option<&str>,
+) -> Result<Mentions, ApiError> {
+    let parsed = ParsedMentions::parse(content);
+    let mut mentions = Mentions {
+        everyone: parsed.everyone
+            && granted.has(Permissions::MENTION_EVERYONE)
+            && allowed.is_none_or(AllowedMentions::allows_everyone),
+        ..Default::default()
+    };
+
+    let mut user_ids = Vec::with_capacity(parsed.users.len() + 1);
+    if let Some(replied) = replied_author {
+        if allowed.is_none_or(|allowed| allowed.replied_user) {
+            user_ids.push(replied.to_string());
+        }
+    }
+    user_ids.extend(parsed.users);
+    for id in user_ids {
+        let allowed = allowed.is_none_or(|allowed| allowed.allows_user(&id));
+        if allowed && !mentions.users.contains(&id) && User::find(db, &id).await?.is_some() {
+            mentions.users.push(id);
+        }
+    }
+
+    if let (Some(guild_id), false) = (guild_id, parsed.roles.is_empty()) {
+        let roles = Role::find_by_guild(db, guild_id).await?;
+        for id in parsed.roles {
+            let Some(role) = roles.iter().find(|role| role.id == id) else {
+                continue;
+            };
+            let mentionable = role.mentionable.0 || granted.has(Permissions::MANAGE_ROLES);
+            if mentionable && allowed.is_none_or(|allowed| allowed.allows_role(&id)) {
+                mentions.roles.push(id);
+            }
+        }
+    }
+    Ok(mentions)
+}
+
+/// `message` as sent to clients, with its author, attachments, mentions and
+/// reactions, and the message it replies to. Reactions are marked as the
+/// user `viewer`'s own, if any.
+pub async fn message_json(
+    db: &DbPool,
+    message: &Message,
+    viewer: Option<&str>,
+) -> Result<Value, ApiError> {
+    let mut data = message_fields(db, message, viewer).await?;
+    if let Some(reference) = &message.message_reference_id {
+        let channel_id = message.channel_id.as_deref().unwrap_or_default();
+        let referenced = match Message::find(db, channel_id, reference).await? {
+            Some(referenced) => message_fields(db, &referenced, viewer).await?,
+            None => Value::Null,
+        };
+        data["referenced_message"] = referenced;
+    }
+    Ok(data)
+}
+
+/// Emoji of a reaction as sent to clients.
+pub fn emoji_json(id: Option<&str>, name: &str, animated: bool) -> Value {
+    match id {
+        Some(id) => json!({ "id": id, "name": name, "animated": animated }),
+        None => json!({ "id": null, "name": name }),
+    }
+}
+
+async fn message_fields(
+    db: &DbPool,
+    message: &Message,
+    viewer: Option<&str>,
+) -> Result<Value, ApiError> {
+    let author = match &message.author_id {
+        Some(author_id) => User::find(db, author_id).await?,
+        None => None,
+    };
+    let (user_ids, role_ids) = Message::mentions(db, &message.id).await?;
+    let mut mentions = Vec::with_capacity(user_ids.len());
+    for id in &user_ids {
+        if let Some(user) = User::find(db, id).await? {
+            mentions.push(MinimalPublicUser::from(&user));
+        }
+    }
+    let attachments = Attachment::find_by_message(db, &message.id).await?;
+    let reactions: Vec<Value> =
+        Reaction::count_by_message(db, &message.id, viewer.unwrap_or_default())
+            .await?
+            .into_iter()
+            .map(|reaction| {
+                json!({
+                    "count": reaction.count,
+                    "count_details": { "burst": 0, "normal": reaction.count },
+                    "me": reaction.me > 0,
+                    "me_burst": false,
+                    "burst_colors": [],
+                    "emoji": emoji_json(
+                        reaction.emoji_id.as_deref(),
+                        &reaction.emoji_name,
+                        reaction.animated.0,
+                    ),
+                })
+            })
+            .collect();
+
+    let mut data = merge(
+        json!(message),
+        json!({
+            "author": author.as_ref().map(MinimalPublicUser::from),
+            "mentions": mentions,
+            "mention_roles": role_ids,
+            "mention_channels": [],
+            "attachments": attachments,
+            "reactions": reactions,
+            "sticker_items": [],
+        }),
+    );
+    if let (Some(guild_id), Some(author)) = (&message.guild_id, &author) {
+        if let Some(member) = Member::find(db, guild_id, &author.id).await? {
+            let role_ids: Vec<String> = Member::role_ids(db, member.index)
+                .await?
+                .into_iter()
+                .filter(|id| id != guild_id)
+                .collect();
+            let mut member = member_json(&member, author, &role_ids);
+            if let Some(member) = member.as_object_mut() {
+                member.remove("user");
+            }
+            data["member"] = member;
+        }
+    }
+    Ok(data)
+}
@@ -1,13 +1,32 @@
+use std::net::{IpAddr, SocketAddr};
+
 use axum::{
+    async_trait,
     body::Body,
-    http::{header, Method, Request, StatusCode},
+    extract::{ConnectInfo, FromRequestParts, State},
+    http::{header, request::Parts, Request, StatusCode},
     middleware::Next,
     response::{IntoResponse, Response},
 };
+use serde_json::Value;
+use util::Rights;
+use util_db::{check_token, TokenError};
+
+use crate::{error::ApiError, session, AppState};
 
-/// Routes that do not require authentication.
-const NO_AUTHORIZATION_ROUTES: &[(&str, &str)] =
-    &[("GET", "/ping"), ("POST", "/science"), ("POST", "/track")];
+/// Routes that do not require authentication. Paths ending in `/` cover
+/// everything below them.
+const NO_AUTHORIZATION_ROUTES: &[(&str, &str)] = &[
+    ("POST", "/auth/forgot"),
+    ("POST", "/auth/login"),
+    ("POST", "/auth/mfa/"),
+    ("POST", "/auth/register"),
+    ("POST", "/auth/reset"),
+    ("POST", "/auth/verify"),
+    ("GET", "/ping"),
+    ("POST", "/science"),
+    ("POST", "/track"),
+];
 
 /// Middleware that extracts the `Accept-Language` header and stores it
 /// in the request extensions for use by handlers.
@@ -33,27 +52,266 @@ pub async fn cors(req: Request<Body>, next: Next) -> Response {
     res
 }
 
-/// Simple bearer token authentication layer used for testing.
-pub async fn authentication(req: Request<Body>, next: Next) -> Response {
+/// Identity of the user a request was made by, stored in the request
+/// extensions by [`authentication`].
+#[derive(Debug, Clone)]
+pub struct Authenticated {
+    pub user_id: String,
+    /// Whether the token belongs to a bot account.
+    pub bot: bool,
+    pub rights: Rights,
+    /// Login session of the token, absent for tokens of the TypeScript
+    /// server.
+    pub session_id: Option<String>,
+}
+
+impl Authenticated {
+    /// Reject the request unless the user has every right in `rights`.
+    pub fn require_rights(&self, rights: Rights) -> Result<(), ApiError> {
+        if self.rights.has(rights) {
+            return Ok(());
+        }
+        Err(ApiError::api(
+            StatusCode::FORBIDDEN,
+            50013,
+            format!("You are missing the following rights {}", rights.names()),
+        ))
+    }
+
+    /// Reject requests made with a bot token, for endpoints that only make
+    /// sense for people.
+    pub fn require_user(&self) -> Result<(), ApiError> {
+        if !self.bot {
+            return Ok(());
+        }
+        Err(ApiError::api(
+            StatusCode::FORBIDDEN,
+            20001,
+            "Bots cannot use this endpoint",
+        ))
+    }
+}
+
+/// Resolve the user token in the `Authorization` header, rejecting the
+/// request if it is missing or invalid.
+pub async fn authentication(
+    State(state): State<AppState>,
+    req: Request<Body>,
+    next: Next,
+) -> Response {
     let method = req.method().as_str();
     let path = req.uri().path();
     if NO_AUTHORIZATION_ROUTES
         .iter()
-        .any(|(m, p)| *m == method && path.starts_with(p))
+        .any(|(m, p)| *m == method && (path == *p || (p.ends_with('/') && path.starts_with(p))))
     {
         return next.run(req).await;
     }
 
-    let authorized = req
+    let Some(token) = req
         .headers()
         .get(header::AUTHORIZATION)
         .and_then(|v| v.to_str().ok())
-        .map(|v| v == "Bearer operator")
-        .unwrap_or(false);
+    else {
+        return ApiError::http(StatusCode::UNAUTHORIZED, "Missing Authorization Header")
+            .into_response();
+    };
+
+    let secret = state.config.get().security.jwt_secret.clone();
+    match check_token(&state.db, token, &secret).await {
+        Ok((user, session)) => {
+            let (mut parts, body) = req.into_parts();
+            if let Some(session) = &session {
+                session::touch_session(&state, session, &mut parts).await;
+            }
+            let mut req = Request::from_parts(parts, body);
+            req.extensions_mut().insert(Authenticated {
+                user_id: user.id,
+                bot: *user.bot,
+                rights: Rights::from_bits_retain(user.rights as u64),
+                session_id: session.map(|session| session.session_id),
+            });
+            next.run(req).await
+        }
+        Err(TokenError::Database(e)) => ApiError::from(e).into_response(),
+        Err(e) => ApiError::http(StatusCode::BAD_REQUEST, e.to_string()).into_response(),
+    }
+}
+
+/// Address of the client, read from the header named by
+/// `security.forwarded_for` when the connection comes from one of
+/// `security.trusted_proxies`.
+#[derive(Debug, Clone, Copy)]
+pub struct ClientIp(pub IpAddr);
+
+#[async_trait]
+impl FromRequestParts<AppState> for ClientIp {
+    type Rejection = ApiError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &AppState) -> Result<Self, ApiError> {
+        let peer = parts
+            .extensions
+            .get::<ConnectInfo<SocketAddr>>()
+            .map(|ConnectInfo(addr)| addr.ip())
+            .ok_or_else(|| ApiError::Internal(anyhow::anyhow!("client address unavailable")))?;
+        let config = state.config.get();
+        let forwarded = config
+            .security
+            .forwarded_for
+            .as_deref()
+            .and_then(|name| parts.headers.get(name)?.to_str().ok());
+        Ok(Self(client_ip(
+            peer,
+            forwarded,
+            config.security.trusted_proxies.as_ref(),
+        )))
+    }
+}
+
+/// The rightmost address of the chain `peer` forwarded that did not come
+/// through a trusted proxy.
+///
+/// Like Express's `trust proxy`, `trusted` is `true` to trust every hop, a
+/// number of hops to trust, or addresses and CIDR ranges, as a list or
+/// separated by commas, where `loopback`, `linklocal` and `uniquelocal` name
+/// the private ranges.
+fn client_ip(peer: IpAddr, forwarded: Option<&str>, trusted: Option<&Value>) -> IpAddr {
+    let peer = peer.to_canonical();
+    // Proxies append the address they received from, so walk the list from
+    // the end, stopping at the first entry that is not an address.
+    let hops: Vec<IpAddr> = std::iter::once(peer)
+        .chain(
+            forwarded
+                .into_iter()
+                .flat_map(|value| value.rsplit(','))
+                .map_while(|entry| entry.trim().parse::<IpAddr>().ok())
+                .map(|ip| ip.to_canonical()),
+        )
+        .collect();
+    let last = hops.len() - 1;
+    hops.iter()
+        .enumerate()
+        .find(|&(hop, &ip)| hop == last || !is_trusted(trusted, hop, ip))
+        .map_or(peer, |(_, &ip)| ip)
+}
+
+/// Whether `ip`, `hop` proxies away from the server, is a trusted proxy.
+fn is_trusted(trusted: Option<&Value>, hop: usize, ip: IpAddr) -> bool {
+    match trusted {
+        Some(Value::Bool(all)) => *all,
+        Some(Value::Number(hops)) => hops.as_u64().is_some_and(|hops| (hop as u64) < hops),
+        Some(Value::String(list)) => list.split(',').any(|range| in_range(range.trim(), ip)),
+        Some(Value::Array(list)) => list
+            .iter()
+            .filter_map(Value::as_str)
+            .any(|range| in_range(range.trim(), ip)),
+        _ => false,
+    }
+}
+
+/// Whether `ip` is the address `range`, inside the CIDR range `range`, or in
+/// one of the named ranges Express accepts.
+fn in_range(range: &str, ip: IpAddr) -> bool {
+    let named: &[&str] = match range {
+        "loopback" => &["127.0.0.0/8", "::1/128"],
+        "linklocal" => &["169.254.0.0/16", "fe80::/10"],
+        "uniquelocal" => &["10.0.0.0/8", "172.16.0.0/12", "192.168.0.0/16", "fc00::/7"],
+        _ => &[],
+    };
+    if !named.is_empty() {
+        return named.iter().any(|range| in_range(range, ip));
+    }
+
+    let (address, bits) = match range.split_once('/') {
+        Some((address, bits)) => (address, bits.parse().ok()),
+        None => (range, None),
+    };
+    let Ok(address) = address.parse::<IpAddr>() else {
+        return false;
+    };
+    match (address.to_canonical(), ip) {
+        (IpAddr::V4(address), IpAddr::V4(ip)) => {
+            let bits = bits.unwrap_or(32).min(32);
+            let mask = u32::MAX.checked_shl(32 - bits).unwrap_or(0);
+            u32::from(address) & mask == u32::from(ip) & mask
+        }
+        (IpAddr::V6(address), IpAddr::V6(ip)) => {
+            let bits = bits.unwrap_or(128).min(128);
+            let mask = u128::MAX.checked_shl(128 - bits).unwrap_or(0);
+            u128::from(address) & mask == u128::from(ip) & mask
+        }
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn ip(address: &str) -> IpAddr {
+        address.parse().unwrap()
+    }
+
+    #[test]
+    fn forwarded_header_needs_a_trusted_peer() {
+        let forwarded = Some("203.0.113.7");
+        assert_eq!(
+            client_ip(ip("198.51.100.1"), forwarded, None),
+            ip("198.51.100.1")
+        );
+        let trusted = json!("10.0.0.0/8");
+        assert_eq!(
+            client_ip(ip("198.51.100.1"), forwarded, Some(&trusted)),
+            ip("198.51.100.1")
+        );
+        assert_eq!(
+            client_ip(ip("10.1.2.3"), forwarded, Some(&trusted)),
+            ip("203.0.113.7")
+        );
+    }
+
+    #[test]
+    fn rightmost_untrusted_hop_is_the_client() {
+        // The client made up the first entry; the trusted proxy appended the
+        // address it actually saw.
+        let forwarded = Some("1.2.3.4, 203.0.113.7, 10.0.0.2");
+        let trusted = json!(["loopback", "10.0.0.0/8"]);
+        assert_eq!(
+            client_ip(ip("127.0.0.1"), forwarded, Some(&trusted)),
+            ip("203.0.113.7")
+        );
+        assert_eq!(
+            client_ip(ip("127.0.0.1"), forwarded, Some(&json!(true))),
+            ip("1.2.3.4")
+        );
+        assert_eq!(
+            client_ip(ip("127.0.0.1"), forwarded, Some(&json!(2))),
+            ip("203.0.113.7")
+        );
+    }
+
+    #[test]
+    fn mapped_addresses_match_ipv4_ranges() {
+        let trusted = json!("127.0.0.1");
+        assert_eq!(
+            client_ip(ip("::ffff:127.0.0.1"), Some("203.0.113.7"), Some(&trusted)),
+            ip("203.0.113.7")
+        );
+    }
 
-    if authorized {
-        next.run(req).await
-    } else {
-        StatusCode::UNAUTHORIZED.into_response()
+    #[test]
+    fn bots_are_kept_out_of_user_endpoints() {
+        let mut auth = Authenticated {
+            user_id: "1".into(),
+            bot: false,
+            rights: Rights::empty(),
+            session_id: None,
+        };
+        assert!(auth.require_user().is_ok());
+        auth.bot = true;
+        let response = auth.require_user().unwrap_err().into_response();
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
     }
 }
@@ -0,0 +1,133 @@
+use serde::Deserialize;
+use util::Permissions;
+
+use super::fields;
+
+/// Permission overwrite in a channel body. The ID may be the placeholder
+/// of a role created along with a guild.
+#[derive(Deserialize, Debug, Clone)]
+pub struct OverwriteRequest {
+    #[serde(deserialize_with = "fields::id")]
+    pub id: Option<String>,
+    /// `0` for a role, `1` for a member.
+    #[serde(rename = "type")]
+    pub kind: i32,
+    #[serde(default)]
+    pub allow: Permissions,
+    #[serde(default)]
+    pub deny: Permissions,
+}
+
+/// Body of `POST /guilds/:guild_id/channels`, also used for the channels in
+/// the body of `POST /guilds`.
+#[derive(Deserialize, Debug, Clone, Default)]
+pub struct ChannelCreateRequest {
+    /// Placeholder other channels of a new guild refer to this one by.
+    #[serde(default, deserialize_with = "fields::id")]
+    pub id: Option<String>,
+    pub name: String,
+    #[serde(rename = "type", default)]
+    pub kind: i32,
+    pub topic: Option<String>,
+    pub icon: Option<String>,
+    pub bitrate: Option<i32>,
+    pub user_limit: Option<i32>,
+    pub rate_limit_per_user: Option<i32>,
+    pub position: Option<i32>,
+    pub permission_overwrites: Option<Vec<OverwriteRequest>>,
+    #[serde(default, deserialize_with = "fields::id")]
+    pub parent_id: Option<String>,
+    pub nsfw: Option<bool>,
+    pub default_auto_archive_duration: Option<i32>,
+    pub default_thread_rate_limit_per_user: Option<i32>,
+    pub video_quality_mode: Option<i32>,
+    pub flags: Option<i32>,
+}
+
+/// Body of `PATCH /channels/:channel_id`. Absent fields are left unchanged.
+#[derive(Deserialize, Debug, Clone, Default)]
+pub struct ChannelModifyRequest {
+    pub name: Option<String>,
+    #[serde(rename = "type")]
+    pub kind: Option<i32>,
+    #[serde(default, deserialize_with = "fields::nullable")]
+    pub topic: Option<Option<String>>,
+    #[serde(default, deserialize_with = "fields::nullable")]
+    pub icon: Option<Option<String>>,
+    pub bitrate: Option<i32>,
+    pub user_limit: Option<i32>,
+    pub rate_limit_per_user: Option<i32>,
+    pub position: Option<usize>,
+    pub permission_overwrites: Option<Vec<OverwriteRequest>>,
+    #[serde(default, deserialize_with = "fields::nullable")]
+    pub parent_id: Option<Option<String>>,
+    pub nsfw: Option<bool>,
+    pub default_auto_archive_duration: Option<i32>,
+    pub default_thread_rate_limit_per_user: Option<i32>,
+    pub video_quality_mode: Option<i32>,
+    pub flags: Option<i32>,
+}
+
+/// Entry in the body of `PATCH /guilds/:guild_id/channels`, moving a
+/// channel to another position or category.
+#[derive(Deserialize, Debug, Clone)]
+pub struct ChannelReorderRequest {
+    pub id: String,
+    pub position: Option<usize>,
+    /// `null` to move the channel out of its category.
+    #[serde(default, deserialize_with = "fields::nullable")]
+    pub parent_id: Option<Option<String>>,
+    /// Whether to copy the overwrites of the new category to the channel.
+    #[serde(default)]
+    pub lock_permissions: bool,
+}
+
+/// Body of `PUT /channels/:channel_id/permissions/:overwrite_id`.
+#[derive(Deserialize, Debug, Clone)]
+pub struct OverwriteEditRequest {
+    /// `0` for a role, `1` for a member.
+    #[serde(rename = "type")]
+    pub kind: i32,
+    #[serde(default)]
+    pub allow: Permissions,
+    #[serde(default)]
+    pub deny: Permissions,
+}
+
+/// Body of `POST /users/@me/channels`.
+#[derive(Deserialize, Debug, Clone, Default)]
+pub struct DmChannelCreateRequest {
+    /// The other users of the channel; a single one for a DM, several for
+    /// a group DM.
+    #[serde(default)]
+    pub recipients: Vec<String>,
+    pub name: Option<String>,
+}
+
+impl ChannelCreateRequest {
+    /// Validate the request according to length constraints.
+    pub fn validate(&self) -> Result<(), (&'static str, String)> {
+        validate_name(&self.name)
+    }
+}
+
+impl ChannelModifyRequest {
+    /// Validate the request according to length constraints.
+    pub fn validate(&self) -> Result<(), (&'static str, String)> {
+        match &self.name {
+            Some(name) => validate_name(name),
+            None => Ok(()),
+        }
+    }
+}
+
+fn validate_name(name: &str) -> Result<(), (&'static str, String)> {
+    let len = name.trim().chars().count();
+    if !(1..=100).contains(&len) {
+        return Err((
+            "name",
+            "name length must be between 1 and 100 characters".into(),
+        ));
+    }
+    Ok(())
+}
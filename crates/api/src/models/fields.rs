@@ -0,0 +1,27 @@
+//! Deserializers for fields whose shape differs from their Rust type.
+
+use serde::{Deserialize, Deserializer};
+use serde_json::Value;
+
+/// An ID sent as either a string or an integer, as clients do for the
+/// placeholder IDs in the body of `POST /guilds`.
+pub fn id<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<String>, D::Error> {
+    match Option::<Value>::deserialize(deserializer)? {
+        None | Some(Value::Null) => Ok(None),
+        Some(Value::String(id)) => Ok(Some(id)),
+        Some(Value::Number(id)) => Ok(Some(id.to_string())),
+        Some(other) => Err(serde::de::Error::custom(format!(
+            "expected an ID, found {other}"
+        ))),
+    }
+}
+
+/// A field that may be absent, `null` to clear the value, or set. Use with
+/// `#[serde(default)]` so that absent fields stay `None`.
+pub fn nullable<'de, D, T>(deserializer: D) -> Result<Option<Option<T>>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    Option::<T>::deserialize(deserializer).map(Some)
+}
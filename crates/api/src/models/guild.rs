@@ -0,0 +1,101 @@
+use serde::Deserialize;
+use util::Permissions;
+
+use super::{channel::ChannelCreateRequest, fields};
+
+/// Role in the body of `POST /guilds`. The first one configures the
+/// `@everyone` role.
+#[derive(Deserialize, Debug, Clone)]
+pub struct GuildCreateRole {
+    /// Placeholder channel overwrites refer to this role by.
+    #[serde(default, deserialize_with = "fields::id")]
+    pub id: Option<String>,
+    pub name: Option<String>,
+    pub color: Option<i32>,
+    pub hoist: Option<bool>,
+    pub mentionable: Option<bool>,
+    pub permissions: Option<Permissions>,
+    pub unicode_emoji: Option<String>,
+}
+
+/// Body of `POST /guilds`. Roles and channels, as in a guild template, are
+/// optional; without channels the guild starts with a text channel in a
+/// category.
+#[derive(Deserialize, Debug)]
+pub struct GuildCreateRequest {
+    pub name: String,
+    pub region: Option<String>,
+    #[serde(default)]
+    pub roles: Vec<GuildCreateRole>,
+    #[serde(default)]
+    pub channels: Vec<ChannelCreateRequest>,
+    /// Placeholder of one of `channels`.
+    #[serde(default, deserialize_with = "fields::id")]
+    pub system_channel_id: Option<String>,
+    /// Placeholder of one of `channels`.
+    #[serde(default, deserialize_with = "fields::id")]
+    pub afk_channel_id: Option<String>,
+    pub afk_timeout: Option<i32>,
+    pub verification_level: Option<i32>,
+    pub default_message_notifications: Option<i32>,
+    pub explicit_content_filter: Option<i32>,
+    pub system_channel_flags: Option<i32>,
+}
+
+/// Body of `PATCH /guilds/:guild_id`. Nullable fields are cleared by `null`.
+#[derive(Deserialize, Debug, Default)]
+pub struct GuildUpdateRequest {
+    pub name: Option<String>,
+    #[serde(default, deserialize_with = "fields::nullable")]
+    pub region: Option<Option<String>>,
+    #[serde(default, deserialize_with = "fields::nullable")]
+    pub description: Option<Option<String>>,
+    pub features: Option<Vec<String>>,
+    pub verification_level: Option<i32>,
+    pub default_message_notifications: Option<i32>,
+    pub explicit_content_filter: Option<i32>,
+    #[serde(default, deserialize_with = "fields::nullable")]
+    pub afk_channel_id: Option<Option<String>>,
+    pub afk_timeout: Option<i32>,
+    #[serde(default, deserialize_with = "fields::nullable")]
+    pub system_channel_id: Option<Option<String>>,
+    pub system_channel_flags: Option<i32>,
+    #[serde(default, deserialize_with = "fields::nullable")]
+    pub rules_channel_id: Option<Option<String>>,
+    #[serde(default, deserialize_with = "fields::nullable")]
+    pub public_updates_channel_id: Option<Option<String>>,
+    #[serde(default, deserialize_with = "fields::nullable")]
+    pub preferred_locale: Option<Option<String>>,
+    pub premium_progress_bar_enabled: Option<bool>,
+    pub widget_enabled: Option<bool>,
+    #[serde(default, deserialize_with = "fields::nullable")]
+    pub widget_channel_id: Option<Option<String>>,
+}
+
+impl GuildCreateRequest {
+    /// Validate the request according to length constraints.
+    pub fn validate(&self) -> Result<(), (&'static str, String)> {
+        validate_name(&self.name)
+    }
+}
+
+impl GuildUpdateRequest {
+    /// Validate the request according to length constraints.
+    pub fn validate(&self) -> Result<(), (&'static str, String)> {
+        match &self.name {
+            Some(name) => validate_name(name),
+            None => Ok(()),
+        }
+    }
+}
+
+fn validate_name(name: &str) -> Result<(), (&'static str, String)> {
+    let len = name.trim().chars().count();
+    if !(2..=100).contains(&len) {
+        return Err((
+            "name",
+            "name length must be between 2 and 100 characters".into(),
+        ));
+    }
+    Ok(())
+}
@@ -2,14 +2,13 @@ use serde::Deserialize;
 
 /// Schema representing a login request body.
 #[derive(Deserialize, Debug)]
-#[serde(rename_all = "camelCase")]
 pub struct LoginRequest {
     pub login: String,
     pub password: String,
     pub undelete: Option<bool>,
     pub captcha_key: Option<String>,
+    /// Where the client sent the user to log in from, such as `gift`.
     pub login_source: Option<String>,
-    pub gift_code_sku_id: Option<String>,
 }
 
 impl LoginRequest {
@@ -0,0 +1,95 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::fields;
+
+/// Query of `GET /channels/:channel_id/messages`. At most one of `around`,
+/// `before` and `after` is used, in that order.
+#[derive(Deserialize, Debug, Default)]
+pub struct MessageQuery {
+    pub around: Option<String>,
+    pub before: Option<String>,
+    pub after: Option<String>,
+    pub limit: Option<i64>,
+}
+
+/// Query of `GET /channels/:channel_id/messages/:message_id/reactions/:emoji`.
+#[derive(Deserialize, Debug, Default)]
+pub struct ReactionQuery {
+    /// Only list users with a greater ID.
+    pub after: Option<String>,
+    pub limit: Option<i64>,
+}
+
+/// Which of the mentions in a message notify, as sent by clients. Without
+/// it, every mention does.
+#[derive(Deserialize, Debug, Clone, Default)]
+pub struct AllowedMentions {
+    /// `"users"`, `"roles"` and `"everyone"`, allowing every mention of
+    /// that kind.
+    #[serde(default)]
+    pub parse: Vec<String>,
+    /// Users that may be mentioned when `parse` lacks `"users"`.
+    #[serde(default)]
+    pub users: Vec<String>,
+    /// Roles that may be mentioned when `parse` lacks `"roles"`.
+    #[serde(default)]
+    pub roles: Vec<String>,
+    /// Whether the author of the message replied to is mentioned.
+    #[serde(default)]
+    pub replied_user: bool,
+}
+
+impl AllowedMentions {
+    pub fn allows_user(&self, id: &str) -> bool {
+        self.parse.iter().any(|kind| kind == "users") || self.users.iter().any(|user| user == id)
+    }
+
+    pub fn allows_role(&self, id: &str) -> bool {
+        self.parse.iter().any(|kind| kind == "roles") || self.roles.iter().any(|role| role == id)
+    }
+
+    pub fn allows_everyone(&self) -> bool {
+        self.parse.iter().any(|kind| kind == "everyone")
+    }
+}
+
+/// The message a reply refers to.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct MessageReference {
+    pub message_id: Option<String>,
+    pub channel_id: Option<String>,
+    pub guild_id: Option<String>,
+}
+
+/// Body of `POST /channels/:channel_id/messages`, sent as JSON or as the
+/// `payload_json` field of a multipart body.
+#[derive(Deserialize, Debug, Default)]
+pub struct MessageCreateRequest {
+    pub content: Option<String>,
+    /// Client-generated ID deduplicating retried sends.
+    #[serde(default, deserialize_with = "fields::id")]
+    pub nonce: Option<String>,
+    #[serde(default)]
+    pub tts: bool,
+    #[serde(default)]
+    pub embeds: Vec<Value>,
+    /// Single embed, from before messages could have several.
+    pub embed: Option<Value>,
+    pub allowed_mentions: Option<AllowedMentions>,
+    pub message_reference: Option<MessageReference>,
+    pub components: Option<Value>,
+    pub flags: Option<i32>,
+}
+
+/// Body of `PATCH /channels/:channel_id/messages/:message_id`.
+#[derive(Deserialize, Debug, Default)]
+pub struct MessageEditRequest {
+    pub content: Option<String>,
+    pub embeds: Option<Vec<Value>>,
+    /// Single embed, from before messages could have several.
+    pub embed: Option<Value>,
+    pub allowed_mentions: Option<AllowedMentions>,
+    pub components: Option<Value>,
+    pub flags: Option<i32>,
+}
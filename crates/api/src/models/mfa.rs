@@ -0,0 +1,70 @@
+use serde::Deserialize;
+
+/// Body of `POST /users/@me/mfa/totp/enable`.
+#[derive(Deserialize, Debug)]
+pub struct TotpEnableRequest {
+    pub password: String,
+    pub code: Option<String>,
+    pub secret: Option<String>,
+}
+
+/// Body of `POST /users/@me/mfa/totp/disable`.
+#[derive(Deserialize, Debug)]
+pub struct TotpDisableRequest {
+    pub code: String,
+}
+
+/// Body of `POST /users/@me/mfa/codes`.
+#[derive(Deserialize, Debug)]
+pub struct MfaCodesRequest {
+    pub password: String,
+    pub regenerate: Option<bool>,
+}
+
+/// Body of `POST /users/@me/mfa/codes-verification`, which carries either
+/// the password or a nonce from `/auth/verify/view-backup-codes-challenge`.
+/// The emailed `key` clients also send is not checked, as this server sends
+/// none.
+#[derive(Deserialize, Debug)]
+pub struct CodesVerificationRequest {
+    pub password: Option<String>,
+    pub nonce: Option<String>,
+    pub regenerate: Option<bool>,
+}
+
+/// Body of `POST /auth/verify/view-backup-codes-challenge`.
+#[derive(Deserialize, Debug)]
+pub struct BackupCodesChallengeRequest {
+    pub password: String,
+}
+
+/// Body of `POST /auth/mfa/totp`.
+#[derive(Deserialize, Debug)]
+pub struct TotpRequest {
+    pub code: String,
+    pub ticket: String,
+}
+
+/// Body of `POST /users/@me/mfa/webauthn/credentials`, which first asks for
+/// a challenge and then submits the credential created for it.
+#[derive(Deserialize, Debug)]
+#[serde(untagged)]
+pub enum WebAuthnCredentialRequest {
+    Create {
+        ticket: String,
+        /// JSON of the `PublicKeyCredential` the browser created.
+        credential: String,
+        name: String,
+    },
+    Generate {
+        password: String,
+    },
+}
+
+/// Body of `POST /auth/mfa/webauthn`.
+#[derive(Deserialize, Debug)]
+pub struct WebAuthnRequest {
+    /// JSON of the `PublicKeyCredential` assertion.
+    pub code: String,
+    pub ticket: String,
+}
@@ -1,2 +1,10 @@
+pub mod channel;
+pub mod fields;
+pub mod guild;
 pub mod login;
+pub mod message;
+pub mod mfa;
+pub mod password;
+pub mod register;
 pub mod user;
+pub mod verify;
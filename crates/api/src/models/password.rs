@@ -0,0 +1,28 @@
+use serde::Deserialize;
+
+/// Body of `POST /auth/forgot`.
+#[derive(Deserialize, Debug)]
+pub struct ForgotPasswordRequest {
+    /// Email address or phone number of the account.
+    pub login: String,
+    pub captcha_key: Option<String>,
+}
+
+/// Body of `POST /auth/reset`.
+#[derive(Deserialize, Debug)]
+pub struct PasswordResetRequest {
+    pub password: String,
+    /// Token from the link emailed by `/auth/forgot`.
+    pub token: String,
+}
+
+impl PasswordResetRequest {
+    /// Validate the request according to length constraints.
+    pub fn validate(&self) -> Result<(), String> {
+        let len = self.password.chars().count();
+        if !(1..=72).contains(&len) {
+            return Err("password length must be between 1 and 72 characters".into());
+        }
+        Ok(())
+    }
+}
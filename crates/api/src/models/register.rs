@@ -0,0 +1,41 @@
+use serde::Deserialize;
+
+/// Schema representing a registration request body.
+#[derive(Deserialize, Debug)]
+pub struct RegisterRequest {
+    pub username: String,
+    pub password: Option<String>,
+    /// Whether the user agreed to the terms of service.
+    #[serde(default)]
+    pub consent: bool,
+    pub email: Option<String>,
+    pub fingerprint: Option<String>,
+    pub invite: Option<String>,
+    /// `YYYY-MM-DD`.
+    pub date_of_birth: Option<String>,
+    pub captcha_key: Option<String>,
+}
+
+impl RegisterRequest {
+    /// Validate the request according to length constraints, returning the
+    /// offending field.
+    pub fn validate(&self) -> Result<(), (&'static str, String)> {
+        let len = self.username.chars().count();
+        if !(2..=32).contains(&len) {
+            return Err((
+                "username",
+                "username length must be between 2 and 32 characters".into(),
+            ));
+        }
+        if let Some(password) = &self.password {
+            let len = password.chars().count();
+            if !(1..=72).contains(&len) {
+                return Err((
+                    "password",
+                    "password length must be between 1 and 72 characters".into(),
+                ));
+            }
+        }
+        Ok(())
+    }
+}
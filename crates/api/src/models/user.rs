@@ -1,14 +1,27 @@
 use serde::Serialize;
+use util_db::entities::User;
 
 /// Public user representation sent to clients.
 #[derive(Serialize, Debug)]
-#[serde(rename_all = "camelCase")]
 pub struct MinimalPublicUser {
     pub avatar: Option<String>,
     pub discriminator: String,
     pub id: String,
-    pub public_flags: i32,
+    pub public_flags: i64,
     pub username: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub badge_ids: Option<Vec<String>>,
 }
+
+impl From<&User> for MinimalPublicUser {
+    fn from(user: &User) -> Self {
+        Self {
+            avatar: user.avatar.clone(),
+            discriminator: user.discriminator.clone(),
+            id: user.id.clone(),
+            public_flags: user.public_flags,
+            username: user.username.clone(),
+            badge_ids: user.badge_ids.as_ref().map(|ids| ids.0.clone()),
+        }
+    }
+}
@@ -0,0 +1,9 @@
+use serde::Deserialize;
+
+/// Body of `POST /auth/verify`.
+#[derive(Deserialize, Debug)]
+pub struct VerifyEmailRequest {
+    /// Token from the emailed verification link.
+    pub token: String,
+    pub captcha_key: Option<String>,
+}
@@ -0,0 +1,113 @@
+//! Permissions of guild members, computed from their roles, and of users in
+//! channels.
+
+use axum::http::StatusCode;
+use util::Permissions;
+use util_db::{
+    entities::{Channel, Guild, Member, Recipient, Role},
+    DbPool,
+};
+
+use crate::error::ApiError;
+
+/// What a member may do in a guild.
+#[derive(Debug, Clone)]
+pub struct MemberPermissions {
+    pub guild_id: String,
+    pub user_id: String,
+    /// IDs of the roles of the member, without `@everyone`.
+    pub role_ids: Vec<String>,
+    /// Guild-wide permissions, before channel overwrites.
+    pub permissions: Permissions,
+}
+
+impl MemberPermissions {
+    /// Resolve the permissions of the user `user_id` in `guild`, or `None`
+    /// if they are not a member.
+    pub async fn of(db: &DbPool, guild: &Guild, user_id: &str) -> Result<Option<Self>, ApiError> {
+        let Some(member) = Member::find(db, &guild.id, user_id).await? else {
+            return Ok(None);
+        };
+        let role_ids: Vec<String> = Member::role_ids(db, member.index)
+            .await?
+            .into_iter()
+            .filter(|id| *id != guild.id)
+            .collect();
+        let roles = Role::find_by_guild(db, &guild.id).await?;
+        let everyone = roles
+            .iter()
+            .find(|role| role.id == guild.id)
+            .map(Role::permissions)
+            .unwrap_or_default();
+        let permissions = Permissions::base(
+            guild.owner_id.as_deref() == Some(user_id),
+            everyone,
+            roles
+                .iter()
+                .filter(|role| role_ids.contains(&role.id))
+                .map(Role::permissions),
+        );
+        Ok(Some(Self {
+            guild_id: guild.id.clone(),
+            user_id: user_id.to_string(),
+            role_ids,
+            permissions,
+        }))
+    }
+
+    /// Permissions of the member in `channel`, a channel of their guild.
+    pub fn in_channel(&self, channel: &Channel) -> Permissions {
+        let overwrites = channel
+            .permission_overwrites
+            .as_ref()
+            .map(|overwrites| overwrites.0.as_slice())
+            .unwrap_or_default();
+        self.permissions
+            .overwrite(&self.guild_id, &self.user_id, &self.role_ids, overwrites)
+    }
+
+    /// Reject the request unless the member has every permission in
+    /// `permissions`.
+    pub fn require(&self, permissions: Permissions) -> Result<(), ApiError> {
+        require(self.permissions, permissions)
+    }
+}
+
+/// Permissions of the user `user_id` in `channel`, or `None` if they are
+/// neither a member of its guild nor one of its recipients.
+pub async fn channel_permissions(
+    db: &DbPool,
+    channel: &Channel,
+    user_id: &str,
+) -> Result<Option<Permissions>, ApiError> {
+    let Some(guild_id) = &channel.guild_id else {
+        if Recipient::find(db, &channel.id, user_id).await?.is_none() {
+            return Ok(None);
+        }
+        if channel.owner_id.as_deref() == Some(user_id) {
+            return Ok(Some(Permissions::all()));
+        }
+        return Ok(Some(Permissions::dm_recipient()));
+    };
+    let Some(guild) = Guild::find(db, guild_id).await? else {
+        return Ok(None);
+    };
+    let member = MemberPermissions::of(db, &guild, user_id).await?;
+    Ok(member.map(|member| member.in_channel(channel)))
+}
+
+/// Reject the request unless `granted` includes every permission in
+/// `permissions`.
+pub fn require(granted: Permissions, permissions: Permissions) -> Result<(), ApiError> {
+    if granted.has(permissions) {
+        return Ok(());
+    }
+    Err(ApiError::api(
+        StatusCode::FORBIDDEN,
+        50013,
+        format!(
+            "You lack permissions to perform that action ({})",
+            permissions.names()
+        ),
+    ))
+}
@@ -0,0 +1,62 @@
+use axum::{extract::State, http::StatusCode, routing::post, Json, Router};
+use config::Config;
+use util::{generate_email_token, Email, EmailTokenPurpose, Template};
+use util_db::entities::User;
+
+use super::{action_url, user_vars};
+use crate::{
+    captcha::check_captcha, error::ApiError, middleware::ClientIp,
+    models::password::ForgotPasswordRequest, AppState,
+};
+
+/// Seconds an emailed password reset link stays valid.
+const RESET_LINK_LIFETIME: i64 = 60 * 60;
+
+/// Email a password reset link. The response is the same whether or not the
+/// account exists.
+async fn handler(
+    State(state): State<AppState>,
+    ClientIp(ip): ClientIp,
+    Json(payload): Json<ForgotPasswordRequest>,
+) -> Result<StatusCode, ApiError> {
+    let config = state.config.get();
+    check_captcha(
+        &config,
+        config.password_reset.require_captcha,
+        payload.captcha_key.as_deref(),
+        ip,
+    )
+    .await?;
+
+    let user = User::find_by_login(&state.db, &payload.login).await?;
+    if let Some(user) = user.filter(|user| user.email.is_some()) {
+        tokio::spawn(async move {
+            if let Err(e) = send_reset_email(&config, &user).await {
+                eprintln!(
+                    "[API] Failed to send password reset email to {}#{} ({}): {e}",
+                    user.username, user.discriminator, user.id
+                );
+            }
+        });
+    }
+    Ok(StatusCode::NO_CONTENT)
+}
+
+async fn send_reset_email(config: &Config, user: &User) -> anyhow::Result<()> {
+    let email = user.email.as_deref().unwrap_or_default();
+    let token = generate_email_token(
+        &user.id,
+        email,
+        EmailTokenPurpose::Reset,
+        RESET_LINK_LIFETIME,
+        &config.security.jwt_secret,
+    )?;
+    let url = action_url(config, "reset", &token);
+    let mut vars = user_vars(user);
+    vars.push(("actionUrl", &url));
+    Email::send_template(config, email, Template::PasswordResetRequest, &vars).await
+}
+
+pub fn router() -> Router<AppState> {
+    Router::new().route("/", post(handler))
+}
@@ -0,0 +1,112 @@
+use axum::{
+    extract::{Query, State},
+    response::{IntoResponse, Response},
+    routing::get,
+    Extension, Json, Router,
+};
+use chrono::{Duration, Utc};
+use rand::{distributions::Alphanumeric, Rng};
+use serde::Deserialize;
+use serde_json::json;
+use util::Rights;
+use util_db::entities::ValidRegistrationToken;
+
+use crate::{error::ApiError, middleware::Authenticated, AppState};
+
+/// Most tokens generated by a single request.
+const MAX_COUNT: usize = 1000;
+/// Longest token, the size of the `token` column.
+const MAX_LENGTH: usize = 255;
+
+#[derive(Deserialize)]
+struct GenerateQuery {
+    count: Option<usize>,
+    length: Option<usize>,
+    /// Milliseconds until the tokens expire, by default
+    /// `security.default_registration_token_expiration`.
+    expires_in: Option<u64>,
+    /// Return links to the registration page rather than bare tokens.
+    #[serde(default)]
+    include_url: bool,
+    /// Return one token per line instead of JSON.
+    #[serde(default)]
+    plain: bool,
+}
+
+async fn handler(
+    State(state): State<AppState>,
+    Extension(auth): Extension<Authenticated>,
+    Query(query): Query<GenerateQuery>,
+) -> Result<Response, ApiError> {
+    auth.require_rights(Rights::CREATE_REGISTRATION_TOKENS)?;
+    let count = query.count.unwrap_or(1);
+    if !(1..=MAX_COUNT).contains(&count) {
+        return Err(ApiError::field(
+            "count",
+            "NUMBER_TYPE_MAX",
+            format!("Must be between 1 and {MAX_COUNT}."),
+        ));
+    }
+    let length = query.length.unwrap_or(MAX_LENGTH);
+    if !(1..=MAX_LENGTH).contains(&length) {
+        return Err(ApiError::field(
+            "length",
+            "NUMBER_TYPE_MAX",
+            format!("Must be between 1 and {MAX_LENGTH}."),
+        ));
+    }
+
+    let config = state.config.get();
+    let expires_in = query
+        .expires_in
+        .unwrap_or(config.security.default_registration_token_expiration);
+    let expires_at = i64::try_from(expires_in)
+        .ok()
+        .and_then(|ms| Utc::now().checked_add_signed(Duration::milliseconds(ms)))
+        .ok_or_else(|| {
+            ApiError::field(
+                "expires_in",
+                "NUMBER_TYPE_MAX",
+                "Expiration is too far in the future.",
+            )
+        })?;
+
+    let tokens: Vec<String> = (0..count)
+        .map(|_| {
+            rand::thread_rng()
+                .sample_iter(Alphanumeric)
+                .take(length)
+                .map(char::from)
+                .collect()
+        })
+        .collect();
+    ValidRegistrationToken::insert_many(&state.db, &tokens, expires_at.into()).await?;
+    println!(
+        "[API] {} generated {count} registration token(s)",
+        auth.user_id
+    );
+
+    let tokens: Vec<String> = if query.include_url {
+        let front_page = config
+            .general
+            .front_page
+            .as_deref()
+            .unwrap_or("http://localhost:3001")
+            .trim_end_matches('/');
+        tokens
+            .into_iter()
+            .map(|token| format!("{front_page}/register?token={token}"))
+            .collect()
+    } else {
+        tokens
+    };
+    if query.plain {
+        Ok(tokens.join("\n").into_response())
+    } else {
+        Ok(Json(json!({ "tokens": tokens })).into_response())
+    }
+}
+
+pub fn router() -> Router<AppState> {
+    Router::new().route("/", get(handler))
+}
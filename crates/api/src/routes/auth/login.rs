@@ -0,0 +1,162 @@
+use axum::{extract::State, http::StatusCode, routing::post, Json, Router};
+use serde::Serialize;
+use serde_json::Value;
+use util::{Totp, WebAuthn};
+use util_db::entities::{SecurityKey, User, UserSettings};
+
+use crate::{
+    captcha::check_captcha,
+    error::ApiError,
+    middleware::ClientIp,
+    models::login::LoginRequest,
+    session::{start_session, ClientInfo},
+    AppState,
+};
+
+#[derive(Serialize)]
+#[serde(untagged)]
+enum LoginResponse {
+    Token {
+        token: String,
+        settings: Value,
+    },
+    /// The client has to finish the login with `/auth/mfa/*`.
+    Mfa {
+        ticket: String,
+        mfa: bool,
+        sms: bool,
+        token: Option<String>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        webauthn: Option<String>,
+    },
+}
+
+async fn handler(
+    State(state): State<AppState>,
+    ClientIp(ip): ClientIp,
+    mut client: ClientInfo,
+    Json(payload): Json<LoginRequest>,
+) -> Result<Json<LoginResponse>, ApiError> {
+    payload
+        .validate()
+        .map_err(|e| ApiError::field("password", "BASE_TYPE_BAD_LENGTH", e))?;
+    let config = state.config.get();
+
+    check_captcha(
+        &config,
+        config.login.require_captcha,
+        payload.captcha_key.as_deref(),
+        ip,
+    )
+    .await?;
+
+    let invalid = |code: &'static str, message: &str| {
+        ApiError::Fields(vec![
+            ("login", code, message.to_string()),
+            ("password", code, message.to_string()),
+        ])
+    };
+    let user = User::find_by_login(&state.db, &payload.login)
+        .await?
+        .ok_or_else(|| invalid("INVALID_LOGIN", "Login or password is invalid."))?;
+
+    let hash = user.data.hash.clone().unwrap_or_default();
+    let password = payload.password.clone();
+    let same_password = tokio::task::spawn_blocking(move || bcrypt::verify(password, &hash))
+        .await?
+        .unwrap_or(false);
+    if !same_password {
+        return Err(invalid("INVALID_PASSWORD", "Login or password is invalid."));
+    }
+
+    if config.login.require_verification && !*user.verified {
+        return Err(ApiError::field(
+            "login",
+            "ACCOUNT_LOGIN_VERIFICATION_EMAIL",
+            "Email verification is required, please check your email.",
+        ));
+    }
+
+    check_account(&state, &user, payload.undelete == Some(true)).await?;
+
+    // With security keys the ticket carries the WebAuthn challenge, and works
+    // for TOTP codes as well.
+    let challenge = if *user.webauthn_enabled {
+        let keys = SecurityKey::find_by_user(&state.db, &user.id).await?;
+        let credentials: Vec<String> = keys.into_iter().map(|key| key.public_key).collect();
+        WebAuthn::start_authentication(&config, &user.id, &credentials)?
+    } else {
+        None
+    };
+    // Keys the TypeScript server stored cannot be used here. Never let their
+    // owner in on the password alone; TOTP still works if it is enabled.
+    if *user.webauthn_enabled && challenge.is_none() && !*user.mfa_enabled {
+        return Err(ApiError::http(
+            StatusCode::BAD_REQUEST,
+            "None of the security keys of this account can be used on this server",
+        ));
+    }
+    if *user.mfa_enabled || *user.webauthn_enabled {
+        let (webauthn, ticket) = match challenge {
+            Some((challenge, ticket)) => (Some(challenge.to_string()), ticket),
+            None => (None, Totp::ticket(&config, &user.id)?),
+        };
+        User::set_totp_last_ticket(&state.db, &user.id, Some(&ticket)).await?;
+        return Ok(Json(LoginResponse::Mfa {
+            ticket,
+            mfa: true,
+            sms: false,
+            token: None,
+            webauthn,
+        }));
+    }
+
+    if let Some(source) = &payload.login_source {
+        client.info["login_source"] = Value::from(source.as_str());
+    }
+    let token = start_session(&state, &user.id, &client).await?;
+    Ok(Json(LoginResponse::Token {
+        token,
+        settings: settings_of(&state, &user).await?,
+    }))
+}
+
+/// Reject accounts that are disabled or scheduled for deletion, or restore
+/// them when `undelete` is set.
+pub(super) async fn check_account(
+    state: &AppState,
+    user: &User,
+    undelete: bool,
+) -> Result<(), ApiError> {
+    if undelete {
+        if *user.deleted || *user.disabled {
+            User::undelete(&state.db, &user.id).await?;
+        }
+    } else if *user.deleted {
+        return Err(ApiError::api(
+            StatusCode::BAD_REQUEST,
+            20011,
+            "This account is scheduled for deletion.",
+        ));
+    } else if *user.disabled {
+        return Err(ApiError::api(
+            StatusCode::BAD_REQUEST,
+            20013,
+            "This account is disabled.",
+        ));
+    }
+    Ok(())
+}
+
+/// The settings sent along with the token of a successful login.
+pub(super) async fn settings_of(state: &AppState, user: &User) -> Result<Value, ApiError> {
+    let settings = match user.settings_index {
+        Some(index) => UserSettings::find(&state.db, index).await?,
+        None => None,
+    };
+    Ok(serde_json::to_value(settings.unwrap_or_default())?)
+}
+
+pub fn router() -> Router<AppState> {
+    Router::new().route("/", post(handler))
+}
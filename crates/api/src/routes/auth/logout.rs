@@ -0,0 +1,20 @@
+use axum::{extract::State, http::StatusCode, routing::post, Extension, Router};
+
+use crate::{error::ApiError, middleware::Authenticated, session::revoke_session, AppState};
+
+/// Revoke the login session of the token the request was made with. The
+/// body, which official clients fill with push notification providers, is
+/// ignored.
+async fn handler(
+    State(state): State<AppState>,
+    Extension(auth): Extension<Authenticated>,
+) -> Result<StatusCode, ApiError> {
+    if let Some(session_id) = &auth.session_id {
+        revoke_session(&state, &auth.user_id, session_id).await?;
+    }
+    Ok(StatusCode::NO_CONTENT)
+}
+
+pub fn router() -> Router<AppState> {
+    Router::new().route("/", post(handler))
+}
@@ -0,0 +1,99 @@
+use axum::{extract::State, http::StatusCode, routing::post, Json, Router};
+use serde_json::{json, Value};
+use util::{Totp, WebAuthn};
+use util_db::entities::{BackupCode, SecurityKey, User};
+
+use super::login::{check_account, settings_of};
+use crate::{
+    error::ApiError,
+    models::mfa::{TotpRequest, WebAuthnRequest},
+    session::{start_session, ClientInfo},
+    AppState,
+};
+
+/// Finish a login with MFA using the ticket it returned and a TOTP or
+/// backup code.
+async fn totp(
+    State(state): State<AppState>,
+    client: ClientInfo,
+    Json(payload): Json<TotpRequest>,
+) -> Result<Json<Value>, ApiError> {
+    let invalid_code = || ApiError::api(StatusCode::BAD_REQUEST, 60008, "Invalid two-factor code");
+    // The TypeScript server clears used tickets to an empty string.
+    let user = match payload.ticket.as_str() {
+        "" => None,
+        ticket => User::find_by_totp_ticket(&state.db, ticket).await?,
+    };
+    let config = state.config.get();
+    let user = user
+        .filter(|user| *user.mfa_enabled && Totp::check_ticket(&config, &user.id, &payload.ticket))
+        .ok_or_else(|| ApiError::http(StatusCode::BAD_REQUEST, "Invalid ticket"))?;
+    // The account may have changed since the ticket was issued.
+    check_account(&state, &user, false).await?;
+
+    let secret = user.totp_secret.as_deref().unwrap_or_default();
+    let accepted = match Totp::check(secret, &payload.code) {
+        Some(step) => User::use_totp_step(&state.db, &user.id, step).await?,
+        None => BackupCode::consume(&state.db, &user.id, &payload.code).await?,
+    };
+    if !accepted {
+        return Err(invalid_code());
+    }
+
+    User::set_totp_last_ticket(&state.db, &user.id, None).await?;
+    let token = start_session(&state, &user.id, &client).await?;
+    Ok(Json(json!({
+        "token": token,
+        "settings": settings_of(&state, &user).await?,
+    })))
+}
+
+/// Finish a login with MFA using the ticket it returned and an assertion of
+/// one of the user's security keys.
+async fn webauthn(
+    State(state): State<AppState>,
+    client: ClientInfo,
+    Json(payload): Json<WebAuthnRequest>,
+) -> Result<Json<Value>, ApiError> {
+    let config = state.config.get();
+    let user = match payload.ticket.as_str() {
+        "" => None,
+        ticket => User::find_by_totp_ticket(&state.db, ticket).await?,
+    };
+    let user = user
+        .filter(|user| *user.webauthn_enabled)
+        .ok_or_else(|| ApiError::http(StatusCode::BAD_REQUEST, "Invalid ticket"))?;
+    check_account(&state, &user, false).await?;
+
+    let keys = SecurityKey::find_by_user(&state.db, &user.id).await?;
+    let credentials: Vec<String> = keys.into_iter().map(|key| key.public_key).collect();
+    let used = WebAuthn::finish_authentication(
+        &config,
+        &user.id,
+        &payload.ticket,
+        &payload.code,
+        &credentials,
+    )
+    .map_err(|_| ApiError::http(StatusCode::BAD_REQUEST, "Invalid security key"))?;
+    SecurityKey::update_credential(
+        &state.db,
+        &user.id,
+        &used.key_id,
+        &used.credential,
+        used.counter.into(),
+    )
+    .await?;
+
+    User::set_totp_last_ticket(&state.db, &user.id, None).await?;
+    let token = start_session(&state, &user.id, &client).await?;
+    Ok(Json(json!({
+        "token": token,
+        "settings": settings_of(&state, &user).await?,
+    })))
+}
+
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route("/totp", post(totp))
+        .route("/webauthn", post(webauthn))
+}
@@ -0,0 +1,73 @@
+use axum::Router;
+use config::Config;
+use util::{generate_email_token, Email, EmailTokenPurpose, Template};
+use util_db::entities::User;
+
+use crate::AppState;
+
+pub mod forgot;
+pub mod generate_registration_tokens;
+pub mod login;
+pub mod logout;
+pub mod mfa;
+pub mod register;
+pub mod reset;
+pub mod verify;
+
+/// Seconds an emailed verification link stays valid.
+const VERIFY_LINK_LIFETIME: i64 = 24 * 60 * 60;
+
+/// Routes under `/auth`.
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .nest("/forgot", forgot::router())
+        .nest(
+            "/generate-registration-tokens",
+            generate_registration_tokens::router(),
+        )
+        .nest("/login", login::router())
+        .nest("/logout", logout::router())
+        .nest("/mfa", mfa::router())
+        .nest("/register", register::router())
+        .nest("/reset", reset::router())
+        .nest("/verify", verify::router())
+}
+
+/// Link to the client page `page` carrying `token`, as sent by email.
+pub(super) fn action_url(config: &Config, page: &str, token: &str) -> String {
+    let front_page = config
+        .general
+        .front_page
+        .as_deref()
+        .unwrap_or("http://localhost:3001");
+    format!("{}/{page}#token={token}", front_page.trim_end_matches('/'))
+}
+
+/// Variables of the email templates describing `user`.
+pub(super) fn user_vars(user: &User) -> Vec<(&'static str, &str)> {
+    vec![
+        ("userUsername", user.username.as_str()),
+        ("userDiscriminator", user.discriminator.as_str()),
+        ("userId", user.id.as_str()),
+        ("userEmail", user.email.as_deref().unwrap_or_default()),
+    ]
+}
+
+/// Email `user` a link confirming its email address.
+pub(super) async fn send_verification_email(config: &Config, user: &User) -> anyhow::Result<()> {
+    let email = user
+        .email
+        .as_deref()
+        .ok_or_else(|| anyhow::anyhow!("user has no email address"))?;
+    let token = generate_email_token(
+        &user.id,
+        email,
+        EmailTokenPurpose::Verify,
+        VERIFY_LINK_LIFETIME,
+        &config.security.jwt_secret,
+    )?;
+    let url = action_url(config, "verify", &token);
+    let mut vars = user_vars(user);
+    vars.push(("actionUrl", &url));
+    Email::send_template(config, email, Template::VerifyEmail, &vars).await
+}
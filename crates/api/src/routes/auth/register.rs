@@ -0,0 +1,471 @@
+use axum::{
+    extract::State,
+    http::{header, HeaderMap, StatusCode},
+    routing::post,
+    Extension, Json, Router,
+};
+use chrono::{Duration, Months, NaiveDate, Utc};
+use config::Config;
+use rand::Rng;
+use serde::Serialize;
+use util::{IpAddress, Snowflake};
+use util_db::{
+    entities::{Invite, User, UserData, UserSettings, ValidRegistrationToken},
+    types::{Bool, Json as DbJson, SimpleArray, Timestamp},
+    Dialect,
+};
+
+use super::send_verification_email;
+use crate::{
+    captcha::check_captcha,
+    error::ApiError,
+    middleware::ClientIp,
+    models::register::RegisterRequest,
+    session::{start_session, ClientInfo},
+    AppState,
+};
+
+#[derive(Serialize)]
+struct RegisterResponse {
+    token: String,
+}
+
+async fn handler(
+    State(state): State<AppState>,
+    ClientIp(ip): ClientIp,
+    client: ClientInfo,
+    language: Option<Extension<String>>,
+    headers: HeaderMap,
+    Json(payload): Json<RegisterRequest>,
+) -> Result<Json<RegisterResponse>, ApiError> {
+    payload
+        .validate()
+        .map_err(|(field, e)| ApiError::field(field, "BASE_TYPE_BAD_LENGTH", e))?;
+    let config = state.config.get();
+    let register = &config.register;
+
+    if !payload.consent {
+        return Err(ApiError::field(
+            "consent",
+            "CONSENT_REQUIRED",
+            "You must agree to the Terms of Service and Privacy Policy.",
+        ));
+    }
+
+    // A valid registration token lifts every restriction on who may register.
+    let registration_token = registration_token(&headers);
+    let token_used = match &registration_token {
+        Some(token) => ValidRegistrationToken::is_valid(&state.db, token).await?,
+        None => false,
+    };
+
+    if !token_used && (register.disabled || !register.allow_new_registration) {
+        return Err(ApiError::field(
+            "email",
+            "REGISTRATION_DISABLED",
+            "New user registration is disabled",
+        ));
+    }
+
+    check_captcha(
+        &config,
+        register.require_captcha && !token_used,
+        payload.captcha_key.as_deref(),
+        ip,
+    )
+    .await?;
+
+    if !token_used && !register.allow_multiple_accounts {
+        if let Some(fingerprint) = &payload.fingerprint {
+            if User::fingerprint_exists(&state.db, fingerprint).await? {
+                return Err(email_already_registered());
+            }
+        }
+    }
+
+    if !token_used && register.block_proxies {
+        if let Some(api_key) = &config.security.ipdata_api_key {
+            match IpAddress::analyse(ip, api_key).await {
+                Ok(Some(data)) if IpAddress::is_proxy(&data) => {
+                    println!("[API] Proxy {ip} blocked from registration");
+                    return Err(ApiError::http(
+                        StatusCode::BAD_REQUEST,
+                        "Your IP is blocked from registration",
+                    ));
+                }
+                Ok(_) => {}
+                Err(e) => eprintln!("[API] Failed to look up {ip}: {e}"),
+            }
+        }
+    }
+
+    if let Some(email) = &payload.email {
+        if !is_email(email) {
+            return Err(ApiError::field(
+                "email",
+                "EMAIL_TYPE_INVALID_EMAIL",
+                "Not a well formed email address.",
+            ));
+        }
+        let domain = email.rsplit('@').next().unwrap_or_default().to_lowercase();
+        let listed = register
+            .email
+            .domains
+            .iter()
+            .any(|d| d.eq_ignore_ascii_case(&domain));
+        if (register.email.allowlist && !listed) || (register.email.blocklist && listed) {
+            return Err(ApiError::field(
+                "email",
+                "EMAIL_DOMAIN_NOT_ALLOWED",
+                "Email addresses from this domain are not allowed.",
+            ));
+        }
+        if User::email_exists(&state.db, email).await? {
+            return Err(email_already_registered());
+        }
+    } else if register.email.required {
+        return Err(required("email"));
+    }
+
+    check_date_of_birth(&config, payload.date_of_birth.as_deref())?;
+
+    let hash = match &payload.password {
+        Some(password) => {
+            check_password(&config, password)?;
+            let password = password.clone();
+            Some(tokio::task::spawn_blocking(move || bcrypt::hash(password, 12)).await??)
+        }
+        None if register.password.required => return Err(required("password")),
+        None => None,
+    };
+
+    let guests = register.guests_require_invite && payload.email.is_none();
+    match &payload.invite {
+        None if !token_used && (register.require_invite || guests) => {
+            return Err(ApiError::field(
+                "email",
+                "INVITE_ONLY",
+                "You must be invited to register on this instance.",
+            ));
+        }
+        // Used up along with the account below, joining the invite's guild
+        // is up to the client.
+        Some(code) => match Invite::find(&state.db, code).await? {
+            Some(invite) if !invite.is_expired() => {}
+            _ => return Err(unknown_invite()),
+        },
+        None => {}
+    }
+
+    let limit = &config.limits.absolute_rate.register;
+    if !token_used && limit.enabled {
+        let since = Utc::now() - Duration::milliseconds(i64::from(limit.window));
+        let count = User::count_created_since(&state.db, since.into()).await?;
+        if count >= i64::from(limit.limit) {
+            println!(
+                "[API] Global register rate limit exceeded for {ip}, {}, {}",
+                payload.username,
+                payload.invite.as_deref().unwrap_or("No invite given")
+            );
+            return Err(ApiError::field(
+                "email",
+                "TOO_MANY_REGISTRATIONS",
+                "Too many registrations, please try again later.",
+            ));
+        }
+    }
+
+    let max_username = config.limits.user.max_username as usize;
+    if payload.username.chars().count() > max_username {
+        return Err(ApiError::field(
+            "username",
+            "USERNAME_INVALID",
+            format!("Username must be less than {max_username} in length"),
+        ));
+    }
+
+    // Strip control characters such as backspace or newlines.
+    let username: String = payload
+        .username
+        .chars()
+        .filter(|c| !c.is_control())
+        .collect();
+    let lowered = username.to_lowercase();
+    let blocked = config
+        .user
+        .blocked_contains
+        .iter()
+        .any(|word| lowered.contains(&word.to_lowercase()))
+        || config
+            .user
+            .blocked_equals
+            .iter()
+            .any(|word| lowered == word.to_lowercase());
+    if blocked {
+        return Err(ApiError::field(
+            "username",
+            "USERNAME_INVALID",
+            "This username is not allowed.",
+        ));
+    }
+
+    let discriminator = generate_discriminator(&state, &config, &username)
+        .await?
+        .ok_or_else(|| {
+            ApiError::field(
+                "username",
+                "USERNAME_TOO_MANY_USERS",
+                "Too many users have this username, please try another.",
+            )
+        })?;
+
+    let locale = language
+        .and_then(|Extension(language)| {
+            let tag = language.split([',', ';']).next()?.trim().to_string();
+            (!tag.is_empty()).then_some(tag)
+        })
+        .map(|tag| if tag == "en" { "en-US".into() } else { tag })
+        .unwrap_or_else(|| "en-US".into());
+    let settings = UserSettings {
+        locale: Some(locale),
+        ..UserSettings::default()
+    };
+
+    let defaults = &config.defaults.user;
+    let now = Timestamp::now();
+    let mut user = User {
+        id: Snowflake::generate().to_string(),
+        username,
+        discriminator,
+        avatar: None,
+        accent_color: None,
+        banner: None,
+        theme_colors: None,
+        pronouns: None,
+        phone: None,
+        desktop: Bool(false),
+        mobile: Bool(false),
+        premium: Bool(defaults.premium),
+        premium_type: defaults.premium_type as i32,
+        bot: Bool(false),
+        bio: String::new(),
+        system: Bool(false),
+        nsfw_allowed: Bool(true),
+        mfa_enabled: Bool(false),
+        webauthn_enabled: Bool(false),
+        totp_secret: None,
+        totp_last_ticket: None,
+        created_at: now,
+        premium_since: defaults.premium.then_some(now),
+        verified: Bool(defaults.verified),
+        disabled: Bool(false),
+        deleted: Bool(false),
+        email: payload.email.clone(),
+        flags: 0,
+        public_flags: 0,
+        purchased_flags: 0,
+        premium_usage_flags: 0,
+        // Checked when the configuration is loaded.
+        rights: register
+            .default_rights
+            .parse()
+            .map_err(|e| anyhow::anyhow!("register.default_rights is invalid: {e}"))?,
+        data: DbJson(UserData {
+            valid_tokens_since: *now,
+            hash,
+        }),
+        fingerprints: SimpleArray(payload.fingerprint.iter().cloned().collect()),
+        extended_settings: "{}".into(),
+        badge_ids: None,
+        settings_index: None,
+    };
+
+    let dialect = Dialect::of(&state.db);
+    let mut tx = state.db.begin().await?;
+    if let Some(token) = registration_token.as_deref().filter(|_| token_used) {
+        if !ValidRegistrationToken::consume(&mut *tx, dialect, token).await? {
+            return Err(ApiError::http(
+                StatusCode::BAD_REQUEST,
+                "Invalid registration token",
+            ));
+        }
+    }
+    if let Some(code) = &payload.invite {
+        if !Invite::consume(&mut tx, dialect, code).await? {
+            return Err(unknown_invite());
+        }
+    }
+    user.settings_index = Some(settings.insert(&mut *tx, dialect).await?);
+    user.insert(&mut *tx, dialect).await?;
+    tx.commit().await?;
+    // The token itself is a credential and stays out of the logs.
+    if registration_token.is_some() && token_used {
+        println!("[API] Registration token used by {}", user.id);
+    }
+
+    let token = start_session(&state, &user.id, &client).await?;
+    // Without an email provider there is no way to deliver the link.
+    if user.email.is_some() && !*user.verified && config.email.provider.is_some() {
+        let config = config.clone();
+        tokio::spawn(async move {
+            if let Err(e) = send_verification_email(&config, &user).await {
+                eprintln!(
+                    "[API] Failed to send verification email to {}#{} ({}): {e}",
+                    user.username, user.discriminator, user.id
+                );
+            }
+        });
+    }
+    Ok(Json(RegisterResponse { token }))
+}
+
+/// The registration token in the `token` query parameter of the page the
+/// request was made from, as linked by `/auth/generate-registration-tokens`.
+fn registration_token(headers: &HeaderMap) -> Option<String> {
+    let referer = headers.get(header::REFERER)?.to_str().ok()?;
+    let (_, query) = referer.split_once('?')?;
+    query
+        .split(['&', '#'])
+        .find_map(|pair| pair.strip_prefix("token="))
+        .filter(|token| !token.is_empty())
+        .map(str::to_string)
+}
+
+fn unknown_invite() -> ApiError {
+    ApiError::api(StatusCode::NOT_FOUND, 10006, "Unknown Invite")
+}
+
+fn required(field: &'static str) -> ApiError {
+    ApiError::field(field, "BASE_TYPE_REQUIRED", "This field is required")
+}
+
+fn email_already_registered() -> ApiError {
+    ApiError::field(
+        "email",
+        "EMAIL_ALREADY_REGISTERED",
+        "Email is already registered",
+    )
+}
+
+fn is_email(email: &str) -> bool {
+    match email.split_once('@') {
+        Some((local, domain)) => {
+            !local.is_empty()
+                && !domain.contains('@')
+                && domain.split('.').count() > 1
+                && domain.split('.').all(|part| !part.is_empty())
+        }
+        None => false,
+    }
+}
+
+fn check_date_of_birth(config: &Config, date_of_birth: Option<&str>) -> Result<(), ApiError> {
+    let rules = &config.register.date_of_birth;
+    let Some(date_of_birth) = date_of_birth else {
+        return if rules.required {
+            Err(required("date_of_birth"))
+        } else {
+            Ok(())
+        };
+    };
+    let date = NaiveDate::parse_from_str(date_of_birth, "%Y-%m-%d").map_err(|_| {
+        ApiError::field(
+            "date_of_birth",
+            "DATE_TYPE_PARSE",
+            "Could not parse date. Should be ISO8601.",
+        )
+    })?;
+
+    let minimum = Utc::now()
+        .date_naive()
+        .checked_sub_months(Months::new(rules.minimum * 12))
+        .unwrap_or(NaiveDate::MIN);
+    // A later date of birth is a younger user.
+    if date > minimum {
+        return Err(ApiError::field(
+            "date_of_birth",
+            "DATE_OF_BIRTH_UNDERAGE",
+            format!("You need to be {} years or older", rules.minimum),
+        ));
+    }
+    Ok(())
+}
+
+pub(super) fn check_password(config: &Config, password: &str) -> Result<(), ApiError> {
+    let rules = &config.register.password;
+    let count = |f: fn(&char) -> bool| password.chars().filter(f).count() as u32;
+
+    let min_length = if rules.min_length > 0 {
+        rules.min_length
+    } else {
+        8
+    };
+    let checks = [
+        (
+            password.chars().count() as u32,
+            min_length,
+            "PASSWORD_REQUIREMENTS_MIN_LENGTH",
+            format!("Must be at least {min_length} characters long."),
+        ),
+        (
+            count(char::is_ascii_digit),
+            rules.min_numbers,
+            "PASSWORD_REQUIREMENTS_MIN_NUMBERS",
+            format!("Must contain at least {} numbers.", rules.min_numbers),
+        ),
+        (
+            count(char::is_ascii_uppercase),
+            rules.min_upper_case,
+            "PASSWORD_REQUIREMENTS_MIN_UPPERCASE",
+            format!(
+                "Must contain at least {} uppercase letters.",
+                rules.min_upper_case
+            ),
+        ),
+        (
+            count(|c| !c.is_alphanumeric() && !c.is_whitespace()),
+            rules.min_symbols,
+            "PASSWORD_REQUIREMENTS_MIN_SYMBOLS",
+            format!("Must contain at least {} symbols.", rules.min_symbols),
+        ),
+    ];
+    let errors: Vec<_> = checks
+        .into_iter()
+        .filter(|(actual, minimum, _, _)| actual < minimum)
+        .map(|(_, _, code, message)| ("password", code, message))
+        .collect();
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(ApiError::Fields(errors))
+    }
+}
+
+/// A free discriminator for `username`: the next one in sequence with
+/// `register.incrementing_discriminators`, otherwise a random one.
+async fn generate_discriminator(
+    state: &AppState,
+    config: &Config,
+    username: &str,
+) -> Result<Option<String>, ApiError> {
+    let taken = User::discriminators(&state.db, username).await?;
+    let discriminator = if config.register.incrementing_discriminators {
+        let highest = taken
+            .iter()
+            .filter_map(|d| d.parse::<u32>().ok())
+            .max()
+            .unwrap_or(0);
+        Some(highest + 1).filter(|d| *d < 10000)
+    } else {
+        // Like the TypeScript server, give up after a few collisions.
+        let mut rng = rand::thread_rng();
+        (0..5)
+            .map(|_| rng.gen_range(1..=9999u32))
+            .find(|d| !taken.contains(&format!("{d:04}")))
+    };
+    Ok(discriminator.map(|d| format!("{d:04}")))
+}
+
+pub fn router() -> Router<AppState> {
+    Router::new().route("/", post(handler))
+}
@@ -0,0 +1,74 @@
+use axum::{extract::State, routing::post, Json, Router};
+use serde_json::{json, Value};
+use util::{decode_email_token, Email, EmailTokenPurpose, Template};
+use util_db::entities::{User, UserData};
+
+use super::{register::check_password, user_vars};
+use crate::{
+    error::ApiError,
+    models::password::PasswordResetRequest,
+    session::{revoke_all_sessions, start_session, ClientInfo},
+    AppState,
+};
+
+/// Set a new password with the token of a reset link, signing out every
+/// session of the account.
+async fn handler(
+    State(state): State<AppState>,
+    client: ClientInfo,
+    Json(payload): Json<PasswordResetRequest>,
+) -> Result<Json<Value>, ApiError> {
+    payload
+        .validate()
+        .map_err(|e| ApiError::field("password", "BASE_TYPE_BAD_LENGTH", e))?;
+    let config = state.config.get();
+
+    let invalid_token = || {
+        ApiError::field(
+            "password",
+            "INVALID_TOKEN",
+            "Invalid or expired password reset token",
+        )
+    };
+    let claims = decode_email_token(
+        &payload.token,
+        EmailTokenPurpose::Reset,
+        &config.security.jwt_secret,
+    )
+    .map_err(|_| invalid_token())?;
+    // A link stops working once the address changes or it has been used,
+    // since a reset moves `valid_tokens_since` past its issue time.
+    let user = User::find(&state.db, &claims.id)
+        .await?
+        .filter(|user| user.email.as_deref() == Some(claims.email.as_str()))
+        .filter(|user| claims.iat * 1000 >= user.data.valid_tokens_since.timestamp_millis())
+        .ok_or_else(invalid_token)?;
+
+    check_password(&config, &payload.password)?;
+    let password = payload.password.clone();
+    let hash = tokio::task::spawn_blocking(move || bcrypt::hash(password, 12)).await??;
+    let data = UserData {
+        valid_tokens_since: chrono::Utc::now(),
+        hash: Some(hash),
+    };
+    User::set_data(&state.db, &user.id, &data).await?;
+
+    revoke_all_sessions(&state, &user.id, data.valid_tokens_since).await?;
+    let token = start_session(&state, &user.id, &client).await?;
+    tokio::spawn(async move {
+        let email = user.email.as_deref().unwrap_or_default();
+        let vars = user_vars(&user);
+        if let Err(e) = Email::send_template(&config, email, Template::PasswordChanged, &vars).await
+        {
+            eprintln!(
+                "[API] Failed to send password changed email to {}#{} ({}): {e}",
+                user.username, user.discriminator, user.id
+            );
+        }
+    });
+    Ok(Json(json!({ "token": token })))
+}
+
+pub fn router() -> Router<AppState> {
+    Router::new().route("/", post(handler))
+}
@@ -0,0 +1,109 @@
+use axum::{extract::State, http::StatusCode, routing::post, Extension, Json, Router};
+use serde_json::{json, Value};
+use util::{decode_email_token, EmailTokenPurpose, Rights, Totp};
+use util_db::entities::User;
+
+use super::send_verification_email;
+use crate::{
+    captcha::check_captcha,
+    error::ApiError,
+    middleware::{Authenticated, ClientIp},
+    models::{mfa::BackupCodesChallengeRequest, verify::VerifyEmailRequest},
+    routes::users::mfa::{check_password, current_user},
+    session::{start_session, ClientInfo},
+    AppState,
+};
+
+/// Confirm an email address with the token of a verification link.
+async fn verify(
+    State(state): State<AppState>,
+    ClientIp(ip): ClientIp,
+    client: ClientInfo,
+    Json(payload): Json<VerifyEmailRequest>,
+) -> Result<Json<Value>, ApiError> {
+    let config = state.config.get();
+    check_captcha(
+        &config,
+        config.register.require_captcha,
+        payload.captcha_key.as_deref(),
+        ip,
+    )
+    .await?;
+
+    let invalid_token = || ApiError::http(StatusCode::BAD_REQUEST, "Invalid Token");
+    let claims = decode_email_token(
+        &payload.token,
+        EmailTokenPurpose::Verify,
+        &config.security.jwt_secret,
+    )
+    .map_err(|_| invalid_token())?;
+    // Links sent to a previous address do not verify the current one.
+    let user = User::find(&state.db, &claims.id)
+        .await?
+        .filter(|user| user.email.as_deref() == Some(claims.email.as_str()))
+        .ok_or_else(invalid_token)?;
+
+    if !*user.verified {
+        User::set_verified(&state.db, &user.id).await?;
+    }
+    let token = start_session(&state, &user.id, &client).await?;
+    Ok(Json(json!({ "token": token, "user_id": user.id })))
+}
+
+/// Send the verification link of the current user again.
+async fn resend(
+    State(state): State<AppState>,
+    Extension(auth): Extension<Authenticated>,
+) -> Result<StatusCode, ApiError> {
+    auth.require_rights(Rights::RESEND_VERIFICATION_EMAIL)?;
+    let config = state.config.get();
+    let user = User::find(&state.db, &auth.user_id)
+        .await?
+        .ok_or_else(|| ApiError::api(StatusCode::NOT_FOUND, 10013, "Unknown User"))?;
+    if user.email.is_none() {
+        return Err(ApiError::http(
+            StatusCode::BAD_REQUEST,
+            "User does not have an email address",
+        ));
+    }
+
+    if let Err(e) = send_verification_email(&config, &user).await {
+        eprintln!(
+            "[API] Failed to send verification email to {}#{} ({}): {e}",
+            user.username, user.discriminator, user.id
+        );
+        return Err(ApiError::http(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "Failed to send verification email",
+        ));
+    }
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Check the password before a client shows or regenerates the backup
+/// codes, returning the nonces it then presents to
+/// `/users/@me/mfa/codes-verification`.
+async fn view_backup_codes_challenge(
+    State(state): State<AppState>,
+    Extension(auth): Extension<Authenticated>,
+    Json(payload): Json<BackupCodesChallengeRequest>,
+) -> Result<Json<Value>, ApiError> {
+    auth.require_user()?;
+    let user = current_user(&state, &auth).await?;
+    check_password(&user, &payload.password).await?;
+    let config = state.config.get();
+    Ok(Json(json!({
+        "nonce": Totp::backup_codes_nonce(&config, &user.id, false)?,
+        "regenerate_nonce": Totp::backup_codes_nonce(&config, &user.id, true)?,
+    })))
+}
+
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route("/", post(verify))
+        .route("/resend", post(resend))
+        .route(
+            "/view-backup-codes-challenge",
+            post(view_backup_codes_challenge),
+        )
+}
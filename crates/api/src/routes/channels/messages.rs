@@ -0,0 +1,839 @@
+use std::io;
+
+use axum::{
+    body::{to_bytes, Body, Bytes},
+    extract::{
+        multipart::MultipartError, DefaultBodyLimit, FromRequest, Multipart, Path, Query, Request,
+        State,
+    },
+    handler::Handler,
+    http::{header::CONTENT_TYPE, StatusCode},
+    middleware::{from_fn, Next},
+    response::Response,
+    routing::{get, patch},
+    Extension, Json, Router,
+};
+use config::Config;
+use futures_util::stream;
+use http_body_util::Limited;
+use serde_json::{json, Map, Value};
+use tokio::sync::mpsc;
+use util::{Permissions, Rights, Snowflake};
+use util_db::{
+    entities::{Attachment, Channel, Message, Recipient, MESSAGE_DEFAULT, MESSAGE_REPLY},
+    types::{Bool, Json as DbJson, Timestamp},
+    Dialect,
+};
+
+use crate::{
+    cdn::{delete_attachment, upload_attachment, Upload, UploadedFile},
+    channel::load_json,
+    dispatch,
+    error::ApiError,
+    message::{message_json, resolve_mentions, TEXT_CHANNEL_TYPES},
+    middleware::Authenticated,
+    models::message::{MessageCreateRequest, MessageEditRequest, MessageQuery},
+    permissions::require,
+    AppState,
+};
+
+use super::{find_channel, visible_permissions};
+
+/// Largest JSON body of a new message, room for the longest content
+/// `limits.message.maxCharacters` allows by default along with embeds.
+const MAX_JSON_BODY: usize = 8 * 1024 * 1024;
+
+/// Most files a message can have, as on Discord.
+const MAX_ATTACHMENTS: usize = 10;
+
+pub(crate) fn unknown_message() -> ApiError {
+    ApiError::api(StatusCode::NOT_FOUND, 10008, "Unknown Message")
+}
+
+/// The message `message_id` of the channel `channel_id`, or a 404.
+pub(crate) async fn find_message(
+    state: &AppState,
+    channel_id: &str,
+    message_id: &str,
+) -> Result<Message, ApiError> {
+    Message::find(&state.db, channel_id, message_id)
+        .await?
+        .ok_or_else(unknown_message)
+}
+
+fn empty_message() -> ApiError {
+    ApiError::api(
+        StatusCode::BAD_REQUEST,
+        50006,
+        "Cannot send an empty message",
+    )
+}
+
+fn non_text_channel() -> ApiError {
+    ApiError::api(
+        StatusCode::BAD_REQUEST,
+        50008,
+        "Cannot send messages in a non-text channel",
+    )
+}
+
+/// Check that `content` fits in the configured limits.
+fn check_content(state: &AppState, content: &str, tts: bool) -> Result<(), ApiError> {
+    let limits = &state.config.get().limits.message;
+    let max = if tts {
+        limits.max_tts_characters
+    } else {
+        limits.max_characters
+    };
+    if content.chars().count() > max as usize {
+        return Err(ApiError::field(
+            "content",
+            "BASE_TYPE_BAD_LENGTH",
+            format!("Must be {max} or fewer in length."),
+        ));
+    }
+    Ok(())
+}
+
+/// Messages of the channel, newest first.
+async fn list(
+    State(state): State<AppState>,
+    Extension(auth): Extension<Authenticated>,
+    Path(channel_id): Path<String>,
+    Query(query): Query<MessageQuery>,
+) -> Result<Json<Vec<Value>>, ApiError> {
+    let channel = find_channel(&state, &channel_id).await?;
+    if !TEXT_CHANNEL_TYPES.contains(&channel.kind) {
+        return Err(non_text_channel());
+    }
+    let limit = query.limit.unwrap_or(50);
+    if !(1..=100).contains(&limit) {
+        return Err(ApiError::http(
+            StatusCode::UNPROCESSABLE_ENTITY,
+            "limit must be between 1 and 100",
+        ));
+    }
+    let granted = visible_permissions(&state, &channel, &auth.user_id).await?;
+    if !granted.has(Permissions::READ_MESSAGE_HISTORY) {
+        return Ok(Json(Vec::new()));
+    }
+
+    let db = &state.db;
+    let messages = if let Some(around) = &query.around {
+        let older = Message::find_before(db, &channel_id, Some(around), limit / 2).await?;
+        let center = Message::find(db, &channel_id, around).await?;
+        let newer = Message::find_after(db, &channel_id, around, limit - limit / 2 - 1).await?;
+        newer.into_iter().rev().chain(center).chain(older).collect()
+    } else if let Some(after) = &query.after {
+        let mut newer = Message::find_after(db, &channel_id, after, limit).await?;
+        newer.reverse();
+        newer
+    } else {
+        Message::find_before(db, &channel_id, query.before.as_deref(), limit).await?
+    };
+
+    let mut data = Vec::with_capacity(messages.len());
+    for message in &messages {
+        data.push(message_json(db, message, Some(&auth.user_id)).await?);
+    }
+    Ok(Json(data))
+}
+
+/// Limit the body of a new message to [`MAX_ATTACHMENTS`] files of
+/// `limits.message.maxAttachmentSize` along with the message itself.
+async fn limit_body(request: Request, next: Next) -> Response {
+    let max_size = Config::get().limits.message.max_attachment_size;
+    let limit = usize::try_from(max_size)
+        .unwrap_or(usize::MAX)
+        .saturating_mul(MAX_ATTACHMENTS)
+        .saturating_add(MAX_JSON_BODY);
+    next.run(request.map(|body| Body::new(Limited::new(body, limit))))
+        .await
+}
+
+fn too_large(message: String) -> ApiError {
+    ApiError::api(StatusCode::PAYLOAD_TOO_LARGE, 40005, message)
+}
+
+fn multipart_error(error: MultipartError) -> ApiError {
+    if error.status() == StatusCode::PAYLOAD_TOO_LARGE {
+        return too_large("Request entity too large".into());
+    }
+    ApiError::http(StatusCode::BAD_REQUEST, error.body_text())
+}
+
+/// Read a new message sent as JSON, or as a multipart body holding the
+/// message in `payload_json` along with files to attach. Files are passed on
+/// to the CDN as they arrive; if reading fails, those already stored are
+/// deleted again.
+async fn read_message(
+    state: &AppState,
+    channel_id: &str,
+    granted: Permissions,
+    request: Request,
+) -> Result<(MessageCreateRequest, Vec<UploadedFile>), ApiError> {
+    let multipart = request
+        .headers()
+        .get(CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.starts_with("multipart/form-data"));
+    let bad_request = |message: String| ApiError::http(StatusCode::BAD_REQUEST, message);
+
+    if !multipart {
+        let body = to_bytes(request.into_body(), MAX_JSON_BODY)
+            .await
+            .map_err(|e| bad_request(e.to_string()))?;
+        let payload = serde_json::from_slice(&body).map_err(|e| bad_request(e.to_string()))?;
+        return Ok((payload, Vec::new()));
+    }
+
+    let multipart = Multipart::from_request(request, state)
+        .await
+        .map_err(|e| bad_request(e.body_text()))?;
+    let mut files = Vec::new();
+    match read_multipart(state, channel_id, granted, multipart, &mut files).await {
+        Ok(payload) => Ok((payload, files)),
+        Err(e) => {
+            discard(state, &files).await;
+            Err(e)
+        }
+    }
+}
+
+/// Read the fields of a multipart message, adding the files stored on the
+/// CDN to `files`.
+async fn read_multipart(
+    state: &AppState,
+    channel_id: &str,
+    granted: Permissions,
+    mut multipart: Multipart,
+    files: &mut Vec<UploadedFile>,
+) -> Result<MessageCreateRequest, ApiError> {
+    let bad_request = |message: String| ApiError::http(StatusCode::BAD_REQUEST, message);
+    let config = state.config.get();
+    let max_size = config.limits.message.max_attachment_size;
+    let mut fields = Map::new();
+    while let Some(mut field) = multipart.next_field().await.map_err(multipart_error)? {
+        let name = field.name().unwrap_or_default().to_string();
+        let Some(filename) = field.file_name().map(str::to_string) else {
+            let text = field.text().await.map_err(multipart_error)?;
+            if name == "payload_json" {
+                let payload: Map<String, Value> =
+                    serde_json::from_str(&text).map_err(|e| bad_request(e.to_string()))?;
+                fields.extend(payload);
+            } else {
+                fields.insert(name, Value::String(text));
+            }
+            continue;
+        };
+
+        require(granted, Permissions::ATTACH_FILES)?;
+        if files.len() == MAX_ATTACHMENTS {
+            return Err(ApiError::field(
+                "files",
+                "BASE_TYPE_MAX_LENGTH",
+                format!("Must be {MAX_ATTACHMENTS} or fewer in length."),
+            ));
+        }
+
+        // The file is read in step with the upload, which fails if reading
+        // stops early.
+        let (sender, receiver) = mpsc::channel::<io::Result<Bytes>>(4);
+        let chunks = stream::unfold(receiver, |mut receiver| async move {
+            receiver.recv().await.map(|chunk| (chunk, receiver))
+        });
+        let upload = Upload {
+            filename,
+            content_type: field.content_type().map(str::to_string),
+            body: reqwest::Body::wrap_stream(chunks),
+        };
+        let read = async move {
+            let mut size = 0;
+            let result = loop {
+                let chunk = match field.chunk().await {
+                    Ok(Some(chunk)) => chunk,
+                    Ok(None) => break Ok(()),
+                    Err(e) => break Err(multipart_error(e)),
+                };
+                size += chunk.len() as u64;
+                if size > max_size {
+                    break Err(too_large(format!(
+                        "Request entity too large (max {max_size} bytes per file)"
+                    )));
+                }
+                // The upload already failed and tells why.
+                if sender.send(Ok(chunk)).await.is_err() {
+                    break Ok(());
+                }
+            };
+            if result.is_err() {
+                let _ = sender.send(Err(io::Error::other("upload aborted"))).await;
+            }
+            result
+        };
+        let (read, uploaded) = tokio::join!(read, upload_attachment(&config, channel_id, upload));
+        read?;
+        files.push(uploaded.map_err(|e| bad_request(e.to_string()))?);
+    }
+    serde_json::from_value(Value::Object(fields)).map_err(|e| bad_request(e.to_string()))
+}
+
+/// Delete `files` from the CDN, as their message is not stored.
+async fn discard(state: &AppState, files: &[UploadedFile]) {
+    let config = state.config.get();
+    for file in files {
+        if let Err(e) = delete_attachment(&config, file).await {
+            eprintln!("[API] Failed to delete attachment {}: {e}", file.path);
+        }
+    }
+}
+
+/// What [`store_message`] did with a new message.
+enum Stored {
+    /// The message was stored.
+    New(Message),
+    /// The author already sent a message with the same nonce, returned
+    /// instead.
+    Existing(Message),
+}
+
+async fn create(
+    State(state): State<AppState>,
+    Extension(auth): Extension<Authenticated>,
+    Path(channel_id): Path<String>,
+    request: Request,
+) -> Result<Json<Value>, ApiError> {
+    auth.require_rights(Rights::SEND_MESSAGES)?;
+    let channel = find_channel(&state, &channel_id).await?;
+    if !TEXT_CHANNEL_TYPES.contains(&channel.kind) {
+        return Err(non_text_channel());
+    }
+    let granted = visible_permissions(&state, &channel, &auth.user_id).await?;
+    require(granted, Permissions::SEND_MESSAGES)?;
+    let (payload, files) = read_message(&state, &channel_id, granted, request).await?;
+
+    let stored = store_message(&state, &auth, &channel, granted, payload, &files).await;
+    let message = match stored {
+        Ok(Stored::New(message)) => message,
+        Ok(Stored::Existing(existing)) => {
+            discard(&state, &files).await;
+            return Ok(Json(
+                message_json(&state.db, &existing, Some(&auth.user_id)).await?,
+            ));
+        }
+        Err(e) => {
+            discard(&state, &files).await;
+            return Err(e);
+        }
+    };
+
+    // Closed DMs show up again for their recipients with a new message.
+    if channel.guild_id.is_none() {
+        for recipient in Recipient::find_by_channel(&state.db, &channel_id).await? {
+            if recipient.closed.0 {
+                Recipient::set_closed(&state.db, &channel_id, &recipient.user_id, false).await?;
+                let data = load_json(&state.db, &channel).await?;
+                dispatch::to_user(&recipient.user_id, "CHANNEL_CREATE", data).await;
+            }
+        }
+    }
+
+    // A new message has no reactions to tell apart per user.
+    let data = message_json(&state.db, &message, None).await?;
+    dispatch::to_channel(&channel, "MESSAGE_CREATE", data.clone()).await;
+    Ok(Json(data))
+}
+
+/// Check a new message and store it along with `files`, unless its nonce
+/// was already used.
+async fn store_message(
+    state: &AppState,
+    auth: &Authenticated,
+    channel: &Channel,
+    granted: Permissions,
+    payload: MessageCreateRequest,
+    files: &[UploadedFile],
+) -> Result<Stored, ApiError> {
+    if let Some(nonce) = &payload.nonce {
+        let existing = Message::find_by_nonce(&state.db, &channel.id, &auth.user_id, nonce).await?;
+        if let Some(existing) = existing {
+            return Ok(Stored::Existing(existing));
+        }
+    }
+    if payload.tts {
+        require(granted, Permissions::SEND_TTS_MESSAGES)?;
+    }
+
+    let content = payload
+        .content
+        .as_deref()
+        .map(str::trim)
+        .filter(|content| !content.is_empty());
+    if let Some(content) = content {
+        check_content(state, content, payload.tts)?;
+    }
+    let mut embeds = payload.embeds;
+    embeds.extend(payload.embed);
+    if content.is_none() && embeds.is_empty() && files.is_empty() && payload.components.is_none() {
+        return Err(empty_message());
+    }
+
+    let mut reply = None;
+    if let Some(mut reference) = payload.message_reference {
+        require(granted, Permissions::READ_MESSAGE_HISTORY)?;
+        let channel_id = reference
+            .channel_id
+            .get_or_insert_with(|| channel.id.clone());
+        if *channel_id != channel.id {
+            return Err(ApiError::http(
+                StatusCode::BAD_REQUEST,
+                "You can only reference messages from this channel",
+            ));
+        }
+        if reference.guild_id.is_none() {
+            reference.guild_id = channel.guild_id.clone();
+        }
+        // The message replied to may be missing, such as when backfilling
+        // history from another platform.
+        let replied = match &reference.message_id {
+            Some(message_id) => Message::find(&state.db, &channel.id, message_id).await?,
+            None => None,
+        };
+        reply = Some((reference, replied));
+    }
+
+    let replied_author = reply
+        .as_ref()
+        .and_then(|(_, replied)| replied.as_ref())
+        .and_then(|replied| replied.author_id.as_deref())
+        .filter(|author_id| *author_id != auth.user_id);
+    let mentions = resolve_mentions(
+        &state.db,
+        channel.guild_id.as_deref(),
+        content.unwrap_or_default(),
+        payload.allowed_mentions.as_ref(),
+        granted,
+        replied_author,
+    )
+    .await?;
+
+    let message = Message {
+        id: Snowflake::generate().to_string(),
+        channel_id: Some(channel.id.clone()),
+        guild_id: channel.guild_id.clone(),
+        author_id: Some(auth.user_id.clone()),
+        member_id: channel.guild_id.as_ref().map(|_| auth.user_id.clone()),
+        webhook_id: None,
+        application_id: None,
+        content: content.map(str::to_string),
+        timestamp: Timestamp::now(),
+        edited_timestamp: None,
+        tts: Some(Bool(payload.tts)),
+        mention_everyone: Some(Bool(mentions.everyone)),
+        embeds: DbJson(embeds),
+        reactions: DbJson(Vec::new()),
+        nonce: payload.nonce,
+        pinned: Some(Bool(false)),
+        kind: if reply.is_some() {
+            MESSAGE_REPLY
+        } else {
+            MESSAGE_DEFAULT
+        },
+        activity: None,
+        flags: payload.flags.unwrap_or(0),
+        message_reference_id: reply
+            .as_ref()
+            .and_then(|(reference, _)| reference.message_id.clone()),
+        message_reference: reply.map(|(reference, _)| DbJson(json!(reference))),
+        interaction: None,
+        components: payload.components.map(DbJson),
+        poll: None,
+        username: None,
+        avatar: None,
+    };
+
+    let dialect = Dialect::of(&state.db);
+    let mut tx = state.db.begin().await?;
+    if let Err(e) = message.insert(&mut *tx, dialect).await {
+        // A retry sent at the same time won the race for the nonce.
+        let retried = e
+            .as_database_error()
+            .is_some_and(|e| e.is_unique_violation());
+        if let (true, Some(nonce)) = (retried, &message.nonce) {
+            drop(tx);
+            let existing =
+                Message::find_by_nonce(&state.db, &channel.id, &auth.user_id, nonce).await?;
+            return Ok(Stored::Existing(existing.ok_or(e)?));
+        }
+        return Err(e.into());
+    }
+    for file in files {
+        let attachment = Attachment {
+            id: file.id.clone(),
+            filename: file.filename.clone(),
+            size: file.size as i64,
+            proxy_url: file.url.clone(),
+            url: file.url.clone(),
+            height: file.height.map(|height| height as i32),
+            width: file.width.map(|width| width as i32),
+            content_type: Some(file.content_type.clone()),
+            message_id: Some(message.id.clone()),
+        };
+        attachment.insert(&mut *tx, dialect).await?;
+    }
+    Message::set_mentions(
+        &mut tx,
+        dialect,
+        &message.id,
+        &mentions.users,
+        &mentions.roles,
+    )
+    .await?;
+    Channel::set_last_message_id(&mut *tx, dialect, &channel.id, &message.id).await?;
+    tx.commit().await?;
+    Ok(Stored::New(message))
+}
+
+async fn edit(
+    State(state): State<AppState>,
+    Extension(auth): Extension<Authenticated>,
+    Path((channel_id, message_id)): Path<(String, String)>,
+    Json(payload): Json<MessageEditRequest>,
+) -> Result<Json<Value>, ApiError> {
+    let channel = find_channel(&state, &channel_id).await?;
+    let granted = visible_permissions(&state, &channel, &auth.user_id).await?;
+    let mut message = find_message(&state, &channel_id, &message_id).await?;
+
+    // Others may only change the flags, such as to suppress embeds.
+    if message.author_id.as_deref() != Some(auth.user_id.as_str()) {
+        if payload.content.is_some() || payload.embeds.is_some() || payload.embed.is_some() {
+            return Err(ApiError::api(
+                StatusCode::FORBIDDEN,
+                50005,
+                "Cannot edit a message authored by another user",
+            ));
+        }
+        require(granted, Permissions::MANAGE_MESSAGES)?;
+    }
+
+    let mut mentions = None;
+    if let Some(content) = &payload.content {
+        let content = content.trim();
+        if content.is_empty() {
+            return Err(empty_message());
+        }
+        check_content(&state, content, false)?;
+        let replied_author = match &message.message_reference_id {
+            Some(reference) => Message::find(&state.db, &channel_id, reference)
+                .await?
+                .and_then(|replied| replied.author_id)
+                .filter(|author_id| *author_id != auth.user_id),
+            None => None,
+        };
+        let resolved = resolve_mentions(
+            &state.db,
+            channel.guild_id.as_deref(),
+            content,
+            payload.allowed_mentions.as_ref(),
+            granted,
+            replied_author.as_deref(),
+        )
+        .await?;
+        message.mention_everyone = Some(Bool(resolved.everyone));
+        message.content = Some(content.to_string());
+        mentions = Some(resolved);
+    }
+    if let Some(mut embeds) = payload.embeds {
+        embeds.extend(payload.embed);
+        message.embeds = DbJson(embeds);
+    } else if let Some(embed) = payload.embed {
+        message.embeds = DbJson(vec![embed]);
+    }
+    if let Some(components) = payload.components {
+        message.components = Some(DbJson(components));
+    }
+    if let Some(flags) = payload.flags {
+        message.flags = flags;
+    }
+    message.edited_timestamp = Some(Timestamp::now());
+
+    let dialect = Dialect::of(&state.db);
+    let mut tx = state.db.begin().await?;
+    message.update(&mut *tx, dialect).await?;
+    if let Some(mentions) = &mentions {
+        Message::set_mentions(
+            &mut tx,
+            dialect,
+            &message.id,
+            &mentions.users,
+            &mentions.roles,
+        )
+        .await?;
+    }
+    tx.commit().await?;
+
+    let data = message_json(&state.db, &message, None).await?;
+    dispatch::to_channel(&channel, "MESSAGE_UPDATE", data).await;
+    Ok(Json(
+        message_json(&state.db, &message, Some(&auth.user_id)).await?,
+    ))
+}
+
+async fn delete(
+    State(state): State<AppState>,
+    Extension(auth): Extension<Authenticated>,
+    Path((channel_id, message_id)): Path<(String, String)>,
+) -> Result<StatusCode, ApiError> {
+    let channel = find_channel(&state, &channel_id).await?;
+    let granted = visible_permissions(&state, &channel, &auth.user_id).await?;
+    let message = find_message(&state, &channel_id, &message_id).await?;
+    if message.author_id.as_deref() != Some(auth.user_id.as_str()) {
+        require(granted, Permissions::MANAGE_MESSAGES)?;
+    }
+
+    // Cascades to the attachments and mentions of the message.
+    Message::delete(&state.db, &message_id).await?;
+    dispatch::to_channel(
+        &channel,
+        "MESSAGE_DELETE",
+        json!({
+            "id": message_id,
+            "channel_id": channel_id,
+            "guild_id": channel.guild_id,
+        }),
+    )
+    .await;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Routes under `/channels/:channel_id/messages`.
+pub fn router() -> Router<AppState> {
+    Router::new()
+        // The limit depends on the configuration, see `limit_body`.
+        .route(
+            "/",
+            get(list).post(
+                create
+                    .layer(from_fn(limit_body))
+                    .layer(DefaultBodyLimit::disable()),
+            ),
+        )
+        .route("/:message_id", patch(edit).delete(delete))
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::http::Method;
+
+    use super::*;
+    use crate::testing::{guild, request, state, text_channel, user};
+
+    fn ids(messages: &Value) -> Vec<&str> {
+        messages
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|message| message["id"].as_str().unwrap())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn sends_messages_once_per_nonce() {
+        let state = state().await;
+        let owner = user(&state, "owner").await;
+        let channel_id = text_channel(&guild(&state, &owner).await);
+        let uri = format!("/channels/{channel_id}/messages");
+
+        let body = json!({ "content": "  hello  ", "nonce": "42" });
+        let (status, sent) = request(&state, &owner, Method::POST, &uri, Some(body.clone())).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(sent["content"], "hello");
+        assert_eq!(sent["author"]["id"], owner.user_id);
+        assert_eq!(sent["nonce"], "42");
+
+        // A retry gets the message back instead of a copy.
+        let (status, retried) = request(&state, &owner, Method::POST, &uri, Some(body)).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(retried["id"], sent["id"]);
+        let (_, history) = request(&state, &owner, Method::GET, &uri, None).await;
+        assert_eq!(ids(&history), [sent["id"].as_str().unwrap()]);
+
+        let body = json!({ "content": "   " });
+        let (status, error) = request(&state, &owner, Method::POST, &uri, Some(body)).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(error["code"], 50006);
+    }
+
+    #[tokio::test]
+    async fn concurrent_retries_store_one_message() {
+        let state = state().await;
+        let owner = user(&state, "owner").await;
+        let channel_id = text_channel(&guild(&state, &owner).await);
+        let uri = format!("/channels/{channel_id}/messages");
+        let body = json!({ "content": "hello", "nonce": "7" });
+        let (first, second) = tokio::join!(
+            request(&state, &owner, Method::POST, &uri, Some(body.clone())),
+            request(&state, &owner, Method::POST, &uri, Some(body)),
+        );
+        assert_eq!(first.0, StatusCode::OK);
+        assert_eq!(second.0, StatusCode::OK);
+        assert_eq!(first.1["id"], second.1["id"]);
+        let (_, history) = request(&state, &owner, Method::GET, &uri, None).await;
+        assert_eq!(ids(&history).len(), 1);
+
+        // What a retry losing the race inserts.
+        let mut copy = Message::find(&state.db, &channel_id, first.1["id"].as_str().unwrap())
+            .await
+            .unwrap()
+            .unwrap();
+        copy.id = Snowflake::generate().to_string();
+        let error = copy
+            .insert(&state.db, Dialect::of(&state.db))
+            .await
+            .unwrap_err();
+        assert!(error.as_database_error().unwrap().is_unique_violation());
+    }
+
+    #[tokio::test]
+    async fn edits_content_of_own_messages() {
+        let state = state().await;
+        let owner = user(&state, "owner").await;
+        let other = user(&state, "other").await;
+        let (_, dm) = request(
+            &state,
+            &owner,
+            Method::POST,
+            "/users/@me/channels",
+            Some(json!({ "recipients": [other.user_id] })),
+        )
+        .await;
+        let uri = format!("/channels/{}/messages", dm["id"].as_str().unwrap());
+        let (_, sent) = request(
+            &state,
+            &owner,
+            Method::POST,
+            &uri,
+            Some(json!({ "content": "hello" })),
+        )
+        .await;
+        let uri = format!("{uri}/{}", sent["id"].as_str().unwrap());
+
+        let body = json!({ "content": " hello again " });
+        let (status, edited) = request(&state, &owner, Method::PATCH, &uri, Some(body)).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(edited["content"], "hello again");
+        assert!(edited["edited_timestamp"].is_string());
+
+        let body = json!({ "content": "  " });
+        let (status, error) = request(&state, &owner, Method::PATCH, &uri, Some(body)).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(error["code"], 50006);
+
+        let body = json!({ "content": "not yours" });
+        let (status, error) = request(&state, &other, Method::PATCH, &uri, Some(body)).await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+        assert_eq!(error["code"], 50005);
+    }
+
+    #[tokio::test]
+    async fn deletes_messages() {
+        let state = state().await;
+        let owner = user(&state, "owner").await;
+        let other = user(&state, "other").await;
+        let (_, dm) = request(
+            &state,
+            &owner,
+            Method::POST,
+            "/users/@me/channels",
+            Some(json!({ "recipients": [other.user_id] })),
+        )
+        .await;
+        let uri = format!("/channels/{}/messages", dm["id"].as_str().unwrap());
+        let (_, sent) = request(
+            &state,
+            &owner,
+            Method::POST,
+            &uri,
+            Some(json!({ "content": "hello" })),
+        )
+        .await;
+        let message_uri = format!("{uri}/{}", sent["id"].as_str().unwrap());
+
+        // Deleting the messages of others takes `MANAGE_MESSAGES`.
+        let (status, _) = request(&state, &other, Method::DELETE, &message_uri, None).await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+
+        let (status, _) = request(&state, &owner, Method::DELETE, &message_uri, None).await;
+        assert_eq!(status, StatusCode::NO_CONTENT);
+        let (_, history) = request(&state, &owner, Method::GET, &uri, None).await;
+        assert!(ids(&history).is_empty());
+        let (status, error) = request(&state, &owner, Method::DELETE, &message_uri, None).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert_eq!(error["code"], 10008);
+    }
+
+    #[tokio::test]
+    async fn pages_through_history() {
+        let state = state().await;
+        let owner = user(&state, "owner").await;
+        let channel_id = text_channel(&guild(&state, &owner).await);
+        let uri = format!("/channels/{channel_id}/messages");
+        let mut sent = Vec::new();
+        for n in 0..5 {
+            let body = json!({ "content": format!("message {n}") });
+            let (_, message) = request(&state, &owner, Method::POST, &uri, Some(body)).await;
+            sent.push(message["id"].as_str().unwrap().to_string());
+        }
+        // The page fetched with `query`, as positions in `sent`.
+        let page = |query: String| {
+            let (state, owner, uri, sent) = (&state, &owner, &uri, &sent);
+            async move {
+                let (status, page) =
+                    request(state, owner, Method::GET, &format!("{uri}?{query}"), None).await;
+                assert_eq!(status, StatusCode::OK);
+                ids(&page)
+                    .into_iter()
+                    .map(|id| sent.iter().position(|sent| sent == id).unwrap())
+                    .collect::<Vec<_>>()
+            }
+        };
+
+        // Pages are always newest first.
+        assert_eq!(page("limit=2".into()).await, [4, 3]);
+        assert_eq!(page(format!("limit=2&before={}", sent[3])).await, [2, 1]);
+        assert_eq!(page(format!("limit=2&after={}", sent[0])).await, [2, 1]);
+        assert_eq!(page(format!("limit=3&around={}", sent[2])).await, [3, 2, 1]);
+
+        let (status, _) =
+            request(&state, &owner, Method::GET, &format!("{uri}?limit=0"), None).await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+    }
+
+    #[tokio::test]
+    async fn resolves_mentions() {
+        let state = state().await;
+        let owner = user(&state, "owner").await;
+        let other = user(&state, "other").await;
+        let channel_id = text_channel(&guild(&state, &owner).await);
+        let uri = format!("/channels/{channel_id}/messages");
+
+        let content = format!("<@{}> <@{}> @everyone", other.user_id, other.user_id);
+        let body = json!({ "content": content });
+        let (_, sent) = request(&state, &owner, Method::POST, &uri, Some(body)).await;
+        assert_eq!(sent["mentions"].as_array().unwrap().len(), 1);
+        assert_eq!(sent["mentions"][0]["id"], other.user_id);
+        assert_eq!(sent["mention_everyone"], true);
+
+        // Only the allowed mentions notify.
+        let body = json!({ "content": content, "allowed_mentions": { "parse": [] } });
+        let (_, sent) = request(&state, &owner, Method::POST, &uri, Some(body)).await;
+        assert_eq!(sent["mentions"], json!([]));
+        assert_eq!(sent["mention_everyone"], false);
+
+        // Edits resolve the mentions again.
+        let message_uri = format!("{uri}/{}", sent["id"].as_str().unwrap());
+        let body = json!({ "content": format!("<@{}>", other.user_id) });
+        let (_, edited) = request(&state, &owner, Method::PATCH, &message_uri, Some(body)).await;
+        assert_eq!(edited["mentions"][0]["id"], other.user_id);
+    }
+}
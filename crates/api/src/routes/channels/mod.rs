@@ -0,0 +1,294 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    routing::get,
+    Extension, Json, Router,
+};
+use serde_json::{json, Value};
+use util::{PermissionOverwrite, Permissions};
+use util_db::{
+    entities::{
+        Channel, Guild, Recipient, User, CHANNEL_DM, CHANNEL_GROUP_DM, CHANNEL_GUILD_ANNOUNCEMENT,
+        CHANNEL_GUILD_CATEGORY, CHANNEL_GUILD_TEXT,
+    },
+    types::{Bool, Json as DbJson},
+    Dialect,
+};
+
+use crate::{
+    channel::{channel_json, check_parent, check_topic, load_json, place},
+    dispatch,
+    error::ApiError,
+    middleware::Authenticated,
+    models::{channel::ChannelModifyRequest, user::MinimalPublicUser},
+    permissions::{channel_permissions, require},
+    routes::guilds::missing_access,
+    AppState,
+};
+
+pub mod messages;
+pub mod permissions;
+pub mod reactions;
+
+pub(crate) fn unknown_channel() -> ApiError {
+    ApiError::api(StatusCode::NOT_FOUND, 10003, "Unknown Channel")
+}
+
+pub(crate) fn dm_action() -> ApiError {
+    ApiError::api(
+        StatusCode::BAD_REQUEST,
+        50003,
+        "Cannot execute action on a DM channel",
+    )
+}
+
+/// The channel `channel_id`, or a 404.
+pub(crate) async fn find_channel(state: &AppState, channel_id: &str) -> Result<Channel, ApiError> {
+    Channel::find(&state.db, channel_id)
+        .await?
+        .ok_or_else(unknown_channel)
+}
+
+/// Permissions of the user `user_id` in `channel`, rejecting the request
+/// if they cannot see it.
+pub(crate) async fn visible_permissions(
+    state: &AppState,
+    channel: &Channel,
+    user_id: &str,
+) -> Result<Permissions, ApiError> {
+    channel_permissions(&state.db, channel, user_id)
+        .await?
+        .filter(|granted| granted.has(Permissions::VIEW_CHANNEL))
+        .ok_or_else(missing_access)
+}
+
+async fn get_channel(
+    State(state): State<AppState>,
+    Extension(auth): Extension<Authenticated>,
+    Path(channel_id): Path<String>,
+) -> Result<Json<Value>, ApiError> {
+    let channel = find_channel(&state, &channel_id).await?;
+    visible_permissions(&state, &channel, &auth.user_id).await?;
+    Ok(Json(load_json(&state.db, &channel).await?))
+}
+
+async fn modify(
+    State(state): State<AppState>,
+    Extension(auth): Extension<Authenticated>,
+    Path(channel_id): Path<String>,
+    Json(payload): Json<ChannelModifyRequest>,
+) -> Result<Json<Value>, ApiError> {
+    payload
+        .validate()
+        .map_err(|(field, e)| ApiError::field(field, "BASE_TYPE_BAD_LENGTH", e))?;
+    let mut channel = find_channel(&state, &channel_id).await?;
+    let granted = visible_permissions(&state, &channel, &auth.user_id).await?;
+    require(granted, Permissions::MANAGE_CHANNELS)?;
+
+    let Some(guild_id) = channel.guild_id.clone() else {
+        // Group DMs may only be renamed or given another icon.
+        if channel.kind != CHANNEL_GROUP_DM {
+            return Err(dm_action());
+        }
+        if let Some(name) = payload.name {
+            channel.name = Some(name.trim().to_string());
+        }
+        if let Some(icon) = payload.icon {
+            channel.icon = icon;
+        }
+        channel.update(&state.db, Dialect::of(&state.db)).await?;
+        let data = load_json(&state.db, &channel).await?;
+        dispatch::to_channel(&channel, "CHANNEL_UPDATE", data.clone()).await;
+        return Ok(Json(data));
+    };
+    let mut guild = Guild::find(&state.db, &guild_id)
+        .await?
+        .ok_or_else(unknown_channel)?;
+
+    if let Some(kind) = payload.kind.filter(|kind| *kind != channel.kind) {
+        // Only text channels can become announcement channels and back.
+        let convertible = [CHANNEL_GUILD_TEXT, CHANNEL_GUILD_ANNOUNCEMENT];
+        if !convertible.contains(&kind) || !convertible.contains(&channel.kind) {
+            return Err(ApiError::field(
+                "type",
+                "CHANNEL_TYPE_INVALID",
+                format!("Channel type {} cannot be changed to {kind}", channel.kind),
+            ));
+        }
+        channel.kind = kind;
+    }
+    if let Some(topic) = payload.topic {
+        if let Some(topic) = &topic {
+            check_topic(&state, topic)?;
+        }
+        channel.topic = topic;
+    }
+    if let Some(overwrites) = payload.permission_overwrites {
+        require(granted, Permissions::MANAGE_ROLES)?;
+        channel.permission_overwrites = Some(DbJson(
+            overwrites
+                .into_iter()
+                .filter_map(|overwrite| {
+                    Some(PermissionOverwrite {
+                        allow: overwrite.allow,
+                        deny: overwrite.deny,
+                        id: overwrite.id?,
+                        kind: overwrite.kind,
+                    })
+                })
+                .collect(),
+        ));
+    }
+    let moved = payload.position.is_some() || payload.parent_id.is_some();
+    if let Some(parent_id) = payload.parent_id {
+        if let Some(parent_id) = parent_id
+            .as_ref()
+            .filter(|parent_id| channel.parent_id.as_ref() != Some(*parent_id))
+        {
+            check_parent(&state, &guild_id, channel.kind, parent_id).await?;
+        }
+        channel.parent_id = parent_id;
+    }
+    if let Some(name) = payload.name {
+        channel.name = Some(name.trim().to_string());
+    }
+    if let Some(icon) = payload.icon {
+        channel.icon = icon;
+    }
+    if let Some(bitrate) = payload.bitrate {
+        channel.bitrate = Some(bitrate);
+    }
+    if let Some(user_limit) = payload.user_limit {
+        channel.user_limit = Some(user_limit);
+    }
+    if let Some(rate_limit) = payload.rate_limit_per_user {
+        channel.rate_limit_per_user = Some(rate_limit);
+    }
+    if let Some(nsfw) = payload.nsfw {
+        channel.nsfw = Bool(nsfw);
+    }
+    if let Some(duration) = payload.default_auto_archive_duration {
+        channel.default_auto_archive_duration = Some(duration);
+    }
+    if let Some(rate_limit) = payload.default_thread_rate_limit_per_user {
+        channel.default_thread_rate_limit_per_user = rate_limit;
+    }
+    if let Some(mode) = payload.video_quality_mode {
+        channel.video_quality_mode = Some(mode);
+    }
+    if let Some(flags) = payload.flags {
+        channel.flags = flags;
+    }
+
+    let dialect = Dialect::of(&state.db);
+    let mut tx = state.db.begin().await?;
+    channel.update(&mut *tx, dialect).await?;
+    if moved {
+        place(
+            &mut guild.channel_ordering.0,
+            &channel.id,
+            payload.position,
+            channel.parent_id.as_deref(),
+        );
+        Guild::set_channel_ordering(&mut *tx, dialect, &guild_id, &guild.channel_ordering).await?;
+    }
+    tx.commit().await?;
+
+    let data = channel_json(&channel, &guild.channel_ordering);
+    dispatch::to_channel(&channel, "CHANNEL_UPDATE", data.clone()).await;
+    Ok(Json(data))
+}
+
+/// Delete a guild channel, close a DM, or leave a group DM.
+async fn delete(
+    State(state): State<AppState>,
+    Extension(auth): Extension<Authenticated>,
+    Path(channel_id): Path<String>,
+) -> Result<Json<Value>, ApiError> {
+    let mut channel = find_channel(&state, &channel_id).await?;
+    let granted = visible_permissions(&state, &channel, &auth.user_id).await?;
+    let data = load_json(&state.db, &channel).await?;
+
+    match channel.kind {
+        CHANNEL_DM => {
+            // The other recipient keeps the channel, which reopens for the
+            // user with the next message.
+            Recipient::set_closed(&state.db, &channel_id, &auth.user_id, true).await?;
+            dispatch::to_user(&auth.user_id, "CHANNEL_DELETE", data.clone()).await;
+        }
+        CHANNEL_GROUP_DM => {
+            Recipient::delete(&state.db, &channel_id, &auth.user_id).await?;
+            let remaining = Recipient::find_by_channel(&state.db, &channel_id).await?;
+            match remaining.first() {
+                None => Channel::delete(&state.db, Dialect::of(&state.db), &channel_id).await?,
+                Some(next) => {
+                    if channel.owner_id.as_deref() == Some(auth.user_id.as_str()) {
+                        channel.owner_id = Some(next.user_id.clone());
+                        channel.update(&state.db, Dialect::of(&state.db)).await?;
+                        let data = load_json(&state.db, &channel).await?;
+                        dispatch::to_channel(&channel, "CHANNEL_UPDATE", data).await;
+                    }
+                    if let Some(user) = User::find(&state.db, &auth.user_id).await? {
+                        dispatch::to_channel(
+                            &channel,
+                            "CHANNEL_RECIPIENT_REMOVE",
+                            json!({
+                                "channel_id": channel_id,
+                                "user": MinimalPublicUser::from(&user),
+                            }),
+                        )
+                        .await;
+                    }
+                }
+            }
+            dispatch::to_user(&auth.user_id, "CHANNEL_DELETE", data.clone()).await;
+        }
+        _ => {
+            require(granted, Permissions::MANAGE_CHANNELS)?;
+            let guild_id = channel.guild_id.clone().ok_or_else(unknown_channel)?;
+            let mut guild = Guild::find(&state.db, &guild_id)
+                .await?
+                .ok_or_else(unknown_channel)?;
+            guild.channel_ordering.0.retain(|id| *id != channel_id);
+
+            // Channels of a deleted category stay, without a category.
+            let mut children = Vec::new();
+            if channel.kind == CHANNEL_GUILD_CATEGORY {
+                children = Channel::find_by_parent(&state.db, &channel_id).await?;
+            }
+            let dialect = Dialect::of(&state.db);
+            let mut tx = state.db.begin().await?;
+            for child in &mut children {
+                child.parent_id = None;
+                child.update(&mut *tx, dialect).await?;
+            }
+            Guild::set_channel_ordering(&mut *tx, dialect, &guild_id, &guild.channel_ordering)
+                .await?;
+            // Cascades to the messages, invites and webhooks of the channel.
+            Channel::delete(&mut *tx, dialect, &channel_id).await?;
+            tx.commit().await?;
+
+            for child in &children {
+                let data = channel_json(child, &guild.channel_ordering);
+                dispatch::to_channel(child, "CHANNEL_UPDATE", data).await;
+            }
+            dispatch::to_channel(&channel, "CHANNEL_DELETE", data.clone()).await;
+        }
+    }
+    Ok(Json(data))
+}
+
+/// Routes under `/channels`.
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route(
+            "/:channel_id",
+            get(get_channel).patch(modify).delete(delete),
+        )
+        .nest("/:channel_id/messages", messages::router())
+        .nest(
+            "/:channel_id/messages/:message_id/reactions",
+            reactions::router(),
+        )
+        .nest("/:channel_id/permissions", permissions::router())
+}
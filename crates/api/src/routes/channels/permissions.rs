@@ -0,0 +1,122 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    routing::put,
+    Extension, Json, Router,
+};
+use util::{PermissionOverwrite, Permissions, OVERWRITE_MEMBER, OVERWRITE_ROLE};
+use util_db::{
+    entities::{Channel, Member, Role},
+    types::Json as DbJson,
+    Dialect,
+};
+
+use crate::{
+    channel::load_json, dispatch, error::ApiError, middleware::Authenticated,
+    models::channel::OverwriteEditRequest, permissions::require, AppState,
+};
+
+use super::{dm_action, find_channel, visible_permissions};
+
+/// The guild channel `channel_id`, once checked that the current user may
+/// manage its overwrites.
+async fn managed_channel(
+    state: &AppState,
+    auth: &Authenticated,
+    channel_id: &str,
+) -> Result<(Channel, Permissions), ApiError> {
+    let channel = find_channel(state, channel_id).await?;
+    if channel.guild_id.is_none() {
+        return Err(dm_action());
+    }
+    let granted = visible_permissions(state, &channel, &auth.user_id).await?;
+    require(granted, Permissions::MANAGE_ROLES)?;
+    Ok((channel, granted))
+}
+
+/// Create or replace the overwrite of a role or member.
+async fn edit(
+    State(state): State<AppState>,
+    Extension(auth): Extension<Authenticated>,
+    Path((channel_id, overwrite_id)): Path<(String, String)>,
+    Json(payload): Json<OverwriteEditRequest>,
+) -> Result<StatusCode, ApiError> {
+    let (mut channel, granted) = managed_channel(&state, &auth, &channel_id).await?;
+    let guild_id = channel.guild_id.clone().unwrap_or_default();
+
+    match payload.kind {
+        OVERWRITE_ROLE => {
+            let roles = Role::find_by_guild(&state.db, &guild_id).await?;
+            if !roles.iter().any(|role| role.id == overwrite_id) {
+                return Err(ApiError::api(StatusCode::NOT_FOUND, 10011, "Unknown Role"));
+            }
+        }
+        OVERWRITE_MEMBER => {
+            if Member::find(&state.db, &guild_id, &overwrite_id)
+                .await?
+                .is_none()
+            {
+                return Err(ApiError::api(
+                    StatusCode::NOT_FOUND,
+                    10007,
+                    "Unknown Member",
+                ));
+            }
+        }
+        kind => {
+            return Err(ApiError::field(
+                "type",
+                "BASE_TYPE_CHOICES",
+                format!("Value must be one of ({OVERWRITE_ROLE}, {OVERWRITE_MEMBER}), got {kind}"),
+            ));
+        }
+    }
+    // Members may only grant or deny what they are allowed themselves.
+    require(granted, payload.allow | payload.deny)?;
+
+    let mut overwrites = channel
+        .permission_overwrites
+        .take()
+        .map(|overwrites| overwrites.0)
+        .unwrap_or_default();
+    overwrites.retain(|overwrite| overwrite.id != overwrite_id);
+    overwrites.push(PermissionOverwrite {
+        allow: payload.allow,
+        deny: payload.deny,
+        id: overwrite_id,
+        kind: payload.kind,
+    });
+    channel.permission_overwrites = Some(DbJson(overwrites));
+    channel.update(&state.db, Dialect::of(&state.db)).await?;
+
+    let data = load_json(&state.db, &channel).await?;
+    dispatch::to_channel(&channel, "CHANNEL_UPDATE", data).await;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Remove the overwrite of a role or member.
+async fn delete(
+    State(state): State<AppState>,
+    Extension(auth): Extension<Authenticated>,
+    Path((channel_id, overwrite_id)): Path<(String, String)>,
+) -> Result<StatusCode, ApiError> {
+    let (mut channel, _) = managed_channel(&state, &auth, &channel_id).await?;
+    let Some(DbJson(overwrites)) = &mut channel.permission_overwrites else {
+        return Ok(StatusCode::NO_CONTENT);
+    };
+    let count = overwrites.len();
+    overwrites.retain(|overwrite| overwrite.id != overwrite_id);
+    if overwrites.len() == count {
+        return Ok(StatusCode::NO_CONTENT);
+    }
+    channel.update(&state.db, Dialect::of(&state.db)).await?;
+
+    let data = load_json(&state.db, &channel).await?;
+    dispatch::to_channel(&channel, "CHANNEL_UPDATE", data).await;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Routes under `/channels/:channel_id/permissions`.
+pub fn router() -> Router<AppState> {
+    Router::new().route("/:overwrite_id", put(edit).delete(delete))
+}
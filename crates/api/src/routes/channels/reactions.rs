@@ -0,0 +1,442 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    routing::{delete, get, put},
+    Extension, Json, Router,
+};
+use serde_json::{json, Value};
+use util::{Permissions, Rights};
+use util_db::{
+    entities::{Channel, Emoji, Member, Message, Reaction, User},
+    types::{Bool, Timestamp},
+    Dialect,
+};
+
+use crate::{
+    dispatch,
+    error::ApiError,
+    guild::member_json,
+    message::emoji_json,
+    middleware::Authenticated,
+    models::{message::ReactionQuery, user::MinimalPublicUser},
+    permissions::require,
+    AppState,
+};
+
+use super::{find_channel, messages::find_message, visible_permissions};
+
+/// Emoji of a reaction, as given in the path.
+struct ReactionEmoji {
+    id: Option<String>,
+    name: String,
+    animated: bool,
+}
+
+impl ReactionEmoji {
+    /// The ID of a custom emoji, or the unicode emoji itself.
+    fn key(&self) -> &str {
+        self.id.as_deref().unwrap_or(&self.name)
+    }
+
+    fn json(&self) -> Value {
+        emoji_json(self.id.as_deref(), &self.name, self.animated)
+    }
+}
+
+fn unknown_emoji() -> ApiError {
+    ApiError::api(StatusCode::NOT_FOUND, 10014, "Unknown Emoji")
+}
+
+/// Parse a unicode emoji or a custom one written `name:id`, along with the
+/// custom emoji if it still exists.
+async fn parse_emoji(
+    state: &AppState,
+    emoji: &str,
+) -> Result<(ReactionEmoji, Option<Emoji>), ApiError> {
+    let Some((name, id)) = emoji.rsplit_once(':') else {
+        if emoji.is_empty() || emoji.is_ascii() {
+            return Err(unknown_emoji());
+        }
+        let emoji = ReactionEmoji {
+            id: None,
+            name: emoji.to_string(),
+            animated: false,
+        };
+        return Ok((emoji, None));
+    };
+    if id.is_empty() || !id.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(unknown_emoji());
+    }
+    // Animated emojis may be written `a:name:id`.
+    let name = name.strip_prefix("a:").unwrap_or(name);
+    let custom = Emoji::find(&state.db, id).await?;
+    let emoji = match &custom {
+        Some(custom) => ReactionEmoji {
+            id: Some(custom.id.clone()),
+            name: custom.name.clone(),
+            animated: custom.animated.0,
+        },
+        None => ReactionEmoji {
+            id: Some(id.to_string()),
+            name: name.to_string(),
+            animated: emoji.starts_with("a:"),
+        },
+    };
+    Ok((emoji, custom))
+}
+
+/// The message `message_id` of the channel `channel_id`, once checked that
+/// the current user may read its reactions.
+async fn reacted_message(
+    state: &AppState,
+    auth: &Authenticated,
+    channel_id: &str,
+    message_id: &str,
+) -> Result<(Channel, Permissions, Message), ApiError> {
+    let channel = find_channel(state, channel_id).await?;
+    let granted = visible_permissions(state, &channel, &auth.user_id).await?;
+    require(granted, Permissions::READ_MESSAGE_HISTORY)?;
+    let message = find_message(state, channel_id, message_id).await?;
+    Ok((channel, granted, message))
+}
+
+/// Fields common to every reaction event of `message`.
+fn event_json(channel: &Channel, message: &Message) -> Value {
+    json!({
+        "channel_id": channel.id,
+        "message_id": message.id,
+        "guild_id": channel.guild_id,
+    })
+}
+
+/// Users who reacted with an emoji.
+async fn list_users(
+    State(state): State<AppState>,
+    Extension(auth): Extension<Authenticated>,
+    Path((channel_id, message_id, emoji)): Path<(String, String, String)>,
+    Query(query): Query<ReactionQuery>,
+) -> Result<Json<Vec<MinimalPublicUser>>, ApiError> {
+    let limit = query.limit.unwrap_or(25);
+    if !(1..=100).contains(&limit) {
+        return Err(ApiError::http(
+            StatusCode::UNPROCESSABLE_ENTITY,
+            "limit must be between 1 and 100",
+        ));
+    }
+    let (_, _, message) = reacted_message(&state, &auth, &channel_id, &message_id).await?;
+    let (emoji, _) = parse_emoji(&state, &emoji).await?;
+
+    let user_ids = Reaction::find_user_ids(
+        &state.db,
+        &message.id,
+        emoji.key(),
+        query.after.as_deref(),
+        limit,
+    )
+    .await?;
+    let mut users = Vec::with_capacity(user_ids.len());
+    for id in &user_ids {
+        if let Some(user) = User::find(&state.db, id).await? {
+            users.push(MinimalPublicUser::from(&user));
+        }
+    }
+    Ok(Json(users))
+}
+
+/// React to a message as the current user.
+async fn add(
+    State(state): State<AppState>,
+    Extension(auth): Extension<Authenticated>,
+    Path((channel_id, message_id, emoji)): Path<(String, String, String)>,
+) -> Result<StatusCode, ApiError> {
+    auth.require_rights(Rights::SELF_ADD_REACTIONS)?;
+    let (channel, granted, message) =
+        reacted_message(&state, &auth, &channel_id, &message_id).await?;
+    let (emoji, custom) = parse_emoji(&state, &emoji).await?;
+    if emoji.id.is_some() {
+        let custom = custom.ok_or_else(unknown_emoji)?;
+        if channel.guild_id.as_deref() != Some(custom.guild_id.as_str()) {
+            require(granted, Permissions::USE_EXTERNAL_EMOJIS)?;
+        }
+    }
+
+    let reaction = Reaction {
+        message_id: message.id.clone(),
+        emoji: emoji.key().to_string(),
+        user_id: auth.user_id.clone(),
+        emoji_id: emoji.id.clone(),
+        emoji_name: emoji.name.clone(),
+        animated: Bool(emoji.animated),
+        created_at: Timestamp::now(),
+    };
+    let dialect = Dialect::of(&state.db);
+    let mut tx = state.db.begin().await?;
+    // Concurrent requests could otherwise each add a new emoji past the limit.
+    Reaction::lock_message(&mut tx, dialect, &message.id).await?;
+    // Anyone who can read the message may join an existing reaction.
+    if !Reaction::exists(&mut *tx, dialect, &message.id, emoji.key()).await? {
+        require(granted, Permissions::ADD_REACTIONS)?;
+        let max = state.config.get().limits.message.max_reactions;
+        if Reaction::count_emojis(&mut *tx, dialect, &message.id).await? >= i64::from(max) {
+            return Err(ApiError::api(
+                StatusCode::BAD_REQUEST,
+                30010,
+                format!("Maximum number of reactions reached ({max})"),
+            ));
+        }
+    }
+    let added = reaction.insert(&mut *tx, dialect).await?;
+    tx.commit().await?;
+    if !added {
+        return Ok(StatusCode::NO_CONTENT);
+    }
+
+    let mut data = event_json(&channel, &message);
+    data["user_id"] = json!(auth.user_id);
+    data["emoji"] = emoji.json();
+    data["burst"] = json!(false);
+    data["type"] = json!(0);
+    if let Some(guild_id) = &channel.guild_id {
+        let member = Member::find(&state.db, guild_id, &auth.user_id).await?;
+        let user = User::find(&state.db, &auth.user_id).await?;
+        if let (Some(member), Some(user)) = (member, user) {
+            let role_ids: Vec<String> = Member::role_ids(&state.db, member.index)
+                .await?
+                .into_iter()
+                .filter(|id| id != guild_id)
+                .collect();
+            data["member"] = member_json(&member, &user, &role_ids);
+        }
+    }
+    dispatch::to_channel(&channel, "MESSAGE_REACTION_ADD", data).await;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Remove the reaction of a user, `@me` for the current one.
+async fn remove(
+    State(state): State<AppState>,
+    Extension(auth): Extension<Authenticated>,
+    Path((channel_id, message_id, emoji, user_id)): Path<(String, String, String, String)>,
+) -> Result<StatusCode, ApiError> {
+    let (channel, granted, message) =
+        reacted_message(&state, &auth, &channel_id, &message_id).await?;
+    let user_id = if user_id == "@me" {
+        auth.user_id.clone()
+    } else {
+        user_id
+    };
+    if user_id != auth.user_id {
+        require(granted, Permissions::MANAGE_MESSAGES)?;
+    }
+    let (emoji, _) = parse_emoji(&state, &emoji).await?;
+
+    if !Reaction::delete(&state.db, &message.id, emoji.key(), &user_id).await? {
+        return Ok(StatusCode::NO_CONTENT);
+    }
+    let mut data = event_json(&channel, &message);
+    data["user_id"] = json!(user_id);
+    data["emoji"] = emoji.json();
+    data["burst"] = json!(false);
+    data["type"] = json!(0);
+    dispatch::to_channel(&channel, "MESSAGE_REACTION_REMOVE", data).await;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Remove the reaction of the current user.
+async fn remove_own(
+    state: State<AppState>,
+    auth: Extension<Authenticated>,
+    Path((channel_id, message_id, emoji)): Path<(String, String, String)>,
+) -> Result<StatusCode, ApiError> {
+    let path = Path((channel_id, message_id, emoji, "@me".to_string()));
+    remove(state, auth, path).await
+}
+
+/// Remove every reaction with an emoji.
+async fn remove_emoji(
+    State(state): State<AppState>,
+    Extension(auth): Extension<Authenticated>,
+    Path((channel_id, message_id, emoji)): Path<(String, String, String)>,
+) -> Result<StatusCode, ApiError> {
+    let (channel, granted, message) =
+        reacted_message(&state, &auth, &channel_id, &message_id).await?;
+    require(granted, Permissions::MANAGE_MESSAGES)?;
+    let (emoji, _) = parse_emoji(&state, &emoji).await?;
+
+    if Reaction::delete_by_emoji(&state.db, &message.id, emoji.key()).await? {
+        let mut data = event_json(&channel, &message);
+        data["emoji"] = emoji.json();
+        dispatch::to_channel(&channel, "MESSAGE_REACTION_REMOVE_EMOJI", data).await;
+    }
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Remove every reaction from a message.
+async fn remove_all(
+    State(state): State<AppState>,
+    Extension(auth): Extension<Authenticated>,
+    Path((channel_id, message_id)): Path<(String, String)>,
+) -> Result<StatusCode, ApiError> {
+    let (channel, granted, message) =
+        reacted_message(&state, &auth, &channel_id, &message_id).await?;
+    require(granted, Permissions::MANAGE_MESSAGES)?;
+
+    if Reaction::delete_by_message(&state.db, &message.id).await? {
+        let data = event_json(&channel, &message);
+        dispatch::to_channel(&channel, "MESSAGE_REACTION_REMOVE_ALL", data).await;
+    }
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Routes under `/channels/:channel_id/messages/:message_id/reactions`.
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route("/", delete(remove_all))
+        .route("/:emoji", get(list_users).delete(remove_emoji))
+        .route("/:emoji/@me", put(add).delete(remove_own))
+        .route("/:emoji/:user_id", delete(remove))
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::http::Method;
+    use config::Config;
+
+    use super::*;
+    use crate::testing::{guild, request, state, text_channel, user, user_with_id};
+
+    /// `limits.message.maxReactions` of the reaction tests. The
+    /// configuration is shared by every test, so they all use this value.
+    const MAX_REACTIONS: u32 = 3;
+
+    fn limit_reactions() {
+        let mut config = (*Config::get()).clone();
+        config.limits.message.max_reactions = MAX_REACTIONS;
+        Config::set(config);
+    }
+
+    /// `emoji` as written in a path.
+    fn encode(emoji: &str) -> String {
+        emoji.bytes().map(|byte| format!("%{byte:02X}")).collect()
+    }
+
+    /// The URI of the reactions to a new message of `auth`.
+    async fn new_message(state: &AppState, auth: &Authenticated) -> String {
+        let channel_id = text_channel(&guild(state, auth).await);
+        let uri = format!("/channels/{channel_id}/messages");
+        let body = json!({ "content": "react to this" });
+        let (_, sent) = request(state, auth, Method::POST, &uri, Some(body)).await;
+        format!("{uri}/{}/reactions", sent["id"].as_str().unwrap())
+    }
+
+    fn emoji_count(message: &Value) -> usize {
+        message["reactions"].as_array().unwrap().len()
+    }
+
+    #[tokio::test]
+    async fn new_emojis_are_limited() {
+        limit_reactions();
+        let state = state().await;
+        let owner = user(&state, "owner").await;
+        let uri = new_message(&state, &owner).await;
+        let emojis = ["😀", "😁", "😂", "🤣"];
+        for emoji in &emojis[..3] {
+            let add = format!("{uri}/{}/@me", encode(emoji));
+            let (status, _) = request(&state, &owner, Method::PUT, &add, None).await;
+            assert_eq!(status, StatusCode::NO_CONTENT);
+        }
+
+        let add = format!("{uri}/{}/@me", encode(emojis[3]));
+        let (status, error) = request(&state, &owner, Method::PUT, &add, None).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(error["code"], 30010);
+
+        // Adding an emoji already there, or one freed up, is still allowed.
+        let add = format!("{uri}/{}/@me", encode(emojis[0]));
+        let (status, _) = request(&state, &owner, Method::PUT, &add, None).await;
+        assert_eq!(status, StatusCode::NO_CONTENT);
+        let own = format!("{uri}/{}/@me", encode(emojis[1]));
+        request(&state, &owner, Method::DELETE, &own, None).await;
+        let add = format!("{uri}/{}/@me", encode(emojis[3]));
+        let (status, _) = request(&state, &owner, Method::PUT, &add, None).await;
+        assert_eq!(status, StatusCode::NO_CONTENT);
+    }
+
+    #[tokio::test]
+    async fn concurrent_adds_respect_the_limit() {
+        limit_reactions();
+        let state = state().await;
+        let owner = user(&state, "owner").await;
+        let uri = new_message(&state, &owner).await;
+        let add = |emoji: &str| {
+            let uri = format!("{uri}/{}/@me", encode(emoji));
+            let (state, owner) = (&state, &owner);
+            async move { request(state, owner, Method::PUT, &uri, None).await.0 }
+        };
+
+        let statuses =
+            futures_util::future::join_all(["😀", "😁", "😂", "🤣", "😃", "😄"].map(&add)).await;
+        let added = statuses
+            .iter()
+            .filter(|status| **status == StatusCode::NO_CONTENT)
+            .count();
+        assert_eq!(added, MAX_REACTIONS as usize);
+
+        // The same reaction added at once is stored once.
+        let statuses = futures_util::future::join_all([add("😀"), add("😀")]).await;
+        assert!(statuses
+            .iter()
+            .all(|status| *status == StatusCode::NO_CONTENT));
+        let message_uri = uri.trim_end_matches("/reactions");
+        let channel_uri = message_uri.rsplit_once('/').unwrap().0;
+        let (_, history) = request(&state, &owner, Method::GET, channel_uri, None).await;
+        assert_eq!(emoji_count(&history[0]), MAX_REACTIONS as usize);
+        for reaction in history[0]["reactions"].as_array().unwrap() {
+            assert_eq!(reaction["count"], 1);
+        }
+    }
+
+    #[tokio::test]
+    async fn users_are_listed_in_numeric_order() {
+        let state = state().await;
+        let owner = user(&state, "owner").await;
+        let uri = new_message(&state, &owner).await;
+        let message_id = uri.split('/').nth(4).unwrap().to_string();
+
+        // As strings, "10" sorts before "9".
+        for id in ["9", "10", "100"] {
+            user_with_id(&state, id, "reactor").await;
+            let reaction = Reaction {
+                message_id: message_id.clone(),
+                emoji: "😀".into(),
+                user_id: id.into(),
+                emoji_id: None,
+                emoji_name: "😀".into(),
+                animated: Bool(false),
+                created_at: Timestamp::now(),
+            };
+            reaction
+                .insert(&state.db, Dialect::of(&state.db))
+                .await
+                .unwrap();
+        }
+        let list = |query: &str| {
+            let uri = format!("{uri}/{}?{query}", encode("😀"));
+            let (state, owner) = (&state, &owner);
+            async move {
+                let (status, users) = request(state, owner, Method::GET, &uri, None).await;
+                assert_eq!(status, StatusCode::OK);
+                users
+                    .as_array()
+                    .unwrap()
+                    .iter()
+                    .map(|user| user["id"].as_str().unwrap().to_string())
+                    .collect::<Vec<_>>()
+            }
+        };
+        assert_eq!(list("").await, ["9", "10", "100"]);
+        assert_eq!(list("limit=1").await, ["9"]);
+        assert_eq!(list("after=9").await, ["10", "100"]);
+        assert_eq!(list("after=10&limit=1").await, ["100"]);
+    }
+}
@@ -0,0 +1,226 @@
+use std::collections::HashMap;
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    routing::get,
+    Extension, Json, Router,
+};
+use serde_json::Value;
+use util::{PermissionOverwrite, Permissions, Snowflake};
+use util_db::{
+    entities::{Channel, Guild},
+    types::Json as DbJson,
+    Dialect,
+};
+
+use crate::{
+    channel::{channel_json, check_parent, check_topic, new_channel, place, GUILD_CHANNEL_TYPES},
+    dispatch,
+    error::ApiError,
+    middleware::Authenticated,
+    models::channel::{ChannelCreateRequest, ChannelReorderRequest},
+    permissions::MemberPermissions,
+    routes::channels::unknown_channel,
+    AppState,
+};
+
+use super::{find_guild, missing_access};
+
+/// The channels of the guild the current user can see, in order.
+async fn list(
+    State(state): State<AppState>,
+    Extension(auth): Extension<Authenticated>,
+    Path(guild_id): Path<String>,
+) -> Result<Json<Vec<Value>>, ApiError> {
+    let guild = find_guild(&state, &guild_id).await?;
+    let member = MemberPermissions::of(&state.db, &guild, &auth.user_id)
+        .await?
+        .ok_or_else(missing_access)?;
+    let mut channels: Vec<Channel> = Channel::find_by_guild(&state.db, &guild_id)
+        .await?
+        .into_iter()
+        .filter(|channel| member.in_channel(channel).has(Permissions::VIEW_CHANNEL))
+        .collect();
+    let ordering = &guild.channel_ordering.0;
+    channels.sort_by_key(|channel| ordering.iter().position(|id| *id == channel.id));
+    Ok(Json(
+        channels
+            .iter()
+            .map(|channel| channel_json(channel, ordering))
+            .collect(),
+    ))
+}
+
+async fn create(
+    State(state): State<AppState>,
+    Extension(auth): Extension<Authenticated>,
+    Path(guild_id): Path<String>,
+    Json(payload): Json<ChannelCreateRequest>,
+) -> Result<(StatusCode, Json<Value>), ApiError> {
+    payload
+        .validate()
+        .map_err(|(field, e)| ApiError::field(field, "BASE_TYPE_BAD_LENGTH", e))?;
+    let mut guild = find_guild(&state, &guild_id).await?;
+    let member = MemberPermissions::of(&state.db, &guild, &auth.user_id)
+        .await?
+        .ok_or_else(missing_access)?;
+    member.require(Permissions::MANAGE_CHANNELS)?;
+
+    if !GUILD_CHANNEL_TYPES.contains(&payload.kind) {
+        return Err(ApiError::field(
+            "type",
+            "CHANNEL_TYPE_INVALID",
+            format!("Channel type {} cannot be created in a guild", payload.kind),
+        ));
+    }
+    if let Some(topic) = &payload.topic {
+        check_topic(&state, topic)?;
+    }
+    let max_channels = state.config.get().limits.guild.max_channels;
+    if Channel::count_by_guild(&state.db, &guild_id).await? >= i64::from(max_channels) {
+        return Err(ApiError::api(
+            StatusCode::BAD_REQUEST,
+            30013,
+            format!("Maximum number of guild channels reached ({max_channels})"),
+        ));
+    }
+    let parent = match &payload.parent_id {
+        Some(parent_id) => Some(check_parent(&state, &guild_id, payload.kind, parent_id).await?),
+        None => None,
+    };
+
+    // Channels created in a category are synced with it unless they bring
+    // their own overwrites.
+    let overwrites = match &payload.permission_overwrites {
+        Some(overwrites) => overwrites
+            .iter()
+            .filter_map(|overwrite| {
+                Some(PermissionOverwrite {
+                    allow: overwrite.allow,
+                    deny: overwrite.deny,
+                    id: overwrite.id.clone()?,
+                    kind: overwrite.kind,
+                })
+            })
+            .collect(),
+        None => parent
+            .as_ref()
+            .and_then(|parent| parent.permission_overwrites.clone())
+            .map(|overwrites| overwrites.0)
+            .unwrap_or_default(),
+    };
+    let channel = new_channel(
+        &guild_id,
+        Snowflake::generate().to_string(),
+        payload.parent_id.clone(),
+        &payload,
+        overwrites,
+    );
+
+    place(
+        &mut guild.channel_ordering.0,
+        &channel.id,
+        payload.position.map(|position| position.max(0) as usize),
+        channel.parent_id.as_deref(),
+    );
+    let dialect = Dialect::of(&state.db);
+    let mut tx = state.db.begin().await?;
+    channel.insert(&mut *tx, dialect).await?;
+    Guild::set_channel_ordering(&mut *tx, dialect, &guild_id, &guild.channel_ordering).await?;
+    tx.commit().await?;
+
+    let data = channel_json(&channel, &guild.channel_ordering);
+    dispatch::to_channel(&channel, "CHANNEL_CREATE", data.clone()).await;
+    Ok((StatusCode::CREATED, Json(data)))
+}
+
+/// Move several channels of the guild at once, to other positions or
+/// categories.
+async fn reorder(
+    State(state): State<AppState>,
+    Extension(auth): Extension<Authenticated>,
+    Path(guild_id): Path<String>,
+    Json(payload): Json<Vec<ChannelReorderRequest>>,
+) -> Result<StatusCode, ApiError> {
+    let mut guild = find_guild(&state, &guild_id).await?;
+    let member = MemberPermissions::of(&state.db, &guild, &auth.user_id)
+        .await?
+        .ok_or_else(missing_access)?;
+    member.require(Permissions::MANAGE_CHANNELS)?;
+
+    let mut channels: HashMap<String, Channel> = Channel::find_by_guild(&state.db, &guild_id)
+        .await?
+        .into_iter()
+        .map(|channel| (channel.id.clone(), channel))
+        .collect();
+    if payload
+        .iter()
+        .any(|entry| !channels.contains_key(&entry.id))
+    {
+        return Err(unknown_channel());
+    }
+
+    // Categories first, so that channels moved without a position land in
+    // their new category.
+    for entry in &payload {
+        let Some(parent_id) = &entry.parent_id else {
+            continue;
+        };
+        let kind = channels[&entry.id].kind;
+        let parent = match parent_id {
+            Some(parent_id) if channels[&entry.id].parent_id.as_ref() != Some(parent_id) => {
+                Some(check_parent(&state, &guild_id, kind, parent_id).await?)
+            }
+            Some(parent_id) => channels.get(parent_id).cloned(),
+            None => None,
+        };
+        let channel = channels.get_mut(&entry.id).expect("checked above");
+        if entry.lock_permissions {
+            channel.permission_overwrites = parent
+                .as_ref()
+                .and_then(|parent| parent.permission_overwrites.clone())
+                .or(Some(DbJson(Vec::new())));
+        }
+        channel.parent_id = parent_id.clone();
+    }
+
+    let ordering = &mut guild.channel_ordering.0;
+    // Channels missing from the ordering, such as those of guilds imported
+    // from the TypeScript server, go last, oldest first.
+    let mut missing: Vec<&String> = channels
+        .keys()
+        .filter(|id| !ordering.contains(id))
+        .collect();
+    missing.sort_by_key(|id| (id.len(), *id));
+    ordering.extend(missing.into_iter().cloned());
+    let mut moves: Vec<&ChannelReorderRequest> = payload
+        .iter()
+        .filter(|entry| entry.position.is_some() || entry.parent_id.is_some())
+        .collect();
+    moves.sort_by_key(|entry| entry.position);
+    for entry in moves {
+        let parent_id = channels[&entry.id].parent_id.as_deref();
+        place(ordering, &entry.id, entry.position, parent_id);
+    }
+
+    let dialect = Dialect::of(&state.db);
+    let mut tx = state.db.begin().await?;
+    for entry in &payload {
+        channels[&entry.id].update(&mut *tx, dialect).await?;
+    }
+    Guild::set_channel_ordering(&mut *tx, dialect, &guild_id, ordering).await?;
+    tx.commit().await?;
+
+    for entry in &payload {
+        let channel = &channels[&entry.id];
+        let data = channel_json(channel, ordering);
+        dispatch::to_channel(channel, "CHANNEL_UPDATE", data).await;
+    }
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Routes under `/guilds/:guild_id/channels`.
+pub fn router() -> Router<AppState> {
+    Router::new().route("/", get(list).post(create).patch(reorder))
+}
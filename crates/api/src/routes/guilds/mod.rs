@@ -0,0 +1,253 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    routing::{get, post},
+    Extension, Json, Router,
+};
+use serde_json::{json, Value};
+use util::{Permissions, Rights};
+use util_db::{
+    entities::{Channel, Guild, Member, Role},
+    types::{Bool, SimpleArray},
+    Dialect,
+};
+
+use crate::{
+    dispatch,
+    error::ApiError,
+    guild::{create_guild, guild_json, CreatedGuild},
+    middleware::Authenticated,
+    models::guild::{GuildCreateRequest, GuildUpdateRequest},
+    permissions::MemberPermissions,
+    AppState,
+};
+
+pub mod channels;
+
+/// Features a guild may turn on and off itself; the others are granted by
+/// the instance.
+const MUTABLE_FEATURES: &[&str] = &["COMMUNITY", "INVITES_DISABLED", "DISCOVERABLE"];
+
+pub(crate) fn unknown_guild() -> ApiError {
+    ApiError::api(StatusCode::NOT_FOUND, 10004, "Unknown Guild")
+}
+
+pub(crate) fn missing_access() -> ApiError {
+    ApiError::api(StatusCode::FORBIDDEN, 50001, "Missing Access")
+}
+
+/// The guild `guild_id`, or a 404.
+pub(crate) async fn find_guild(state: &AppState, guild_id: &str) -> Result<Guild, ApiError> {
+    Guild::find(&state.db, guild_id)
+        .await?
+        .ok_or_else(unknown_guild)
+}
+
+async fn create(
+    State(state): State<AppState>,
+    Extension(auth): Extension<Authenticated>,
+    Json(payload): Json<GuildCreateRequest>,
+) -> Result<(StatusCode, Json<Value>), ApiError> {
+    auth.require_rights(Rights::CREATE_GUILDS)?;
+    payload
+        .validate()
+        .map_err(|(field, e)| ApiError::field(field, "BASE_TYPE_BAD_LENGTH", e))?;
+
+    let max_guilds = state.config.get().limits.user.max_guilds;
+    let guild_count = Member::count_by_user(&state.db, &auth.user_id).await?;
+    if guild_count >= i64::from(max_guilds) && !auth.rights.has(Rights::MANAGE_GUILDS) {
+        return Err(ApiError::api(
+            StatusCode::BAD_REQUEST,
+            30001,
+            format!("Maximum number of guilds reached ({max_guilds})"),
+        ));
+    }
+
+    let CreatedGuild {
+        guild,
+        roles,
+        channels,
+    } = create_guild(&state, &auth.user_id, payload).await?;
+    Ok((
+        StatusCode::CREATED,
+        Json(guild_json(&guild, &roles, &channels)),
+    ))
+}
+
+async fn get_guild(
+    State(state): State<AppState>,
+    Extension(auth): Extension<Authenticated>,
+    Path(guild_id): Path<String>,
+) -> Result<Json<Value>, ApiError> {
+    let guild = find_guild(&state, &guild_id).await?;
+    let member = Member::find(&state.db, &guild_id, &auth.user_id)
+        .await?
+        .ok_or_else(|| {
+            ApiError::http(
+                StatusCode::UNAUTHORIZED,
+                "You are not a member of the guild you are trying to access",
+            )
+        })?;
+    let roles = Role::find_by_guild(&state.db, &guild_id).await?;
+    let mut data = json!(guild);
+    data["roles"] = json!(roles);
+    data["joined_at"] = json!(member.joined_at);
+    Ok(Json(data))
+}
+
+/// Check that `channel_id` is a channel of the guild `guild_id`.
+async fn check_channel(
+    state: &AppState,
+    guild_id: &str,
+    field: &'static str,
+    channel_id: &str,
+) -> Result<(), ApiError> {
+    let channel = Channel::find(&state.db, channel_id).await?;
+    if channel.is_some_and(|channel| channel.guild_id.as_deref() == Some(guild_id)) {
+        return Ok(());
+    }
+    Err(ApiError::field(
+        field,
+        "CHANNEL_INVALID",
+        format!("Unknown channel {channel_id}"),
+    ))
+}
+
+async fn update(
+    State(state): State<AppState>,
+    Extension(auth): Extension<Authenticated>,
+    Path(guild_id): Path<String>,
+    Json(payload): Json<GuildUpdateRequest>,
+) -> Result<Json<Value>, ApiError> {
+    payload
+        .validate()
+        .map_err(|(field, e)| ApiError::field(field, "BASE_TYPE_BAD_LENGTH", e))?;
+    let mut guild = find_guild(&state, &guild_id).await?;
+    if !auth.rights.has(Rights::MANAGE_GUILDS) {
+        let member = MemberPermissions::of(&state.db, &guild, &auth.user_id)
+            .await?
+            .ok_or_else(missing_access)?;
+        member.require(Permissions::MANAGE_GUILD)?;
+    }
+
+    if let Some(features) = payload.features {
+        let changed = guild
+            .features
+            .iter()
+            .filter(|feature| !features.contains(feature))
+            .chain(
+                features
+                    .iter()
+                    .filter(|feature| !guild.features.contains(feature)),
+            );
+        for feature in changed {
+            if !MUTABLE_FEATURES.contains(&feature.as_str()) {
+                return Err(ApiError::api(
+                    StatusCode::FORBIDDEN,
+                    45007,
+                    format!("The feature ({feature}) cannot be edited."),
+                ));
+            }
+        }
+        guild.features = SimpleArray(features);
+    }
+
+    for (field, channel_id) in [
+        ("afk_channel_id", &payload.afk_channel_id),
+        ("system_channel_id", &payload.system_channel_id),
+        ("rules_channel_id", &payload.rules_channel_id),
+        (
+            "public_updates_channel_id",
+            &payload.public_updates_channel_id,
+        ),
+        ("widget_channel_id", &payload.widget_channel_id),
+    ] {
+        if let Some(Some(channel_id)) = channel_id {
+            check_channel(&state, &guild_id, field, channel_id).await?;
+        }
+    }
+
+    if let Some(name) = payload.name {
+        guild.name = name.trim().to_string();
+    }
+    if let Some(region) = payload.region {
+        guild.region = region;
+    }
+    if let Some(description) = payload.description {
+        guild.description = description;
+    }
+    if let Some(level) = payload.verification_level {
+        guild.verification_level = Some(level);
+    }
+    if let Some(notifications) = payload.default_message_notifications {
+        guild.default_message_notifications = Some(notifications);
+    }
+    if let Some(filter) = payload.explicit_content_filter {
+        guild.explicit_content_filter = Some(filter);
+    }
+    if let Some(channel_id) = payload.afk_channel_id {
+        guild.afk_channel_id = channel_id;
+    }
+    if let Some(timeout) = payload.afk_timeout {
+        guild.afk_timeout = Some(timeout);
+    }
+    if let Some(channel_id) = payload.system_channel_id {
+        guild.system_channel_id = channel_id;
+    }
+    if let Some(flags) = payload.system_channel_flags {
+        guild.system_channel_flags = Some(flags);
+    }
+    if let Some(channel_id) = payload.rules_channel_id {
+        guild.rules_channel_id = channel_id;
+    }
+    if let Some(channel_id) = payload.public_updates_channel_id {
+        guild.public_updates_channel_id = channel_id;
+    }
+    if let Some(locale) = payload.preferred_locale {
+        guild.preferred_locale = locale;
+    }
+    if let Some(enabled) = payload.premium_progress_bar_enabled {
+        guild.premium_progress_bar_enabled = Some(Bool(enabled));
+    }
+    if let Some(enabled) = payload.widget_enabled {
+        guild.widget_enabled = Bool(enabled);
+    }
+    if let Some(channel_id) = payload.widget_channel_id {
+        guild.widget_channel_id = channel_id;
+    }
+
+    guild.update(&state.db, Dialect::of(&state.db)).await?;
+    let data = json!(guild);
+    dispatch::to_guild(&guild_id, "GUILD_UPDATE", data.clone()).await;
+    Ok(Json(data))
+}
+
+async fn delete(
+    State(state): State<AppState>,
+    Extension(auth): Extension<Authenticated>,
+    Path(guild_id): Path<String>,
+) -> Result<StatusCode, ApiError> {
+    let guild = find_guild(&state, &guild_id).await?;
+    if guild.owner_id.as_deref() != Some(auth.user_id.as_str()) {
+        return Err(ApiError::http(
+            StatusCode::UNAUTHORIZED,
+            "You are not the owner of this guild",
+        ));
+    }
+
+    // Cascades to the roles, channels and members of the guild.
+    Guild::delete(&state.db, &guild_id).await?;
+    dispatch::to_guild(&guild_id, "GUILD_DELETE", json!({ "id": guild_id })).await;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Routes under `/guilds`.
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route("/", post(create))
+        .route("/:guild_id", get(get_guild).patch(update).delete(delete))
+        // Discord clients delete guilds through this route rather than with
+        // the DELETE method.
+        .route("/:guild_id/delete", post(delete))
+        .nest("/:guild_id/channels", channels::router())
+}
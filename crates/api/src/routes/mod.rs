@@ -2,16 +2,24 @@ use axum::Router;
 
 use crate::AppState;
 
+pub mod auth;
+pub mod channels;
+pub mod guilds;
 pub mod ping;
 pub mod science;
 pub mod stop;
 pub mod track;
+pub mod users;
 
 /// Combine all API routes into a single router.
 pub fn create_router() -> Router<AppState> {
     Router::new()
+        .nest("/auth", auth::router())
+        .nest("/channels", channels::router())
+        .nest("/guilds", guilds::router())
         .nest("/ping", ping::router())
         .nest("/stop", stop::router())
         .nest("/science", science::router())
         .nest("/track", track::router())
+        .nest("/users", users::router())
 }
@@ -24,7 +24,8 @@ struct PingResponse {
 }
 
 async fn handler(State(state): State<AppState>) -> Json<PingResponse> {
-    let general = &state.config.general;
+    let config = state.config.get();
+    let general = &config.general;
     let resp = PingResponse {
         ping: "pong!",
         instance: InstanceInfo {
@@ -1,39 +1,41 @@
 use axum::body::Body;
 use axum::middleware::Next;
-use axum::response::Response;
+use axum::response::{IntoResponse, Response};
 use axum::{
     extract::State,
-    http::{header, Request, StatusCode},
+    http::{Request, StatusCode},
     middleware::from_fn,
     routing::post,
-    Json, Router,
+    Extension, Json, Router,
 };
 use serde::Deserialize;
+use util::Rights;
 
-use crate::AppState;
+use crate::{error::ApiError, middleware::Authenticated, AppState};
 
 #[derive(Deserialize)]
 struct StopRequest {
     reason: Option<String>,
 }
 
-async fn handler(State(_state): State<AppState>, Json(payload): Json<StopRequest>) -> StatusCode {
-    println!("/stop was called: {:?}", payload.reason);
+async fn handler(
+    State(_state): State<AppState>,
+    Extension(user): Extension<Authenticated>,
+    Json(payload): Json<StopRequest>,
+) -> StatusCode {
+    println!("/stop was called by {}: {:?}", user.user_id, payload.reason);
     StatusCode::OK
 }
 
-async fn auth(req: Request<Body>, next: Next) -> Result<Response, StatusCode> {
-    let authorized = req
-        .headers()
-        .get(header::AUTHORIZATION)
-        .and_then(|v| v.to_str().ok())
-        .map(|v| v == "Bearer operator")
-        .unwrap_or(false);
-    if authorized {
-        Ok(next.run(req).await)
-    } else {
-        Err(StatusCode::UNAUTHORIZED)
+/// Only operators may stop the server.
+async fn auth(req: Request<Body>, next: Next) -> Response {
+    let Some(user) = req.extensions().get::<Authenticated>() else {
+        return ApiError::http(StatusCode::UNAUTHORIZED, "Unauthorized").into_response();
+    };
+    if let Err(e) = user.require_rights(Rights::OPERATOR) {
+        return e.into_response();
     }
+    next.run(req).await
 }
 
 pub fn router() -> Router<AppState> {
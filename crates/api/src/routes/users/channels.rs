@@ -0,0 +1,130 @@
+use axum::{extract::State, routing::get, Extension, Json, Router};
+use serde_json::Value;
+use util::Snowflake;
+use util_db::{
+    entities::{Channel, Recipient, User, CHANNEL_DM, CHANNEL_GROUP_DM},
+    types::{Bool, Json as DbJson, Timestamp},
+    Dialect,
+};
+
+use crate::{
+    channel::{dm_json, load_json},
+    dispatch,
+    error::ApiError,
+    middleware::Authenticated,
+    models::channel::DmChannelCreateRequest,
+    AppState,
+};
+
+/// Most users a group DM may hold, its owner included.
+const MAX_GROUP_DM_RECIPIENTS: usize = 10;
+
+/// The direct messages the current user has not closed.
+async fn list(
+    State(state): State<AppState>,
+    Extension(auth): Extension<Authenticated>,
+) -> Result<Json<Vec<Value>>, ApiError> {
+    let mut channels = Vec::new();
+    for channel in Channel::find_open_dms(&state.db, &auth.user_id).await? {
+        channels.push(load_json(&state.db, &channel).await?);
+    }
+    Ok(Json(channels))
+}
+
+/// Open a DM with a single recipient, reusing the existing one, or create a
+/// group DM with several.
+async fn create(
+    State(state): State<AppState>,
+    Extension(auth): Extension<Authenticated>,
+    Json(payload): Json<DmChannelCreateRequest>,
+) -> Result<Json<Value>, ApiError> {
+    let mut recipient_ids: Vec<String> = Vec::new();
+    for id in payload.recipients {
+        if id != auth.user_id && !recipient_ids.contains(&id) {
+            recipient_ids.push(id);
+        }
+    }
+    if recipient_ids.is_empty() || recipient_ids.len() >= MAX_GROUP_DM_RECIPIENTS {
+        return Err(ApiError::field(
+            "recipients",
+            "BASE_TYPE_BAD_LENGTH",
+            format!(
+                "Must be between 1 and {} in length.",
+                MAX_GROUP_DM_RECIPIENTS - 1
+            ),
+        ));
+    }
+
+    if let [recipient_id] = recipient_ids.as_slice() {
+        if let Some(channel) = Channel::find_dm(&state.db, &auth.user_id, recipient_id).await? {
+            Recipient::set_closed(&state.db, &channel.id, &auth.user_id, false).await?;
+            let data = load_json(&state.db, &channel).await?;
+            dispatch::to_user(&auth.user_id, "CHANNEL_CREATE", data.clone()).await;
+            return Ok(Json(data));
+        }
+    }
+
+    let mut users = Vec::with_capacity(recipient_ids.len() + 1);
+    for id in std::iter::once(&auth.user_id).chain(&recipient_ids) {
+        let user = User::find(&state.db, id).await?.ok_or_else(|| {
+            ApiError::field("recipients", "USER_INVALID", format!("Unknown user {id}"))
+        })?;
+        users.push(user);
+    }
+
+    let group = recipient_ids.len() > 1;
+    let channel = Channel {
+        id: Snowflake::generate().to_string(),
+        created_at: Timestamp::now(),
+        name: payload.name.filter(|_| group),
+        icon: None,
+        kind: if group { CHANNEL_GROUP_DM } else { CHANNEL_DM },
+        last_message_id: None,
+        guild_id: None,
+        parent_id: None,
+        owner_id: group.then(|| auth.user_id.clone()),
+        last_pin_timestamp: None,
+        default_auto_archive_duration: None,
+        permission_overwrites: Some(DbJson(Vec::new())),
+        video_quality_mode: None,
+        bitrate: None,
+        user_limit: None,
+        nsfw: Bool(false),
+        rate_limit_per_user: None,
+        topic: None,
+        retention_policy_id: None,
+        flags: 0,
+        default_thread_rate_limit_per_user: 0,
+    };
+
+    let dialect = Dialect::of(&state.db);
+    let mut tx = state.db.begin().await?;
+    channel.insert(&mut *tx, dialect).await?;
+    for user in &users {
+        // A new DM only shows up for the other user once it has messages.
+        let closed = !group && user.id != auth.user_id;
+        Recipient {
+            id: Snowflake::generate().to_string(),
+            channel_id: channel.id.clone(),
+            user_id: user.id.clone(),
+            closed: Bool(closed),
+        }
+        .insert(&mut *tx, dialect)
+        .await?;
+    }
+    tx.commit().await?;
+
+    let data = dm_json(&channel, &users);
+    if group {
+        for user in &users {
+            dispatch::to_user(&user.id, "CHANNEL_CREATE", data.clone()).await;
+        }
+    } else {
+        dispatch::to_user(&auth.user_id, "CHANNEL_CREATE", data.clone()).await;
+    }
+    Ok(Json(data))
+}
+
+pub fn router() -> Router<AppState> {
+    Router::new().route("/", get(list).post(create))
+}
@@ -0,0 +1,50 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    routing::{delete, get},
+    Extension, Json, Router,
+};
+use serde_json::{json, Value};
+use util_db::entities::Session;
+
+use crate::{error::ApiError, middleware::Authenticated, session::revoke_session, AppState};
+
+/// The logins of the current user, each a device its tokens are used on.
+async fn list(
+    State(state): State<AppState>,
+    Extension(auth): Extension<Authenticated>,
+) -> Result<Json<Value>, ApiError> {
+    let sessions = Session::find_logins(&state.db, &auth.user_id).await?;
+    let devices: Vec<Value> = sessions
+        .into_iter()
+        .map(|session| {
+            json!({
+                "id": session.session_id,
+                "client_info": session.client_info,
+                "ip": session.ip,
+                "user_agent": session.user_agent,
+                "last_seen": session.last_seen,
+                "current": auth.session_id.as_deref() == Some(session.session_id.as_str()),
+            })
+        })
+        .collect();
+    Ok(Json(Value::Array(devices)))
+}
+
+/// Sign a device out, closing its gateway connections.
+async fn remove(
+    State(state): State<AppState>,
+    Extension(auth): Extension<Authenticated>,
+    Path(session_id): Path<String>,
+) -> Result<StatusCode, ApiError> {
+    if !revoke_session(&state, &auth.user_id, &session_id).await? {
+        return Err(ApiError::http(StatusCode::NOT_FOUND, "Unknown session"));
+    }
+    Ok(StatusCode::NO_CONTENT)
+}
+
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route("/", get(list))
+        .route("/:session_id", delete(remove))
+}
@@ -0,0 +1,16 @@
+use axum::{extract::State, routing::get, Extension, Json, Router};
+use util_db::entities::Guild;
+
+use crate::{error::ApiError, middleware::Authenticated, AppState};
+
+/// The guilds the current user is a member of.
+async fn list(
+    State(state): State<AppState>,
+    Extension(auth): Extension<Authenticated>,
+) -> Result<Json<Vec<Guild>>, ApiError> {
+    Ok(Json(Guild::find_by_member(&state.db, &auth.user_id).await?))
+}
+
+pub fn router() -> Router<AppState> {
+    Router::new().route("/", get(list))
+}
@@ -0,0 +1,222 @@
+use axum::{extract::State, http::StatusCode, routing::post, Extension, Json, Router};
+use rand::RngCore;
+use serde::Serialize;
+use serde_json::{json, Value};
+use util::{generate_token, Totp};
+use util_db::entities::{BackupCode, User};
+
+use crate::{
+    error::ApiError,
+    middleware::Authenticated,
+    models::mfa::{
+        CodesVerificationRequest, MfaCodesRequest, TotpDisableRequest, TotpEnableRequest,
+    },
+    AppState,
+};
+
+/// A backup code as sent to clients.
+#[derive(Serialize)]
+struct BackupCodeResponse {
+    user_id: String,
+    /// Only known right after the code was generated, as the database keeps
+    /// hashes alone.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    code: Option<String>,
+    consumed: bool,
+}
+
+impl From<BackupCode> for BackupCodeResponse {
+    fn from(code: BackupCode) -> Self {
+        Self {
+            user_id: code.user_id.unwrap_or_default(),
+            code: None,
+            consumed: *code.consumed,
+        }
+    }
+}
+
+fn invalid_code() -> ApiError {
+    ApiError::api(StatusCode::BAD_REQUEST, 60008, "Invalid two-factor code")
+}
+
+pub(crate) async fn current_user(state: &AppState, auth: &Authenticated) -> Result<User, ApiError> {
+    User::find(&state.db, &auth.user_id)
+        .await?
+        .ok_or_else(|| ApiError::api(StatusCode::NOT_FOUND, 10013, "Unknown User"))
+}
+
+/// Reject the request unless `password` is the password of `user`.
+pub(crate) async fn check_password(user: &User, password: &str) -> Result<(), ApiError> {
+    let hash = user.data.hash.clone().unwrap_or_default();
+    let password = password.to_string();
+    let same_password = tokio::task::spawn_blocking(move || bcrypt::verify(password, &hash))
+        .await?
+        .unwrap_or(false);
+    if !same_password {
+        return Err(ApiError::field(
+            "password",
+            "INVALID_PASSWORD",
+            "Invalid password",
+        ));
+    }
+    Ok(())
+}
+
+/// Expire the backup codes of `user_id` and generate new ones, unless
+/// `security.twoFactor.generateBackupCodes` is off.
+async fn regenerate_codes(
+    state: &AppState,
+    user_id: &str,
+) -> Result<Vec<BackupCodeResponse>, ApiError> {
+    let config = state.config.get();
+    let codes: Vec<String> = if config.security.two_factor.generate_backup_codes {
+        let mut rng = rand::thread_rng();
+        (0..config.security.mfa_backup_code_count)
+            .map(|_| {
+                let mut code = [0u8; 4];
+                rng.fill_bytes(&mut code);
+                hex::encode(code)
+            })
+            .collect()
+    } else {
+        Vec::new()
+    };
+    BackupCode::replace(&state.db, user_id, &codes).await?;
+    Ok(codes
+        .into_iter()
+        .map(|code| BackupCodeResponse {
+            user_id: user_id.to_string(),
+            code: Some(code),
+            consumed: false,
+        })
+        .collect())
+}
+
+async fn enable(
+    State(state): State<AppState>,
+    Extension(auth): Extension<Authenticated>,
+    Json(payload): Json<TotpEnableRequest>,
+) -> Result<Json<Value>, ApiError> {
+    auth.require_user()?;
+    let user = current_user(&state, &auth).await?;
+    if *user.mfa_enabled {
+        return Err(ApiError::http(
+            StatusCode::BAD_REQUEST,
+            "Two-factor authentication is already enabled",
+        ));
+    }
+    check_password(&user, &payload.password).await?;
+
+    let secret = payload
+        .secret
+        .filter(|secret| Totp::code(secret, 0).is_some())
+        .ok_or_else(|| {
+            ApiError::api(StatusCode::BAD_REQUEST, 60005, "Invalid two-factor secret")
+        })?;
+    let code = payload.code.ok_or_else(invalid_code)?;
+    let step = Totp::check(&secret, &code).ok_or_else(invalid_code)?;
+
+    let backup_codes = regenerate_codes(&state, &user.id).await?;
+    User::set_totp_secret(&state.db, &user.id, Some(&secret)).await?;
+    User::use_totp_step(&state.db, &user.id, step).await?;
+    let token = generate_token(
+        &user.id,
+        auth.session_id.as_deref(),
+        &state.config.get().security.jwt_secret,
+    )?;
+    Ok(Json(
+        json!({ "token": token, "backup_codes": backup_codes }),
+    ))
+}
+
+async fn disable(
+    State(state): State<AppState>,
+    Extension(auth): Extension<Authenticated>,
+    Json(payload): Json<TotpDisableRequest>,
+) -> Result<Json<Value>, ApiError> {
+    auth.require_user()?;
+    let user = current_user(&state, &auth).await?;
+    if !*user.mfa_enabled {
+        return Err(invalid_code());
+    }
+    let secret = user.totp_secret.as_deref().unwrap_or_default();
+    let accepted = match Totp::check(secret, &payload.code) {
+        Some(step) => User::use_totp_step(&state.db, &user.id, step).await?,
+        None => BackupCode::consume(&state.db, &user.id, &payload.code).await?,
+    };
+    if !accepted {
+        return Err(invalid_code());
+    }
+
+    User::set_totp_secret(&state.db, &user.id, None).await?;
+    BackupCode::expire_all(&state.db, &user.id).await?;
+    let token = generate_token(
+        &user.id,
+        auth.session_id.as_deref(),
+        &state.config.get().security.jwt_secret,
+    )?;
+    Ok(Json(json!({ "token": token })))
+}
+
+/// List the active backup codes, or replace them when `regenerate` is set.
+async fn list_codes(
+    state: &AppState,
+    user_id: &str,
+    regenerate: bool,
+) -> Result<Json<Value>, ApiError> {
+    let backup_codes: Vec<BackupCodeResponse> = if regenerate {
+        regenerate_codes(state, user_id).await?
+    } else {
+        BackupCode::find_active(&state.db, user_id)
+            .await?
+            .into_iter()
+            .map(Into::into)
+            .collect()
+    };
+    Ok(Json(json!({ "backup_codes": backup_codes })))
+}
+
+async fn codes(
+    State(state): State<AppState>,
+    Extension(auth): Extension<Authenticated>,
+    Json(payload): Json<MfaCodesRequest>,
+) -> Result<Json<Value>, ApiError> {
+    auth.require_user()?;
+    let user = current_user(&state, &auth).await?;
+    check_password(&user, &payload.password).await?;
+    list_codes(&state, &user.id, payload.regenerate.unwrap_or(false)).await
+}
+
+/// Newer clients prove their identity with the `nonce` of a backup codes
+/// challenge instead of the password.
+async fn codes_verification(
+    State(state): State<AppState>,
+    Extension(auth): Extension<Authenticated>,
+    Json(payload): Json<CodesVerificationRequest>,
+) -> Result<Json<Value>, ApiError> {
+    auth.require_user()?;
+    let user = current_user(&state, &auth).await?;
+    let regenerate = payload.regenerate.unwrap_or(false);
+    match (&payload.password, &payload.nonce) {
+        (Some(password), _) => check_password(&user, password).await?,
+        (None, Some(nonce))
+            if Totp::check_backup_codes_nonce(&state.config.get(), &user.id, nonce, regenerate) => {
+        }
+        _ => {
+            return Err(ApiError::field(
+                "nonce",
+                "INVALID_NONCE",
+                "Invalid or expired nonce",
+            ))
+        }
+    }
+    list_codes(&state, &user.id, regenerate).await
+}
+
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route("/totp/enable", post(enable))
+        .route("/totp/disable", post(disable))
+        .route("/codes", post(codes))
+        .route("/codes-verification", post(codes_verification))
+}
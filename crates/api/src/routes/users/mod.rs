@@ -0,0 +1,19 @@
+use axum::Router;
+
+use crate::AppState;
+
+pub mod channels;
+pub mod devices;
+pub mod guilds;
+pub mod mfa;
+pub mod webauthn;
+
+/// Routes under `/users`.
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .nest("/@me/channels", channels::router())
+        .nest("/@me/devices", devices::router())
+        .nest("/@me/guilds", guilds::router())
+        .nest("/@me/mfa", mfa::router())
+        .nest("/@me/mfa/webauthn", webauthn::router())
+}
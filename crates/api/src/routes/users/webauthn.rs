@@ -0,0 +1,112 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    routing::{delete, get},
+    Extension, Json, Router,
+};
+use serde_json::{json, Value};
+use util::{Snowflake, WebAuthn};
+use util_db::entities::{SecurityKey, User};
+
+use super::mfa::{check_password, current_user};
+use crate::{
+    error::ApiError, middleware::Authenticated, models::mfa::WebAuthnCredentialRequest, AppState,
+};
+
+async fn list(
+    State(state): State<AppState>,
+    Extension(auth): Extension<Authenticated>,
+) -> Result<Json<Value>, ApiError> {
+    auth.require_user()?;
+    let keys = SecurityKey::find_by_user(&state.db, &auth.user_id).await?;
+    let keys: Vec<Value> = keys
+        .into_iter()
+        .map(|key| json!({ "id": key.id, "name": key.name }))
+        .collect();
+    Ok(Json(Value::Array(keys)))
+}
+
+/// Either start registering a key, returning a challenge and its ticket, or
+/// finish it with the credential created for that challenge.
+async fn create(
+    State(state): State<AppState>,
+    Extension(auth): Extension<Authenticated>,
+    Json(payload): Json<WebAuthnCredentialRequest>,
+) -> Result<Json<Value>, ApiError> {
+    auth.require_user()?;
+    let config = state.config.get();
+    let user = current_user(&state, &auth).await?;
+    let keys = SecurityKey::find_by_user(&state.db, &user.id).await?;
+    match payload {
+        WebAuthnCredentialRequest::Generate { password } => {
+            check_password(&user, &password).await?;
+            let credentials: Vec<String> = keys.into_iter().map(|key| key.public_key).collect();
+            let (challenge, ticket) =
+                WebAuthn::start_registration(&config, &user.id, &user.username, &credentials)?;
+            Ok(Json(json!({
+                "ticket": ticket,
+                "challenge": challenge.to_string(),
+            })))
+        }
+        WebAuthnCredentialRequest::Create {
+            ticket,
+            credential,
+            name,
+        } => {
+            if name.trim().is_empty() {
+                return Err(ApiError::field(
+                    "name",
+                    "BASE_TYPE_REQUIRED",
+                    "This field is required",
+                ));
+            }
+            let registered = WebAuthn::finish_registration(&config, &user.id, &ticket, &credential)
+                .map_err(|_| ApiError::http(StatusCode::BAD_REQUEST, "Invalid security key"))?;
+            if keys.iter().any(|key| key.key_id == registered.key_id) {
+                return Err(ApiError::http(
+                    StatusCode::BAD_REQUEST,
+                    "This security key is already registered",
+                ));
+            }
+
+            let key = SecurityKey {
+                id: Snowflake::generate().to_string(),
+                user_id: Some(user.id.clone()),
+                key_id: registered.key_id,
+                public_key: registered.credential,
+                counter: 0,
+                name,
+            };
+            key.insert(&state.db).await?;
+            User::set_webauthn_enabled(&state.db, &user.id, true).await?;
+            Ok(Json(json!({ "name": key.name, "id": key.id })))
+        }
+    }
+}
+
+async fn remove(
+    State(state): State<AppState>,
+    Extension(auth): Extension<Authenticated>,
+    Path(key_id): Path<String>,
+) -> Result<StatusCode, ApiError> {
+    auth.require_user()?;
+    if !SecurityKey::delete(&state.db, &auth.user_id, &key_id).await? {
+        return Err(ApiError::http(
+            StatusCode::NOT_FOUND,
+            "Unknown security key",
+        ));
+    }
+    if SecurityKey::find_by_user(&state.db, &auth.user_id)
+        .await?
+        .is_empty()
+    {
+        User::set_webauthn_enabled(&state.db, &auth.user_id, false).await?;
+    }
+    Ok(StatusCode::NO_CONTENT)
+}
+
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route("/credentials", get(list).post(create))
+        .route("/credentials/:key_id", delete(remove))
+}
@@ -0,0 +1,173 @@
+//! Login sessions. Every token issued by a login belongs to a row of the
+//! `sessions` table, which the user can list as a device and revoke.
+
+use axum::{
+    async_trait,
+    extract::FromRequestParts,
+    http::{header, request::Parts},
+};
+use chrono::{DateTime, Utc};
+use data_encoding::BASE64;
+use events::{emit_event, Event, SESSIONS_REVOKED_EVENT};
+use rand::RngCore;
+use serde_json::{json, Value};
+use util::{generate_token, Snowflake};
+use util_db::{
+    entities::{Session, KIND_TOKEN},
+    types::{Json, Timestamp},
+};
+
+use crate::{error::ApiError, middleware::ClientIp, AppState};
+
+/// Longest user agent that is stored.
+const MAX_USER_AGENT: usize = 1024;
+
+/// Seconds between updates of the `last_seen` time of a session used from
+/// the same address.
+const LAST_SEEN_INTERVAL: i64 = 60;
+
+/// Describes the client a request was made by, to be recorded with the
+/// sessions it opens.
+#[derive(Debug, Clone)]
+pub struct ClientInfo {
+    pub ip: String,
+    pub user_agent: Option<String>,
+    /// `{client, os, version}`, the shape the TypeScript server stores for
+    /// gateway sessions, taken from the `X-Super-Properties` header official
+    /// clients send.
+    pub info: Value,
+}
+
+#[async_trait]
+impl FromRequestParts<AppState> for ClientInfo {
+    type Rejection = ApiError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &AppState) -> Result<Self, ApiError> {
+        let ClientIp(ip) = ClientIp::from_request_parts(parts, state).await?;
+        let user_agent = user_agent(&parts.headers);
+        let properties: Value = parts
+            .headers
+            .get("x-super-properties")
+            .and_then(|value| BASE64.decode(value.as_bytes()).ok())
+            .and_then(|json| serde_json::from_slice(&json).ok())
+            .unwrap_or_default();
+        Ok(Self {
+            ip: ip.to_string(),
+            user_agent,
+            info: json!({
+                "client": properties["browser"],
+                "os": properties["os"],
+                "version": properties["client_version"],
+            }),
+        })
+    }
+}
+
+/// The `User-Agent` header, cut to the length the database holds.
+pub fn user_agent(headers: &header::HeaderMap) -> Option<String> {
+    let value = headers.get(header::USER_AGENT)?.to_str().ok()?;
+    Some(value.chars().take(MAX_USER_AGENT).collect())
+}
+
+/// Open a login session for the user `user_id` and return its token.
+pub async fn start_session(
+    state: &AppState,
+    user_id: &str,
+    client: &ClientInfo,
+) -> Result<String, ApiError> {
+    let mut session_id = [0u8; 16];
+    rand::thread_rng().fill_bytes(&mut session_id);
+    let session = Session {
+        id: Snowflake::generate().to_string(),
+        user_id: Some(user_id.to_string()),
+        session_id: hex::encode(session_id),
+        activities: None,
+        client_info: Json(client.info.clone()),
+        client_status: Json(json!({})),
+        status: "offline".into(),
+        kind: KIND_TOKEN.into(),
+        ip: Some(client.ip.clone()),
+        user_agent: client.user_agent.clone(),
+        last_seen: Some(Timestamp::now()),
+    };
+    session.insert(&state.db).await?;
+    let secret = &state.config.get().security.jwt_secret;
+    Ok(generate_token(user_id, Some(&session.session_id), secret)?)
+}
+
+/// Record that the login `session` was used by the request `parts`. The row
+/// is only written when the address changed or the last use is a while ago.
+pub async fn touch_session(state: &AppState, session: &Session, parts: &mut Parts) {
+    let Ok(ClientIp(ip)) = ClientIp::from_request_parts(parts, state).await else {
+        return;
+    };
+    let ip = ip.to_string();
+    let recent = session
+        .last_seen
+        .is_some_and(|last_seen| (Utc::now() - *last_seen).num_seconds() < LAST_SEEN_INTERVAL);
+    if recent && session.ip.as_deref() == Some(ip.as_str()) {
+        return;
+    }
+
+    let db = state.db.clone();
+    let session_id = session.session_id.clone();
+    let user_agent = user_agent(&parts.headers);
+    tokio::spawn(async move {
+        if let Err(e) = Session::touch(&db, &session_id, &ip, user_agent.as_deref()).await {
+            eprintln!("[API] Failed to update session {session_id}: {e}");
+        }
+    });
+}
+
+/// Tell gateways to close the connections of the revoked logins
+/// `session_ids` of the user `user_id`.
+pub async fn announce_revoked(user_id: &str, session_ids: Vec<String>) {
+    if session_ids.is_empty() {
+        return;
+    }
+    announce(user_id, json!({ "session_ids": session_ids })).await;
+}
+
+async fn announce(user_id: &str, data: Value) {
+    let event = Event {
+        event: SESSIONS_REVOKED_EVENT.into(),
+        data,
+        guild_id: None,
+        channel_id: None,
+        user_id: Some(user_id.to_string()),
+    };
+    if let Err(e) = emit_event(event).await {
+        eprintln!("[API] Failed to announce revoked sessions of {user_id}: {e}");
+    }
+}
+
+/// Revoke the login `session_id` of the user `user_id`, returning whether it
+/// existed.
+pub async fn revoke_session(
+    state: &AppState,
+    user_id: &str,
+    session_id: &str,
+) -> Result<bool, ApiError> {
+    if !Session::delete_login(&state.db, user_id, session_id).await? {
+        return Ok(false);
+    }
+    announce_revoked(user_id, vec![session_id.to_string()]).await;
+    Ok(true)
+}
+
+/// Revoke every login of the user `user_id` after their `valid_tokens_since`
+/// was moved to `valid_since`. Connections identified with tokens of no
+/// login session are closed too if those tokens were issued before it.
+pub async fn revoke_all_sessions(
+    state: &AppState,
+    user_id: &str,
+    valid_since: DateTime<Utc>,
+) -> Result<(), ApiError> {
+    let session_ids = Session::delete_logins(&state.db, user_id).await?;
+    let data = json!({
+        "session_ids": session_ids,
+        "valid_tokens_since": valid_since.timestamp_millis(),
+    });
+    announce(user_id, data).await;
+    Ok(())
+}
@@ -0,0 +1,135 @@
+//! Helpers for the route tests: an in-memory database, users and requests
+//! made on their behalf.
+
+use axum::{
+    body::{to_bytes, Body},
+    http::{header::CONTENT_TYPE, Method, Request, StatusCode},
+    Extension, Router,
+};
+use config::Config;
+use serde_json::{json, Value};
+use tower::ServiceExt;
+use util::{Rights, Snowflake};
+use util_db::{
+    entities::{User, UserData, CHANNEL_GUILD_TEXT},
+    init_database,
+    types::{Bool, Json, SimpleArray, Timestamp},
+    Dialect,
+};
+
+use crate::{
+    guild::{create_guild, CreatedGuild},
+    middleware::Authenticated,
+    routes, AppState,
+};
+
+/// State backed by a new in-memory database.
+pub async fn state() -> AppState {
+    AppState {
+        db: init_database("sqlite::memory:").await.unwrap(),
+        config: Config::handle(),
+    }
+}
+
+/// Store a user named `username` and authenticate as them.
+pub async fn user(state: &AppState, username: &str) -> Authenticated {
+    user_with_id(state, &Snowflake::generate().to_string(), username).await
+}
+
+/// Store a user with the ID `id` and authenticate as them.
+pub async fn user_with_id(state: &AppState, id: &str, username: &str) -> Authenticated {
+    let user = User {
+        id: id.into(),
+        username: username.into(),
+        discriminator: "0001".into(),
+        avatar: None,
+        accent_color: None,
+        banner: None,
+        theme_colors: None,
+        pronouns: None,
+        phone: None,
+        desktop: Bool(false),
+        mobile: Bool(false),
+        premium: Bool(false),
+        premium_type: 0,
+        bot: Bool(false),
+        bio: String::new(),
+        system: Bool(false),
+        nsfw_allowed: Bool(true),
+        mfa_enabled: Bool(false),
+        webauthn_enabled: Bool(false),
+        totp_secret: None,
+        totp_last_ticket: None,
+        created_at: Timestamp::now(),
+        premium_since: None,
+        verified: Bool(true),
+        disabled: Bool(false),
+        deleted: Bool(false),
+        email: None,
+        flags: 0,
+        public_flags: 0,
+        purchased_flags: 0,
+        premium_usage_flags: 0,
+        rights: 0,
+        data: Json(UserData {
+            valid_tokens_since: *Timestamp::now(),
+            hash: None,
+        }),
+        fingerprints: SimpleArray(Vec::new()),
+        extended_settings: "{}".into(),
+        badge_ids: None,
+        settings_index: None,
+    };
+    user.insert(&state.db, Dialect::of(&state.db))
+        .await
+        .unwrap();
+    Authenticated {
+        user_id: user.id,
+        bot: false,
+        rights: Rights::SEND_MESSAGES | Rights::SELF_ADD_REACTIONS,
+        session_id: None,
+    }
+}
+
+/// A guild owned by `owner`, with the default channels.
+pub async fn guild(state: &AppState, owner: &Authenticated) -> CreatedGuild {
+    let payload = serde_json::from_value(json!({ "name": "Test" })).unwrap();
+    match create_guild(state, &owner.user_id, payload).await {
+        Ok(created) => created,
+        Err(_) => panic!("could not create the guild"),
+    }
+}
+
+/// The first text channel of `guild`.
+pub fn text_channel(guild: &CreatedGuild) -> String {
+    guild
+        .channels
+        .iter()
+        .find(|channel| channel.kind == CHANNEL_GUILD_TEXT)
+        .map(|channel| channel.id.clone())
+        .unwrap()
+}
+
+/// Make a request to the API as `auth`, with `body` as JSON. Returns the
+/// status and the JSON response, or null if there is none.
+pub async fn request(
+    state: &AppState,
+    auth: &Authenticated,
+    method: Method,
+    uri: &str,
+    body: Option<Value>,
+) -> (StatusCode, Value) {
+    let app: Router = routes::create_router()
+        .layer(Extension(auth.clone()))
+        .with_state(state.clone());
+    let request = Request::builder()
+        .method(method)
+        .uri(uri)
+        .header(CONTENT_TYPE, "application/json");
+    let body = body.map_or_else(Body::empty, |body| Body::from(body.to_string()));
+    let response = app.oneshot(request.body(body).unwrap()).await.unwrap();
+    let status = response.status();
+    let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    let body = serde_json::from_slice(&body).unwrap_or(Value::Null);
+    (status, body)
+}
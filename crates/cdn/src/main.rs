@@ -1,6 +1,6 @@
 //! CDN service for serving static assets.
 
-use std::{net::SocketAddr, path::Path, sync::Arc};
+use std::{net::SocketAddr, path::Path};
 
 use anyhow::Result;
 use axum::{
@@ -10,7 +10,8 @@ use axum::{
     routing::get,
     Router,
 };
-use config::Config;
+use config::{Config, ConfigHandle};
+use events::init_event;
 use tokio::{net::TcpListener, signal};
 use tower::ServiceBuilder;
 use tower_http::{
@@ -18,7 +19,7 @@ use tower_http::{
     trace::TraceLayer,
 };
 
-use util_db::{init_database, DbPool};
+use util_db::{init_config, init_database, watch_config, DbPool};
 
 mod routes;
 mod signature;
@@ -28,7 +29,7 @@ mod storage;
 #[derive(Clone)]
 pub struct AppState {
     pub storage: storage::ArcStorage,
-    pub config: Arc<Config>,
+    pub config: ConfigHandle,
 }
 
 #[tokio::main]
@@ -36,9 +37,11 @@ async fn main() -> Result<()> {
     tracing_subscriber::fmt::init();
 
     // Load configuration and database.
-    let config = Config::init().await;
     let database_url = std::env::var("DATABASE_URL").unwrap_or_else(|_| "sqlite::memory:".into());
     let db = init_database(&database_url).await?;
+    init_config(&db).await?;
+    init_event().await?;
+    watch_config(db.clone());
 
     // Run clean-up for any stale attachment signatures.
     cleanup_attachment_signatures(&db).await.ok();
@@ -48,7 +51,7 @@ async fn main() -> Result<()> {
 
     let state = AppState {
         storage,
-        config: config.clone(),
+        config: Config::handle(),
     };
 
     // Build application with routes and middleware.
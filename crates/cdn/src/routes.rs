@@ -8,7 +8,7 @@ use axum::{
 use infer::Infer;
 use serde::Serialize;
 use std::collections::HashMap;
-use uuid::Uuid;
+use util::Snowflake;
 
 use crate::{signature, AppState};
 
@@ -46,7 +46,7 @@ async fn upload_attachment(
         .get("signature")
         .and_then(|v| v.to_str().ok())
         .unwrap_or("");
-    if signature != state.config.security.request_signature {
+    if signature != state.config.get().security.request_signature {
         return Err(StatusCode::UNAUTHORIZED);
     }
 
@@ -58,7 +58,7 @@ async fn upload_attachment(
         .map_err(|_| StatusCode::BAD_REQUEST)?
     {
         if field.name() == Some("file") {
-            filename = field.file_name().map(|s| sanitize_filename::sanitize(s));
+            filename = field.file_name().map(sanitize_filename::sanitize);
             let data = field.bytes().await.map_err(|_| StatusCode::BAD_REQUEST)?;
             file_bytes = Some(data.to_vec());
             break;
@@ -68,7 +68,7 @@ async fn upload_attachment(
     let data = file_bytes.ok_or(StatusCode::BAD_REQUEST)?;
     let filename = filename.unwrap_or_else(|| "file".into());
 
-    let id = Uuid::new_v4().to_string();
+    let id = Snowflake::generate().to_string();
     let path = format!("attachments/{}/{}/{}", channel_id, id, filename);
     state
         .storage
@@ -89,6 +89,7 @@ async fn upload_attachment(
 
     let endpoint = state
         .config
+        .get()
         .cdn
         .endpoint
         .endpoint_public
@@ -117,7 +118,7 @@ async fn get_attachment(
 ) -> Result<Response, StatusCode> {
     let path = format!("attachments/{}/{}/{}", channel_id, id, filename);
 
-    if state.config.security.cdn_sign_urls {
+    if state.config.get().security.cdn_sign_urls {
         let ex = params.get("ex").ok_or(StatusCode::NOT_FOUND)?;
         let is = params.get("is").ok_or(StatusCode::NOT_FOUND)?;
         let hm = params.get("hm").ok_or(StatusCode::NOT_FOUND)?;
@@ -132,7 +133,7 @@ async fn get_attachment(
             hm,
             Some(&addr.ip().to_string()),
             ua,
-            &state.config,
+            &state.config.get(),
         ) {
             return Err(StatusCode::NOT_FOUND);
         }
@@ -179,7 +180,7 @@ async fn delete_attachment(
         .get("signature")
         .and_then(|v| v.to_str().ok())
         .unwrap_or("");
-    if signature != state.config.security.request_signature {
+    if signature != state.config.get().security.request_signature {
         return Err(StatusCode::UNAUTHORIZED);
     }
     let path = format!("attachments/{}/{}/{}", channel_id, id, filename);
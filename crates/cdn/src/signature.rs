@@ -32,6 +32,11 @@ pub fn has_valid_signature(
         return false;
     }
 
+    // An empty key would let anyone compute signatures.
+    if config.security.cdn_signature_key.is_empty() {
+        return false;
+    }
+
     let mut mac = match HmacSha256::new_from_slice(config.security.cdn_signature_key.as_bytes()) {
         Ok(m) => m,
         Err(_) => return false,
@@ -1,14 +1,19 @@
-use std::path::{Path, PathBuf};
+use std::{
+    future::Future,
+    path::{Path, PathBuf},
+    pin::Pin,
+};
 
 use anyhow::Result;
-use async_trait::async_trait;
+
+/// Future returned by the methods of [`Storage`].
+pub type StorageFuture<'a, T> = Pin<Box<dyn Future<Output = Result<T>> + Send + 'a>>;
 
 /// Abstraction over attachment storage backends.
-#[async_trait]
 pub trait Storage: Send + Sync {
-    async fn set(&self, path: &str, data: &[u8]) -> Result<()>;
-    async fn get(&self, path: &str) -> Result<Option<Vec<u8>>>;
-    async fn delete(&self, path: &str) -> Result<()>;
+    fn set<'a>(&'a self, path: &'a str, data: &'a [u8]) -> StorageFuture<'a, ()>;
+    fn get<'a>(&'a self, path: &'a str) -> StorageFuture<'a, Option<Vec<u8>>>;
+    fn delete<'a>(&'a self, path: &'a str) -> StorageFuture<'a, ()>;
 }
 
 /// Storage backend that keeps files on the local filesystem.
@@ -26,33 +31,38 @@ impl LocalStorage {
     }
 }
 
-#[async_trait]
 impl Storage for LocalStorage {
-    async fn set(&self, path: &str, data: &[u8]) -> Result<()> {
-        let full = self.resolve(path);
-        if let Some(parent) = full.parent() {
-            tokio::fs::create_dir_all(parent).await?;
-        }
-        tokio::fs::write(full, data).await?;
-        Ok(())
+    fn set<'a>(&'a self, path: &'a str, data: &'a [u8]) -> StorageFuture<'a, ()> {
+        Box::pin(async move {
+            let full = self.resolve(path);
+            if let Some(parent) = full.parent() {
+                tokio::fs::create_dir_all(parent).await?;
+            }
+            tokio::fs::write(full, data).await?;
+            Ok(())
+        })
     }
 
-    async fn get(&self, path: &str) -> Result<Option<Vec<u8>>> {
-        let full = self.resolve(path);
-        match tokio::fs::read(full).await {
-            Ok(data) => Ok(Some(data)),
-            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
-            Err(e) => Err(e.into()),
-        }
+    fn get<'a>(&'a self, path: &'a str) -> StorageFuture<'a, Option<Vec<u8>>> {
+        Box::pin(async move {
+            let full = self.resolve(path);
+            match tokio::fs::read(full).await {
+                Ok(data) => Ok(Some(data)),
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+                Err(e) => Err(e.into()),
+            }
+        })
     }
 
-    async fn delete(&self, path: &str) -> Result<()> {
-        let full = self.resolve(path);
-        match tokio::fs::remove_file(&full).await {
-            Ok(_) => Ok(()),
-            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
-            Err(e) => Err(e.into()),
-        }
+    fn delete<'a>(&'a self, path: &'a str) -> StorageFuture<'a, ()> {
+        Box::pin(async move {
+            let full = self.resolve(path);
+            match tokio::fs::remove_file(&full).await {
+                Ok(_) => Ok(()),
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+                Err(e) => Err(e.into()),
+            }
+        })
     }
 }
 
@@ -98,9 +108,8 @@ impl std::ops::Deref for ArcStorage {
 
 #[cfg(feature = "s3")]
 mod s3 {
-    use super::Storage;
+    use super::{Storage, StorageFuture};
     use anyhow::Result;
-    use async_trait::async_trait;
     use aws_sdk_s3::{types::ByteStream, Client, Region};
 
     pub struct S3Storage {
@@ -136,50 +145,55 @@ mod s3 {
         }
     }
 
-    #[async_trait]
     impl Storage for S3Storage {
-        async fn set(&self, path: &str, data: &[u8]) -> Result<()> {
-            self.client
-                .put_object()
-                .bucket(&self.bucket)
-                .key(self.key(path))
-                .body(ByteStream::from(data.to_owned()))
-                .send()
-                .await?;
-            Ok(())
+        fn set<'a>(&'a self, path: &'a str, data: &'a [u8]) -> StorageFuture<'a, ()> {
+            Box::pin(async move {
+                self.client
+                    .put_object()
+                    .bucket(&self.bucket)
+                    .key(self.key(path))
+                    .body(ByteStream::from(data.to_owned()))
+                    .send()
+                    .await?;
+                Ok(())
+            })
         }
 
-        async fn get(&self, path: &str) -> Result<Option<Vec<u8>>> {
-            match self
-                .client
-                .get_object()
-                .bucket(&self.bucket)
-                .key(self.key(path))
-                .send()
-                .await
-            {
-                Ok(obj) => {
-                    let data = obj.body.collect().await?.into_bytes().to_vec();
-                    Ok(Some(data))
-                }
-                Err(err) => {
-                    if err.is_not_found() {
-                        Ok(None)
-                    } else {
-                        Err(err.into())
+        fn get<'a>(&'a self, path: &'a str) -> StorageFuture<'a, Option<Vec<u8>>> {
+            Box::pin(async move {
+                match self
+                    .client
+                    .get_object()
+                    .bucket(&self.bucket)
+                    .key(self.key(path))
+                    .send()
+                    .await
+                {
+                    Ok(obj) => {
+                        let data = obj.body.collect().await?.into_bytes().to_vec();
+                        Ok(Some(data))
+                    }
+                    Err(err) => {
+                        if err.is_not_found() {
+                            Ok(None)
+                        } else {
+                            Err(err.into())
+                        }
                     }
                 }
-            }
+            })
         }
 
-        async fn delete(&self, path: &str) -> Result<()> {
-            self.client
-                .delete_object()
-                .bucket(&self.bucket)
-                .key(self.key(path))
-                .send()
-                .await?;
-            Ok(())
+        fn delete<'a>(&'a self, path: &'a str) -> StorageFuture<'a, ()> {
+            Box::pin(async move {
+                self.client
+                    .delete_object()
+                    .bucket(&self.bucket)
+                    .key(self.key(path))
+                    .send()
+                    .await?;
+                Ok(())
+            })
         }
     }
 
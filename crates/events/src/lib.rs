@@ -15,6 +15,18 @@ pub struct Event {
     pub user_id: Option<String>,
 }
 
+/// ID that `CONFIG_UPDATE` events are published under, shared by every
+/// service.
+pub const CONFIG_EVENT_ID: &str = "config";
+
+/// Internal event published under a user's ID when logins of that user are
+/// revoked, with the revoked session IDs as `{"session_ids": [...]}`.
+/// Gateways close the connections identified with their tokens. When the
+/// user's `valid_tokens_since` moved, it is included in milliseconds as
+/// `valid_tokens_since`, and connections identified with tokens issued
+/// before it are closed as well.
+pub const SESSIONS_REVOKED_EVENT: &str = "SESSIONS_REVOKED";
+
 static RABBIT_CONN: OnceCell<Connection> = OnceCell::const_new();
 static RABBIT_CH: OnceCell<Channel> = OnceCell::const_new();
 static LOCAL_TX: OnceCell<broadcast::Sender<Event>> = OnceCell::const_new();
@@ -24,7 +36,7 @@ pub async fn init_event() -> Result<()> {
         return Ok(());
     }
 
-    let cfg = config::Config::init().await;
+    let cfg = config::Config::init().await?;
     if let Some(host) = &cfg.rabbitmq.host {
         if let Ok(conn) = Connection::connect(host, ConnectionProperties::default()).await {
             let ch = conn.create_channel().await?;
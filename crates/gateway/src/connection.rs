@@ -1,20 +1,60 @@
 use std::collections::HashMap;
 use std::net::SocketAddr;
+use std::ops::RangeInclusive;
 
 use axum::extract::ws::{Message, WebSocket};
+use events::Cancel;
 use serde_json::json;
+use tokio::sync::mpsc;
+use util_db::entities::Session;
 use uuid::Uuid;
 
 use crate::{GatewayState, ConnectionInfo};
 use crate::error::GatewayError;
 use crate::opcodes::{self, Payload};
 
+/// Gateway versions clients may ask for.
+const API_VERSIONS: RangeInclusive<u8> = 6..=10;
+
+/// State of one socket, shared with the opcode handlers.
+pub struct Connection {
+    pub session_id: String,
+    pub addr: SocketAddr,
+    pub user_agent: Option<String>,
+    /// User the connection identified as.
+    pub user_id: Option<String>,
+    /// Closes the socket with the given error from outside the receive loop,
+    /// such as when the login session of its token is revoked.
+    pub close: mpsc::UnboundedSender<GatewayError>,
+    /// Stops listening for events once the socket is closed.
+    pub listener: Option<Cancel>,
+}
+
+/// Reject connections asking for a gateway version this server does not
+/// speak, with `v` like Discord clients or `version` like the TypeScript
+/// server reads it. Connections that ask for none get the current one.
+fn check_api_version(query: &HashMap<String, String>) -> Result<(), GatewayError> {
+    let Some(version) = query.get("v").or_else(|| query.get("version")) else {
+        return Ok(());
+    };
+    match version.parse::<u8>() {
+        Ok(version) if API_VERSIONS.contains(&version) => Ok(()),
+        _ => Err(GatewayError::InvalidApiVersion),
+    }
+}
+
 pub async fn handle_socket(
     mut socket: WebSocket,
     addr: SocketAddr,
+    user_agent: Option<String>,
     query: HashMap<String, String>,
     state: GatewayState,
 ) {
+    if let Err(err) = check_api_version(&query) {
+        let _ = socket.send(Message::Close(Some(err.close_frame()))).await;
+        return;
+    }
+
     let session_id = Uuid::new_v4().to_string();
 
     let mut shard = None;
@@ -34,20 +74,40 @@ pub async fn handle_socket(
     let total = state.connections.lock().await.len();
     println!("[Gateway] New connection from {addr}, session {session_id}, total {total}");
 
+    let (close, mut closed) = mpsc::unbounded_channel();
+    let mut conn = Connection {
+        session_id: session_id.clone(),
+        addr,
+        user_agent,
+        user_id: None,
+        close,
+        listener: None,
+    };
+
     let hello = json!({"op": 10, "d": {"heartbeat_interval": 30_000}});
     let _ = socket.send(Message::Text(hello.to_string())).await;
 
     loop {
-        let msg = match socket.recv().await {
-            Some(Ok(m)) => m,
-            _ => break,
+        let msg = tokio::select! {
+            msg = socket.recv() => match msg {
+                Some(Ok(m)) => m,
+                _ => break,
+            },
+            Some(err) = closed.recv() => {
+                let _ = socket
+                    .send(Message::Close(Some(err.close_frame())))
+                    .await;
+                break;
+            }
         };
 
         match msg {
             Message::Text(text) => {
                 match serde_json::from_str::<Payload>(&text) {
                     Ok(payload) => {
-                        if let Err(err) = opcodes::dispatch(&mut socket, &state, payload).await {
+                        let result =
+                            opcodes::dispatch(&mut socket, &state, &mut conn, payload).await;
+                        if let Err(err) = result {
                             let _ = socket
                                 .send(Message::Close(Some(err.close_frame())))
                                 .await;
@@ -75,6 +135,15 @@ pub async fn handle_socket(
         }
     }
 
+    if let Some(cancel) = conn.listener.take() {
+        cancel();
+    }
+    if conn.user_id.is_some() {
+        if let Err(e) = Session::delete_gateway(&state.db, &session_id).await {
+            eprintln!("[Gateway] Failed to delete session {session_id}: {e}");
+        }
+    }
+
     {
         let mut conns = state.connections.lock().await;
         conns.remove(&addr);
@@ -82,3 +151,28 @@ pub async fn handle_socket(
         println!("[Gateway] Connection closed from {addr}, total {total}");
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn query(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs
+            .iter()
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn unsupported_api_versions_are_rejected() {
+        assert!(check_api_version(&query(&[])).is_ok());
+        assert!(check_api_version(&query(&[("v", "9"), ("encoding", "json")])).is_ok());
+        assert!(check_api_version(&query(&[("version", "8")])).is_ok());
+        for version in ["5", "11", "nine", ""] {
+            assert!(matches!(
+                check_api_version(&query(&[("v", version)])),
+                Err(GatewayError::InvalidApiVersion)
+            ));
+        }
+    }
+}
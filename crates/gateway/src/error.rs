@@ -5,21 +5,30 @@ use thiserror::Error;
 
 #[derive(Debug, Error)]
 pub enum GatewayError {
+    #[error("unknown error")]
+    Unknown,
     #[error("decode error")]
     DecodeError,
     #[error("invalid api version")]
     InvalidApiVersion,
     #[error("unknown opcode {0}")]
     UnknownOpcode(u8),
+    #[error("authentication failed")]
+    AuthenticationFailed,
+    #[error("already authenticated")]
+    AlreadyAuthenticated,
 }
 
 impl GatewayError {
     pub fn close_frame(&self) -> CloseFrame<'static> {
         CloseFrame {
             code: match self {
+                GatewayError::Unknown => CloseCode::from(4000u16),
                 GatewayError::DecodeError => CloseCode::from(4002u16),
                 GatewayError::InvalidApiVersion => CloseCode::from(4012u16),
                 GatewayError::UnknownOpcode(_) => CloseCode::from(4001u16),
+                GatewayError::AuthenticationFailed => CloseCode::from(4004u16),
+                GatewayError::AlreadyAuthenticated => CloseCode::from(4005u16),
             },
             reason: Cow::from(self.to_string()),
         }
@@ -6,18 +6,19 @@ use axum::{
         ws::WebSocketUpgrade,
         ConnectInfo, Query, State,
     },
+    http::{header, HeaderMap},
     response::Response,
     routing::get,
     serve, Router,
 };
-use config::Config;
+use config::{Config, ConfigHandle};
 use events::init_event;
 use tokio::{
     net::TcpListener,
     signal,
     sync::{oneshot, Mutex},
 };
-use util_db::{close_database, init_database, DbPool};
+use util_db::{close_database, init_config, init_database, watch_config, DbPool};
 
 mod connection;
 mod error;
@@ -28,7 +29,7 @@ use connection::handle_socket;
 #[derive(Clone)]
 pub struct GatewayState {
     pub db: DbPool,
-    pub config: Arc<Config>,
+    pub config: ConfigHandle,
     pub connections: Arc<Mutex<HashMap<SocketAddr, ConnectionInfo>>>,
 }
 
@@ -57,15 +58,16 @@ impl GatewayServer {
     }
 
     pub async fn start(&mut self) -> Result<()> {
-        let config = Config::init().await;
         let database_url =
             std::env::var("DATABASE_URL").unwrap_or_else(|_| "sqlite::memory:".into());
         let db = init_database(&database_url).await?;
+        init_config(&db).await?;
         init_event().await?;
+        watch_config(db.clone());
 
         let state = GatewayState {
             db,
-            config,
+            config: Config::handle(),
             connections: Arc::new(Mutex::new(HashMap::new())),
         };
         self.state = Some(state.clone());
@@ -111,8 +113,13 @@ async fn ws_handler(
     ConnectInfo(addr): ConnectInfo<SocketAddr>,
     State(state): State<GatewayState>,
     Query(query): Query<HashMap<String, String>>,
+    headers: HeaderMap,
 ) -> Response {
-    ws.on_upgrade(move |socket| handle_socket(socket, addr, query, state))
+    let user_agent = headers
+        .get(header::USER_AGENT)
+        .and_then(|v| v.to_str().ok())
+        .map(|v| v.chars().take(1024).collect());
+    ws.on_upgrade(move |socket| handle_socket(socket, addr, user_agent, query, state))
 }
 
 #[tokio::main]
@@ -1,12 +1,23 @@
 use axum::extract::ws::{Message, WebSocket};
-use serde_json::{Value, json};
+use events::{listen_event, SESSIONS_REVOKED_EVENT};
+use serde_json::{json, Value};
+use util::{decode_token, Snowflake};
+use util_db::{
+    check_token,
+    entities::{Session, KIND_GATEWAY},
+    issued_before,
+    types::{Json, Timestamp},
+    TokenError,
+};
 
-use crate::{GatewayState};
+use crate::connection::Connection;
 use crate::error::GatewayError;
+use crate::GatewayState;
 
 pub async fn heartbeat(
     socket: &mut WebSocket,
     _state: &GatewayState,
+    _conn: &mut Connection,
     _data: Value,
 ) -> Result<(), GatewayError> {
     let ack = json!({"op": 11});
@@ -14,18 +25,114 @@ pub async fn heartbeat(
     Ok(())
 }
 
+/// Authenticate the connection and record its session. Connections are
+/// closed when the login session of their token is revoked, or when the
+/// user's `valid_tokens_since` moves past the token's issue time.
 pub async fn identify(
     _socket: &mut WebSocket,
-    _state: &GatewayState,
+    state: &GatewayState,
+    conn: &mut Connection,
     data: Value,
 ) -> Result<(), GatewayError> {
-    println!("[Gateway] Identify: {}", data);
+    if conn.user_id.is_some() {
+        return Err(GatewayError::AlreadyAuthenticated);
+    }
+    let token = data["token"]
+        .as_str()
+        .ok_or(GatewayError::AuthenticationFailed)?;
+    let secret = state.config.get().security.jwt_secret.clone();
+    let (user, login) = match check_token(&state.db, token, &secret).await {
+        Ok(checked) => checked,
+        Err(TokenError::Database(e)) => {
+            eprintln!("[Gateway] Failed to check token: {e}");
+            return Err(GatewayError::Unknown);
+        }
+        Err(_) => return Err(GatewayError::AuthenticationFailed),
+    };
+    let token = token.strip_prefix("Bot ").unwrap_or(token);
+    let iat = decode_token(token, &secret)
+        .map_err(|_| GatewayError::AuthenticationFailed)?
+        .iat;
+
+    let ip = conn.addr.ip().to_string();
+    let properties = &data["properties"];
+    let session = Session {
+        id: Snowflake::generate().to_string(),
+        user_id: Some(user.id.clone()),
+        session_id: conn.session_id.clone(),
+        activities: Some(Json(
+            data["presence"]["activities"]
+                .as_array()
+                .cloned()
+                .unwrap_or_default(),
+        )),
+        client_info: Json(json!({
+            "client": properties["browser"],
+            "os": properties["os"],
+            "version": 0,
+        })),
+        client_status: Json(json!({})),
+        status: data["presence"]["status"]
+            .as_str()
+            .unwrap_or("online")
+            .to_string(),
+        kind: KIND_GATEWAY.into(),
+        ip: Some(ip.clone()),
+        user_agent: conn.user_agent.clone(),
+        last_seen: Some(Timestamp::now()),
+    };
+    session.insert(&state.db).await.map_err(|e| {
+        eprintln!("[Gateway] Failed to save session {}: {e}", conn.session_id);
+        GatewayError::Unknown
+    })?;
+    conn.user_id = Some(user.id.clone());
+
+    if let Some(login) = &login {
+        let _ = Session::touch(
+            &state.db,
+            &login.session_id,
+            &ip,
+            conn.user_agent.as_deref(),
+        )
+        .await;
+    }
+    let session_id = login.map(|login| login.session_id);
+    let close = conn.close.clone();
+    let cancel = listen_event(&user.id, move |event| {
+        if event.event != SESSIONS_REVOKED_EVENT {
+            return;
+        }
+        let logged_out = session_id.as_ref().is_some_and(|session_id| {
+            event.data["session_ids"]
+                .as_array()
+                .is_some_and(|ids| ids.iter().any(|id| id.as_str() == Some(session_id)))
+        });
+        let expired = event.data["valid_tokens_since"]
+            .as_i64()
+            .and_then(chrono::DateTime::from_timestamp_millis)
+            .is_some_and(|valid_since| issued_before(iat, valid_since));
+        if logged_out || expired {
+            let _ = close.send(GatewayError::AuthenticationFailed);
+        }
+    })
+    .await
+    .map_err(|e| {
+        eprintln!("[Gateway] Failed to listen for events of {}: {e}", user.id);
+        GatewayError::Unknown
+    })?;
+    conn.listener = Some(cancel);
+
+    println!(
+        "[Gateway] Identified {}#{} on session {}",
+        user.username, user.discriminator, conn.session_id
+    );
     Ok(())
 }
 
 pub async fn resume(
     _socket: &mut WebSocket,
     _state: &GatewayState,
+    _conn: &mut Connection,
     _data: Value,
 ) -> Result<(), GatewayError> {
     println!("[Gateway] Resume received");
@@ -2,7 +2,7 @@ use axum::extract::ws::WebSocket;
 use serde::Deserialize;
 use serde_json::Value;
 
-use crate::{error::GatewayError, GatewayState};
+use crate::{connection::Connection, error::GatewayError, GatewayState};
 
 mod handlers;
 
@@ -16,12 +16,13 @@ pub struct Payload {
 pub async fn dispatch(
     socket: &mut WebSocket,
     state: &GatewayState,
+    conn: &mut Connection,
     payload: Payload,
 ) -> Result<(), GatewayError> {
     match payload.op {
-        1 => handlers::heartbeat(socket, state, payload.d).await,
-        2 => handlers::identify(socket, state, payload.d).await,
-        6 => handlers::resume(socket, state, payload.d).await,
+        1 => handlers::heartbeat(socket, state, conn, payload.d).await,
+        2 => handlers::identify(socket, state, conn, payload.d).await,
+        6 => handlers::resume(socket, state, conn, payload.d).await,
         _ => Err(GatewayError::UnknownOpcode(payload.op)),
     }
 }
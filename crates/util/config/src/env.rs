@@ -0,0 +1,132 @@
+//! Overrides from `SPACEBAR__`-prefixed environment variables.
+//!
+//! The rest of the variable name is the dotted key of a field with `__` in
+//! place of the dots, so `SPACEBAR__SECURITY__JWT_SECRET` sets
+//! `security.jwt_secret` and `SPACEBAR__LIMITS__RATE__ROUTES__AUTH__LOGIN`
+//! takes a JSON object for the whole `limits.rate.routes.auth.login` section.
+
+use serde_json::{Map, Value};
+
+use crate::{
+    pairs::{insert, snake_case},
+    Config, ConfigError,
+};
+
+/// Prefix of the environment variables read by [`Config::with_env`].
+pub const ENV_PREFIX: &str = "SPACEBAR__";
+
+impl Config {
+    /// Apply the overrides found in the environment on top of `self`.
+    pub fn with_env(&self) -> Result<Self, ConfigError> {
+        self.with_overrides(std::env::vars())
+    }
+
+    /// Apply `SPACEBAR__*` overrides from `vars`, ignoring other variables.
+    ///
+    /// Values are parsed as JSON, except for fields whose default is a
+    /// string, which take the value as is. A value that does not parse, or
+    /// parses to a type the field does not accept, is kept as a string, so
+    /// `SPACEBAR__EMAIL__SMTP__PASSWORD=12345678` sets a string password.
+    pub fn with_overrides<I>(&self, vars: I) -> Result<Self, ConfigError>
+    where
+        I: IntoIterator<Item = (String, String)>,
+    {
+        let defaults = serde_json::to_value(Self::default()).unwrap_or_default();
+        let schema = Self::json_schema();
+        let mut patch = Map::new();
+        for (name, raw) in vars {
+            let Some(key) = name.strip_prefix(ENV_PREFIX) else {
+                continue;
+            };
+            let key = key.to_ascii_lowercase().replace("__", ".");
+            let value = match (lookup(&defaults, &key), serde_json::from_str(&raw)) {
+                (Some(Value::String(_)), _) | (_, Err(_)) => Value::String(raw),
+                (_, Ok(value)) if !accepts(&schema, &key, &value) => Value::String(raw),
+                (_, Ok(value)) => value,
+            };
+            insert(&mut patch, &defaults, &key, value);
+        }
+
+        if patch.is_empty() {
+            return Ok(self.clone());
+        }
+        self.merged(&Value::Object(patch))
+    }
+}
+
+/// The default value at a dotted snake_case key.
+fn lookup<'a>(defaults: &'a Value, key: &str) -> Option<&'a Value> {
+    key.split('.')
+        .try_fold(defaults, |value, segment| match value {
+            Value::Object(map) => map
+                .iter()
+                .find(|(name, _)| snake_case(name) == segment)
+                .map(|(_, value)| value),
+            _ => None,
+        })
+}
+
+/// Whether the schema allows `value` at a dotted snake_case key. Keys it does
+/// not describe, and fields of any type, accept everything.
+fn accepts(schema: &Value, key: &str, value: &Value) -> bool {
+    let mut fields = vec![schema];
+    for segment in key.split('.') {
+        fields = fields
+            .into_iter()
+            .flat_map(|field| resolve(schema, field))
+            .filter_map(|field| field.get("properties")?.as_object())
+            .flat_map(|properties| {
+                properties
+                    .iter()
+                    .filter(|(name, _)| snake_case(name) == segment)
+                    .map(|(_, field)| field)
+            })
+            .collect();
+        if fields.is_empty() {
+            return true;
+        }
+    }
+
+    let mut types = Vec::new();
+    for field in fields.into_iter().flat_map(|field| resolve(schema, field)) {
+        match field.get("type") {
+            Some(Value::String(name)) => types.push(name.as_str()),
+            Some(Value::Array(names)) => types.extend(names.iter().filter_map(Value::as_str)),
+            _ => return true,
+        }
+    }
+    let name = match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(n) if n.is_f64() => "number",
+        Value::Number(_) => "integer",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    };
+    types.contains(&name) || (name == "integer" && types.contains(&"number"))
+}
+
+/// The schemas `field` stands for once references and combinations are
+/// followed.
+fn resolve<'a>(root: &'a Value, field: &'a Value) -> Vec<&'a Value> {
+    if let Some(reference) = field.get("$ref").and_then(Value::as_str) {
+        let name = reference.trim_start_matches("#/definitions/");
+        return root
+            .get("definitions")
+            .and_then(|definitions| definitions.get(name))
+            .map(|definition| resolve(root, definition))
+            .unwrap_or_default();
+    }
+    let combined: Vec<&Value> = ["allOf", "anyOf", "oneOf"]
+        .iter()
+        .filter_map(|combinator| field.get(*combinator)?.as_array())
+        .flatten()
+        .flat_map(|branch| resolve(root, branch))
+        .collect();
+    if combined.is_empty() {
+        vec![field]
+    } else {
+        combined
+    }
+}
@@ -1,70 +1,203 @@
-use serde::Deserialize;
-use std::sync::Arc;
-use tokio::sync::OnceCell;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::{
+    path::PathBuf,
+    sync::{Arc, OnceLock},
+};
+use tokio::sync::watch;
 
-static CONFIG: OnceCell<Arc<Config>> = OnceCell::const_new();
+mod env;
+mod pairs;
+mod schema;
+mod secrets;
+mod validate;
 
-#[derive(Debug, Deserialize, Clone, Default)]
+pub use env::ENV_PREFIX;
+pub use validate::{parse_duration, ConfigError, ConfigIssue};
+
+static CONFIG: OnceLock<watch::Sender<Arc<Config>>> = OnceLock::new();
+
+/// Spacebar server configuration, as stored in `config.json` and the
+/// `config` table.
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone, Default)]
 #[serde(default)]
 pub struct Config {
+    /// Public endpoint of the gateway.
     pub gateway: EndpointConfiguration,
+    /// Public endpoint of the CDN and file storage limits.
     pub cdn: CdnConfiguration,
+    /// Public endpoint and versions of the HTTP API.
     pub api: ApiConfiguration,
+    /// Name and description shown to clients.
     pub general: GeneralConfiguration,
+    /// Size and rate limits.
     pub limits: LimitsConfiguration,
+    /// Secrets, captcha, two-factor authentication and CDN URL signing.
     pub security: SecurityConfiguration,
     pub login: LoginConfiguration,
+    /// Who may create accounts and what they have to provide.
     pub register: RegisterConfiguration,
+    /// Voice regions.
     pub regions: RegionConfiguration,
+    /// Guild discovery and guilds new users join automatically.
     pub guild: GuildConfiguration,
     pub gif: GifConfiguration,
+    /// RabbitMQ connection used to send events between services.
     pub rabbitmq: RabbitMQConfiguration,
+    /// Kafka brokers used to send events between services.
     pub kafka: KafkaConfiguration,
     pub templates: TemplateConfiguration,
     pub metrics: MetricsConfiguration,
     pub sentry: SentryConfiguration,
+    /// Settings applied to new guilds and users.
     pub defaults: DefaultsConfiguration,
+    /// Tokens for third-party services.
     pub external: ExternalTokensConfiguration,
+    /// Provider used to send verification and password reset emails.
     pub email: EmailConfiguration,
+    /// Requirements for resetting a forgotten password.
     #[serde(rename = "passwordReset")]
     pub password_reset: PasswordResetConfiguration,
     pub user: UserConfiguration,
 }
 
 impl Config {
-    pub async fn init() -> Arc<Self> {
+    /// Load the configuration file, generate missing secrets, apply the
+    /// `SPACEBAR__*` environment overrides and install the result, unless
+    /// one was already loaded.
+    pub async fn init() -> Result<Arc<Self>, ConfigError> {
+        if let Some(tx) = CONFIG.get() {
+            return Ok(tx.borrow().clone());
+        }
+        let mut cfg = Self::from_file().await?.unwrap_or_default();
+        for key in cfg.generate_secrets() {
+            println!("[Config] Generated {key}, set it in the configuration file to keep it.");
+        }
+        let cfg = cfg.with_env()?;
+        Ok(Self::init_with(cfg).await)
+    }
+
+    /// Install `config` as the process-wide configuration, unless one was
+    /// already loaded.
+    pub async fn init_with(config: Self) -> Arc<Self> {
         CONFIG
-            .get_or_init(|| async {
-                let path = std::env::var("CONFIG_PATH").unwrap_or_else(|_| "config.json".to_string());
-                let cfg = match tokio::fs::read_to_string(&path).await {
-                    Ok(content) => serde_json::from_str(&content).unwrap_or_default(),
-                    Err(_) => Self::default(),
-                };
-                Arc::new(cfg)
-            })
-            .await
+            .get_or_init(|| watch::Sender::new(Arc::new(config)))
+            .borrow()
             .clone()
     }
+
+    /// The current configuration, or the defaults before [`Config::init`].
+    pub fn get() -> Arc<Self> {
+        CONFIG
+            .get()
+            .map(|tx| tx.borrow().clone())
+            .unwrap_or_default()
+    }
+
+    /// Replace the process-wide configuration and notify every
+    /// [`ConfigHandle`].
+    pub fn set(config: Self) -> Arc<Self> {
+        let config = Arc::new(config);
+        sender().send_replace(config.clone());
+        config
+    }
+
+    /// Handle that always reads the latest configuration.
+    pub fn handle() -> ConfigHandle {
+        ConfigHandle(sender().subscribe())
+    }
+
+    /// Location of the configuration file: `CONFIG_PATH` or `config.json`.
+    pub fn path() -> PathBuf {
+        std::env::var_os("CONFIG_PATH")
+            .map(PathBuf::from)
+            .unwrap_or_else(|| PathBuf::from("config.json"))
+    }
+
+    /// Read `config.json`, or the file named by `CONFIG_PATH`, if it exists.
+    pub async fn from_file() -> Result<Option<Self>, ConfigError> {
+        match Self::read_file().await? {
+            Some(value) => Self::parse_logged(value).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// Raw JSON of the configuration file, if it exists.
+    pub async fn read_file() -> Result<Option<serde_json::Value>, ConfigError> {
+        let path = Self::path();
+        let error = |reason: String| {
+            ConfigError(vec![ConfigIssue {
+                path: path.display().to_string(),
+                reason,
+            }])
+        };
+        match tokio::fs::read_to_string(&path).await {
+            Ok(content) => serde_json::from_str(&content)
+                .map(Some)
+                .map_err(|e| error(e.to_string())),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(error(e.to_string())),
+        }
+    }
+
+    /// Copy of this configuration with the fields present in `patch`, a
+    /// partial configuration in the file format, replaced.
+    pub fn merged(&self, patch: &serde_json::Value) -> Result<Self, ConfigError> {
+        fn merge(target: &mut serde_json::Value, patch: &serde_json::Value) {
+            match (target, patch) {
+                (serde_json::Value::Object(target), serde_json::Value::Object(patch)) => {
+                    for (key, value) in patch {
+                        merge(target.entry(key.clone()).or_insert(serde_json::Value::Null), value);
+                    }
+                }
+                (target, patch) => *target = patch.clone(),
+            }
+        }
+
+        let mut value = serde_json::to_value(self).unwrap_or_default();
+        merge(&mut value, patch);
+        Self::parse_logged(value)
+    }
+
+    /// [`Config::parse`], printing a warning for each unknown key.
+    pub(crate) fn parse_logged(value: serde_json::Value) -> Result<Self, ConfigError> {
+        for warning in Self::unknown_keys(&value) {
+            eprintln!("[Config] Warning: {warning}");
+        }
+        Self::parse(value)
+    }
 }
 
-#[derive(Debug, Deserialize, Clone)]
+fn sender() -> &'static watch::Sender<Arc<Config>> {
+    CONFIG.get_or_init(|| watch::Sender::new(Arc::default()))
+}
+
+/// Cloneable view of the process-wide configuration that follows reloads.
+#[derive(Debug, Clone)]
+pub struct ConfigHandle(watch::Receiver<Arc<Config>>);
+
+impl ConfigHandle {
+    /// Snapshot of the current configuration.
+    pub fn get(&self) -> Arc<Config> {
+        self.0.borrow().clone()
+    }
+
+    /// Wait until the configuration is replaced. Returns `false` once no
+    /// more changes can happen.
+    pub async fn changed(&mut self) -> bool {
+        self.0.changed().await.is_ok()
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone, Default)]
 #[serde(default)]
 pub struct EndpointConfiguration {
     pub endpoint_client: Option<String>,
     pub endpoint_private: Option<String>,
     pub endpoint_public: Option<String>,
 }
-impl Default for EndpointConfiguration {
-    fn default() -> Self {
-        Self {
-            endpoint_client: None,
-            endpoint_private: None,
-            endpoint_public: None,
-        }
-    }
-}
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone)]
 #[serde(default)]
 pub struct CdnConfiguration {
     #[serde(flatten)]
@@ -86,7 +219,7 @@ impl Default for CdnConfiguration {
     }
 }
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone)]
 #[serde(default)]
 pub struct ApiConfiguration {
     pub default_version: String,
@@ -103,7 +236,7 @@ impl Default for ApiConfiguration {
     }
 }
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone)]
 #[serde(default)]
 pub struct GeneralConfiguration {
     pub instance_name: String,
@@ -134,7 +267,7 @@ impl Default for GeneralConfiguration {
     }
 }
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone)]
 #[serde(default)]
 pub struct GifConfiguration {
     pub enabled: bool,
@@ -151,35 +284,25 @@ impl Default for GifConfiguration {
     }
 }
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone, Default)]
 #[serde(default)]
 pub struct RabbitMQConfiguration {
     pub host: Option<String>,
 }
-impl Default for RabbitMQConfiguration {
-    fn default() -> Self {
-        Self { host: None }
-    }
-}
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone, Default)]
 #[serde(default)]
 pub struct KafkaConfiguration {
     pub brokers: Option<Vec<KafkaBroker>>,
 }
-impl Default for KafkaConfiguration {
-    fn default() -> Self {
-        Self { brokers: None }
-    }
-}
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone)]
 pub struct KafkaBroker {
     pub ip: String,
     pub port: u16,
 }
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone)]
 #[serde(default)]
 pub struct TemplateConfiguration {
     pub enabled: bool,
@@ -198,7 +321,7 @@ impl Default for TemplateConfiguration {
     }
 }
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone)]
 #[serde(default)]
 pub struct MetricsConfiguration {
     pub timeout: u32,
@@ -209,7 +332,7 @@ impl Default for MetricsConfiguration {
     }
 }
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone)]
 #[serde(default)]
 pub struct SentryConfiguration {
     pub enabled: bool,
@@ -229,22 +352,14 @@ impl Default for SentryConfiguration {
     }
 }
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone, Default)]
 #[serde(default)]
 pub struct DefaultsConfiguration {
     pub guild: GuildDefaults,
     pub user: UserDefaults,
 }
-impl Default for DefaultsConfiguration {
-    fn default() -> Self {
-        Self {
-            guild: GuildDefaults::default(),
-            user: UserDefaults::default(),
-        }
-    }
-}
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone)]
 #[serde(default)]
 pub struct GuildDefaults {
     pub max_presences: u32,
@@ -265,7 +380,7 @@ impl Default for GuildDefaults {
     }
 }
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone)]
 #[serde(default)]
 pub struct UserDefaults {
     pub premium: bool,
@@ -282,18 +397,13 @@ impl Default for UserDefaults {
     }
 }
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone, Default)]
 #[serde(default)]
 pub struct ExternalTokensConfiguration {
     pub twitter: Option<String>,
 }
-impl Default for ExternalTokensConfiguration {
-    fn default() -> Self {
-        Self { twitter: None }
-    }
-}
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone, Default)]
 #[serde(default)]
 pub struct EmailConfiguration {
     pub provider: Option<String>,
@@ -302,21 +412,10 @@ pub struct EmailConfiguration {
     pub mailgun: MailGunConfiguration,
     pub mailjet: MailJetConfiguration,
     pub sendgrid: SendGridConfiguration,
-}
-impl Default for EmailConfiguration {
-    fn default() -> Self {
-        Self {
-            provider: None,
-            sender_address: None,
-            smtp: SMTPConfiguration::default(),
-            mailgun: MailGunConfiguration::default(),
-            mailjet: MailJetConfiguration::default(),
-            sendgrid: SendGridConfiguration::default(),
-        }
-    }
+    pub file: FileEmailConfiguration,
 }
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone, Default)]
 #[serde(default)]
 pub struct SMTPConfiguration {
     pub host: Option<String>,
@@ -325,73 +424,49 @@ pub struct SMTPConfiguration {
     pub username: Option<String>,
     pub password: Option<String>,
 }
-impl Default for SMTPConfiguration {
-    fn default() -> Self {
-        Self {
-            host: None,
-            port: None,
-            secure: None,
-            username: None,
-            password: None,
-        }
-    }
-}
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone, Default)]
 #[serde(default)]
 pub struct MailGunConfiguration {
     pub api_key: Option<String>,
     pub domain: Option<String>,
-}
-impl Default for MailGunConfiguration {
-    fn default() -> Self {
-        Self {
-            api_key: None,
-            domain: None,
-        }
-    }
+    /// API base URL, e.g. `https://api.eu.mailgun.net` for the EU region.
+    pub endpoint: Option<String>,
 }
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone, Default)]
 #[serde(default)]
 pub struct MailJetConfiguration {
     pub api_key: Option<String>,
     pub api_secret: Option<String>,
-}
-impl Default for MailJetConfiguration {
-    fn default() -> Self {
-        Self {
-            api_key: None,
-            api_secret: None,
-        }
-    }
+    /// API base URL, `https://api.mailjet.com` if unset.
+    pub endpoint: Option<String>,
 }
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone, Default)]
 #[serde(default)]
 pub struct SendGridConfiguration {
     pub api_key: Option<String>,
+    /// API base URL, e.g. `https://api.eu.sendgrid.com` for EU subusers.
+    pub endpoint: Option<String>,
 }
-impl Default for SendGridConfiguration {
-    fn default() -> Self {
-        Self { api_key: None }
-    }
+
+/// The `file` provider, which stores messages in a local maildir instead of
+/// sending them. Meant for development.
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone, Default)]
+#[serde(default)]
+pub struct FileEmailConfiguration {
+    /// Maildir to deliver into, `emails` if unset.
+    pub path: Option<String>,
 }
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone, Default)]
 #[serde(default)]
 pub struct PasswordResetConfiguration {
     pub require_captcha: bool,
 }
-impl Default for PasswordResetConfiguration {
-    fn default() -> Self {
-        Self {
-            require_captcha: false,
-        }
-    }
-}
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone)]
 #[serde(default)]
 pub struct UserConfiguration {
     pub blocked_contains: Vec<String>,
@@ -406,7 +481,7 @@ impl Default for UserConfiguration {
     }
 }
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone)]
 #[serde(default)]
 pub struct RegionConfiguration {
     pub default: String,
@@ -423,7 +498,7 @@ impl Default for RegionConfiguration {
     }
 }
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone)]
 #[serde(default)]
 pub struct Region {
     pub id: String,
@@ -448,7 +523,7 @@ impl Default for Region {
     }
 }
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone)]
 #[serde(default)]
 pub struct Location {
     pub latitude: f64,
@@ -463,7 +538,7 @@ impl Default for Location {
     }
 }
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone, Default)]
 #[serde(default)]
 pub struct GuildConfiguration {
     pub discovery: DiscoveryConfiguration,
@@ -472,17 +547,8 @@ pub struct GuildConfiguration {
     #[serde(rename = "defaultFeatures")]
     pub default_features: Vec<String>,
 }
-impl Default for GuildConfiguration {
-    fn default() -> Self {
-        Self {
-            discovery: DiscoveryConfiguration::default(),
-            auto_join: AutoJoinConfiguration::default(),
-            default_features: Vec::new(),
-        }
-    }
-}
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone)]
 #[serde(default)]
 pub struct DiscoveryConfiguration {
     pub show_all_guilds: bool,
@@ -501,7 +567,7 @@ impl Default for DiscoveryConfiguration {
     }
 }
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone)]
 #[serde(default)]
 pub struct AutoJoinConfiguration {
     pub enabled: bool,
@@ -519,22 +585,14 @@ impl Default for AutoJoinConfiguration {
     }
 }
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone, Default)]
 #[serde(default)]
 pub struct LoginConfiguration {
     pub require_captcha: bool,
     pub require_verification: bool,
 }
-impl Default for LoginConfiguration {
-    fn default() -> Self {
-        Self {
-            require_captcha: false,
-            require_verification: false,
-        }
-    }
-}
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone)]
 #[serde(default)]
 pub struct RegisterConfiguration {
     pub email: RegistrationEmailConfiguration,
@@ -578,7 +636,7 @@ impl Default for RegisterConfiguration {
     }
 }
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone)]
 #[serde(default)]
 pub struct RegistrationEmailConfiguration {
     pub required: bool,
@@ -597,7 +655,7 @@ impl Default for RegistrationEmailConfiguration {
     }
 }
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone)]
 #[serde(default)]
 pub struct DateOfBirthConfiguration {
     pub required: bool,
@@ -612,7 +670,7 @@ impl Default for DateOfBirthConfiguration {
     }
 }
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone)]
 #[serde(default)]
 pub struct PasswordConfiguration {
     pub required: bool,
@@ -637,7 +695,7 @@ impl Default for PasswordConfiguration {
     }
 }
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone)]
 #[serde(default)]
 pub struct SecurityConfiguration {
     pub captcha: CaptchaConfiguration,
@@ -645,12 +703,17 @@ pub struct SecurityConfiguration {
     pub two_factor: TwoFactorConfiguration,
     #[serde(rename = "autoUpdate")]
     pub auto_update: AutoUpdate,
+    /// Generated on first boot if empty.
     #[serde(rename = "requestSignature")]
     pub request_signature: String,
+    /// Key that signs user tokens. Generated on first boot if empty.
     #[serde(rename = "jwtSecret")]
     pub jwt_secret: String,
+    /// Header proxies put the client address in, e.g. `X-Forwarded-For`.
     #[serde(rename = "forwardedFor")]
     pub forwarded_for: Option<String>,
+    /// Proxies whose `forwardedFor` header is believed: `true` for all, a
+    /// number of hops, or addresses and CIDR ranges.
     #[serde(rename = "trustedProxies")]
     pub trusted_proxies: Option<serde_json::Value>,
     #[serde(rename = "ipdataApiKey")]
@@ -663,8 +726,10 @@ pub struct SecurityConfiguration {
     pub default_registration_token_expiration: u64,
     #[serde(rename = "cdnSignUrls")]
     pub cdn_sign_urls: bool,
+    /// Key that signs CDN URLs. Generated on first boot if empty.
     #[serde(rename = "cdnSignatureKey")]
     pub cdn_signature_key: String,
+    /// How long signed CDN URLs stay valid, e.g. `24h` or `30m`.
     #[serde(rename = "cdnSignatureDuration")]
     pub cdn_signature_duration: String,
     #[serde(rename = "cdnSignatureIncludeIp")]
@@ -697,26 +762,20 @@ impl Default for SecurityConfiguration {
     }
 }
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone, Default)]
 #[serde(default)]
 pub struct CaptchaConfiguration {
     pub enabled: bool,
+    /// `hcaptcha`, `recaptcha` or `turnstile`.
     pub service: Option<String>,
     pub sitekey: Option<String>,
     pub secret: Option<String>,
-}
-impl Default for CaptchaConfiguration {
-    fn default() -> Self {
-        Self {
-            enabled: false,
-            service: None,
-            sitekey: None,
-            secret: None,
-        }
-    }
+    /// Verification URL replacing the service's siteverify endpoint, e.g.
+    /// `https://www.recaptcha.net/recaptcha/api/siteverify`.
+    pub endpoint: Option<String>,
 }
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone)]
 #[serde(default)]
 pub struct TwoFactorConfiguration {
     #[serde(rename = "generateBackupCodes")]
@@ -730,7 +789,7 @@ impl Default for TwoFactorConfiguration {
     }
 }
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone)]
 #[serde(untagged)]
 pub enum AutoUpdate {
     Bool(bool),
@@ -742,7 +801,7 @@ impl Default for AutoUpdate {
     }
 }
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone, Default)]
 #[serde(default)]
 pub struct LimitsConfiguration {
     pub user: UserLimits,
@@ -753,20 +812,8 @@ pub struct LimitsConfiguration {
     #[serde(rename = "absoluteRate")]
     pub absolute_rate: GlobalRateLimits,
 }
-impl Default for LimitsConfiguration {
-    fn default() -> Self {
-        Self {
-            user: UserLimits::default(),
-            guild: GuildLimits::default(),
-            message: MessageLimits::default(),
-            channel: ChannelLimits::default(),
-            rate: RateLimits::default(),
-            absolute_rate: GlobalRateLimits::default(),
-        }
-    }
-}
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone)]
 #[serde(default)]
 pub struct UserLimits {
     pub max_guilds: u32,
@@ -785,7 +832,7 @@ impl Default for UserLimits {
     }
 }
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone)]
 #[serde(default)]
 pub struct GuildLimits {
     pub max_roles: u32,
@@ -808,7 +855,7 @@ impl Default for GuildLimits {
     }
 }
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone)]
 #[serde(default)]
 pub struct MessageLimits {
     pub max_characters: u32,
@@ -831,7 +878,7 @@ impl Default for MessageLimits {
     }
 }
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone)]
 #[serde(default)]
 pub struct ChannelLimits {
     pub max_pins: u32,
@@ -848,7 +895,7 @@ impl Default for ChannelLimits {
     }
 }
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone)]
 #[serde(default)]
 pub struct RateLimits {
     pub enabled: bool,
@@ -881,27 +928,22 @@ impl Default for RateLimits {
     }
 }
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone, Default)]
 #[serde(default)]
 pub struct RateLimitOptions {
+    /// Requests allowed per window for bots, `count` if unset.
     pub bot: Option<u32>,
+    /// Requests allowed per window.
     pub count: u32,
+    /// Length of the window in seconds.
     pub window: u32,
+    /// Count requests per IP address only, not per user. The key is spelled
+    /// `onyIp` like in the TypeScript server.
     #[serde(rename = "onyIp")]
     pub ony_ip: Option<bool>,
 }
-impl Default for RateLimitOptions {
-    fn default() -> Self {
-        Self {
-            bot: None,
-            count: 0,
-            window: 0,
-            ony_ip: None,
-        }
-    }
-}
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone)]
 #[serde(default)]
 pub struct RouteRateLimit {
     pub guild: RateLimitOptions,
@@ -932,7 +974,7 @@ impl Default for RouteRateLimit {
     }
 }
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone)]
 #[serde(default)]
 pub struct AuthRateLimit {
     pub login: RateLimitOptions,
@@ -955,7 +997,7 @@ impl Default for AuthRateLimit {
     }
 }
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone)]
 #[serde(default)]
 pub struct GlobalRateLimits {
     pub register: GlobalRateLimit,
@@ -979,7 +1021,7 @@ impl Default for GlobalRateLimits {
     }
 }
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone)]
 #[serde(default)]
 pub struct GlobalRateLimit {
     pub limit: u32,
@@ -0,0 +1,23 @@
+//! Command line tools for the configuration.
+//!
+//! ```text
+//! config schema    Print the JSON Schema of config.json
+//! ```
+
+use std::process::ExitCode;
+
+use config::Config;
+
+fn main() -> ExitCode {
+    match std::env::args().nth(1).as_deref() {
+        Some("schema") => {
+            let schema = serde_json::to_string_pretty(&Config::json_schema()).unwrap_or_default();
+            println!("{schema}");
+            ExitCode::SUCCESS
+        }
+        _ => {
+            eprintln!("Usage: config schema");
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,168 @@
+//! Conversion between [`Config`] and the key/value rows of the `config` table.
+//!
+//! Keys are the dotted path of snake_case field names, e.g.
+//! `security.jwt_secret`. Every field that is not itself a section is stored
+//! as one row holding its JSON value, lists included.
+
+use serde_json::{Map, Value};
+
+use crate::{Config, ConfigError};
+
+impl Config {
+    /// Flatten into `(key, value)` pairs, one per field.
+    pub fn to_pairs(&self) -> Vec<(String, Value)> {
+        let value = serde_json::to_value(self).unwrap_or_default();
+        let defaults = serde_json::to_value(Self::default()).unwrap_or_default();
+        let mut pairs = Vec::new();
+        flatten(&value, &defaults, String::new(), &mut pairs);
+        pairs
+    }
+
+    /// Rebuild from `(key, value)` pairs. Fields without a pair keep their
+    /// default value.
+    pub fn from_pairs<I>(pairs: I) -> Result<Self, ConfigError>
+    where
+        I: IntoIterator<Item = (String, Value)>,
+    {
+        let defaults = serde_json::to_value(Self::default()).unwrap_or_default();
+        let mut root = Map::new();
+        for (key, value) in pairs {
+            insert(&mut root, &defaults, &key, value);
+        }
+        Self::parse_logged(Value::Object(root))
+    }
+
+    /// Rebuild from the pairs the TypeScript server stores, keyed by the
+    /// `_`-separated path of camelCase field names, e.g. `security_jwtSecret`.
+    /// It splits lists and free-form objects into one pair per item as well.
+    pub fn from_typescript_pairs<I>(pairs: I) -> Result<Self, ConfigError>
+    where
+        I: IntoIterator<Item = (String, Value)>,
+    {
+        let root = serde_json::to_value(Self::default()).unwrap_or_default();
+        let mut fields = Map::new();
+        for (key, value) in pairs {
+            let mut rest: Vec<&str> = key.split('_').collect();
+            let mut path = Vec::new();
+            let mut defaults = &root;
+            // Follow the sections of the default configuration; whatever is
+            // left of the key belongs inside the value of a single field.
+            while let (Value::Object(map), Some(segment)) = (defaults, rest.first()) {
+                let segment = snake_case(segment);
+                defaults = map
+                    .iter()
+                    .find(|(name, _)| snake_case(name) == segment)
+                    .map_or(&Value::Null, |(_, defaults)| defaults);
+                path.push(segment);
+                rest.remove(0);
+            }
+            let field = fields.entry(path.join(".")).or_insert(Value::Null);
+            nest(field, &rest, value);
+        }
+        for value in fields.values_mut() {
+            restore_lists(value);
+        }
+        Self::from_pairs(fields)
+    }
+}
+
+/// `jwtSecret` -> `jwt_secret`.
+pub(crate) fn snake_case(key: &str) -> String {
+    let mut out = String::with_capacity(key.len() + 4);
+    for c in key.chars() {
+        if c.is_ascii_uppercase() {
+            out.push('_');
+            out.push(c.to_ascii_lowercase());
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
+
+/// Recurse only where the default configuration has a section, so free-form
+/// JSON values are stored whole.
+fn flatten(value: &Value, defaults: &Value, prefix: String, pairs: &mut Vec<(String, Value)>) {
+    let (Value::Object(map), Value::Object(default_map)) = (value, defaults) else {
+        pairs.push((prefix, value.clone()));
+        return;
+    };
+    for (key, value) in map {
+        let path = if prefix.is_empty() {
+            snake_case(key)
+        } else {
+            format!("{prefix}.{}", snake_case(key))
+        };
+        flatten(
+            value,
+            default_map.get(key).unwrap_or(&Value::Null),
+            path,
+            pairs,
+        );
+    }
+}
+
+pub(crate) fn insert(map: &mut Map<String, Value>, defaults: &Value, key: &str, value: Value) {
+    let (segment, rest) = match key.split_once('.') {
+        Some((segment, rest)) => (segment, Some(rest)),
+        None => (key, None),
+    };
+    // Map the snake_case segment back to the serialised field name.
+    let (name, defaults) = match defaults {
+        Value::Object(default_map) => default_map
+            .iter()
+            .find(|(name, _)| snake_case(name) == segment)
+            .map(|(name, defaults)| (name.clone(), defaults))
+            .unwrap_or((segment.to_string(), &Value::Null)),
+        _ => (segment.to_string(), &Value::Null),
+    };
+
+    match rest {
+        None => {
+            map.insert(name, value);
+        }
+        Some(rest) => {
+            let entry = map.entry(name).or_insert_with(|| Value::Object(Map::new()));
+            if !entry.is_object() {
+                *entry = Value::Object(Map::new());
+            }
+            if let Value::Object(child) = entry {
+                insert(child, defaults, rest, value);
+            }
+        }
+    }
+}
+
+/// Set `value` at `path` inside `target`, creating objects along the way.
+fn nest(target: &mut Value, path: &[&str], value: Value) {
+    let Some((segment, rest)) = path.split_first() else {
+        *target = value;
+        return;
+    };
+    if !target.is_object() {
+        *target = Value::Object(Map::new());
+    }
+    if let Value::Object(map) = target {
+        nest(map.entry(*segment).or_insert(Value::Null), rest, value);
+    }
+}
+
+/// Turn objects keyed by list indexes back into lists.
+fn restore_lists(value: &mut Value) {
+    let Value::Object(map) = value else {
+        return;
+    };
+    map.values_mut().for_each(restore_lists);
+    let mut items = Vec::with_capacity(map.len());
+    for (key, item) in map.iter() {
+        match key.parse::<usize>() {
+            Ok(index) => items.push((index, item.clone())),
+            Err(_) => return,
+        }
+    }
+    if items.is_empty() {
+        return;
+    }
+    items.sort_by_key(|(index, _)| *index);
+    *value = Value::Array(items.into_iter().map(|(_, item)| item).collect());
+}
@@ -0,0 +1,14 @@
+//! JSON Schema of the configuration file.
+
+use schemars::schema_for;
+use serde_json::Value;
+
+use crate::Config;
+
+impl Config {
+    /// JSON Schema describing `config.json`, with the default value and
+    /// documentation of every field.
+    pub fn json_schema() -> Value {
+        serde_json::to_value(schema_for!(Config)).unwrap_or_default()
+    }
+}
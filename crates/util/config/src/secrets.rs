@@ -0,0 +1,44 @@
+//! Generation of the secrets a fresh install starts without.
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use rand::{rngs::OsRng, RngCore};
+
+use crate::Config;
+
+impl Config {
+    /// Fill `security.jwt_secret`, `security.request_signature` and
+    /// `security.cdn_signature_key` with random values if they are empty,
+    /// returning the keys that were generated.
+    ///
+    /// Uses the same lengths as the TypeScript server: 256 random bytes for
+    /// the JWT secret and 32 for the others, base64 encoded.
+    pub fn generate_secrets(&mut self) -> Vec<&'static str> {
+        let security = &mut self.security;
+        let mut generated = Vec::new();
+        for (key, secret, len) in [
+            ("security.jwt_secret", &mut security.jwt_secret, 256),
+            (
+                "security.request_signature",
+                &mut security.request_signature,
+                32,
+            ),
+            (
+                "security.cdn_signature_key",
+                &mut security.cdn_signature_key,
+                32,
+            ),
+        ] {
+            if secret.is_empty() {
+                *secret = random_secret(len);
+                generated.push(key);
+            }
+        }
+        generated
+    }
+}
+
+fn random_secret(len: usize) -> String {
+    let mut bytes = vec![0; len];
+    OsRng.fill_bytes(&mut bytes);
+    STANDARD.encode(bytes)
+}
@@ -0,0 +1,251 @@
+//! Strict parsing of configuration sources.
+//!
+//! Every field with the wrong type is reported with its path instead of
+//! silently falling back to the defaults, unknown keys produce warnings and
+//! values that deserialise but make no sense are rejected.
+
+use std::{fmt, time::Duration};
+
+use serde_json::Value;
+use serde_path_to_error::Segment;
+
+use crate::{pairs::snake_case, Config, RateLimitOptions};
+
+/// A problem with one configuration field.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfigIssue {
+    /// Dotted key of the field, e.g. `security.jwt_secret`.
+    pub path: String,
+    pub reason: String,
+}
+
+impl fmt::Display for ConfigIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.path, self.reason)
+    }
+}
+
+/// Configuration that cannot be used, with every offending field.
+#[derive(Debug, Clone)]
+pub struct ConfigError(pub Vec<ConfigIssue>);
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid configuration")?;
+        for issue in &self.0 {
+            write!(f, "\n  {issue}")?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+impl Config {
+    /// Keys of a configuration in the file format that no field declares.
+    pub fn unknown_keys(value: &Value) -> Vec<ConfigIssue> {
+        let defaults = serde_json::to_value(Self::default()).unwrap_or_default();
+        let mut warnings = Vec::new();
+        unknown_keys(value, &defaults, String::new(), &mut warnings);
+        warnings
+    }
+
+    /// Parse a configuration in the file format, reporting every field with
+    /// a wrong type or an unusable value.
+    pub fn parse(mut value: Value) -> Result<Self, ConfigError> {
+        // serde stops at the first bad field, so drop each one in turn to
+        // find the rest.
+        let mut errors = Vec::new();
+        let config = loop {
+            match serde_path_to_error::deserialize::<_, Self>(&value) {
+                Ok(config) => break config,
+                Err(e) => {
+                    let segments: Vec<Segment> = e.path().iter().cloned().collect();
+                    errors.push(ConfigIssue {
+                        path: display_path(&segments),
+                        reason: e.into_inner().to_string(),
+                    });
+                    if !remove(&mut value, &segments) {
+                        return Err(ConfigError(errors));
+                    }
+                }
+            }
+        };
+
+        // Fields that were dropped above fall back to their defaults, which
+        // may not pass the checks themselves.
+        let checks: Vec<ConfigIssue> = config
+            .check()
+            .into_iter()
+            .filter(|issue| !errors.iter().any(|e| e.path == issue.path))
+            .collect();
+        errors.extend(checks);
+        if errors.is_empty() {
+            Ok(config)
+        } else {
+            Err(ConfigError(errors))
+        }
+    }
+
+    /// Constraints the types alone cannot express.
+    fn check(&self) -> Vec<ConfigIssue> {
+        let mut issues = Vec::new();
+
+        if parse_duration(&self.security.cdn_signature_duration).is_none() {
+            issues.push(ConfigIssue {
+                path: "security.cdn_signature_duration".into(),
+                reason: format!(
+                    "`{}` is not a duration such as `30m` or `24h`",
+                    self.security.cdn_signature_duration
+                ),
+            });
+        }
+
+        // Stored in the signed 64-bit `users.rights` column.
+        if !matches!(self.register.default_rights.parse::<i64>(), Ok(rights) if rights >= 0) {
+            issues.push(ConfigIssue {
+                path: "register.default_rights".into(),
+                reason: format!(
+                    "`{}` is not a rights bitfield such as `875069521787904`",
+                    self.register.default_rights
+                ),
+            });
+        }
+
+        let rate = &self.limits.rate;
+        let windows: [(&str, &RateLimitOptions); 8] = [
+            ("ip", &rate.ip),
+            ("global", &rate.global),
+            ("error", &rate.error),
+            ("routes.guild", &rate.routes.guild),
+            ("routes.webhook", &rate.routes.webhook),
+            ("routes.channel", &rate.routes.channel),
+            ("routes.auth.login", &rate.routes.auth.login),
+            ("routes.auth.register", &rate.routes.auth.register),
+        ];
+        for (name, options) in windows {
+            if options.window == 0 {
+                issues.push(ConfigIssue {
+                    path: format!("limits.rate.{name}.window"),
+                    reason: "rate limit window must be greater than zero".into(),
+                });
+            }
+        }
+
+        let absolute = &self.limits.absolute_rate;
+        for (name, limit) in [
+            ("register", &absolute.register),
+            ("send_message", &absolute.send_message),
+        ] {
+            if limit.enabled && limit.window == 0 {
+                issues.push(ConfigIssue {
+                    path: format!("limits.absolute_rate.{name}.window"),
+                    reason: "rate limit window must be greater than zero".into(),
+                });
+            }
+        }
+
+        issues
+    }
+}
+
+/// Parse a duration in the format of the `ms` package used by the
+/// TypeScript server: `500`, `10s`, `30 minutes`, `1.5h`, `7d`, ...
+pub fn parse_duration(value: &str) -> Option<Duration> {
+    let value = value.trim();
+    let split = value
+        .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+        .unwrap_or(value.len());
+    let (number, unit) = value.split_at(split);
+    let number: f64 = number.parse().ok()?;
+
+    let millis = match unit.trim().to_ascii_lowercase().as_str() {
+        "" | "ms" | "msec" | "msecs" | "millisecond" | "milliseconds" => 1.0,
+        "s" | "sec" | "secs" | "second" | "seconds" => 1_000.0,
+        "m" | "min" | "mins" | "minute" | "minutes" => 60_000.0,
+        "h" | "hr" | "hrs" | "hour" | "hours" => 3_600_000.0,
+        "d" | "day" | "days" => 86_400_000.0,
+        "w" | "week" | "weeks" => 604_800_000.0,
+        "y" | "yr" | "yrs" | "year" | "years" => 31_557_600_000.0,
+        _ => return None,
+    };
+    Some(Duration::from_millis((number * millis) as u64))
+}
+
+/// Warn about keys that no section of the configuration declares. Values
+/// without a section in the defaults, such as lists, are not inspected.
+fn unknown_keys(value: &Value, defaults: &Value, prefix: String, warnings: &mut Vec<ConfigIssue>) {
+    let (Value::Object(map), Value::Object(default_map)) = (value, defaults) else {
+        return;
+    };
+    for (key, value) in map {
+        let path = if prefix.is_empty() {
+            snake_case(key)
+        } else {
+            format!("{prefix}.{}", snake_case(key))
+        };
+        match default_map.get(key) {
+            Some(defaults) => unknown_keys(value, defaults, path, warnings),
+            None => warnings.push(ConfigIssue {
+                path,
+                reason: "unknown key, ignored".into(),
+            }),
+        }
+    }
+}
+
+fn display_path(segments: &[Segment]) -> String {
+    let mut path = String::new();
+    for segment in segments {
+        match segment {
+            Segment::Seq { index } => path += &format!("[{index}]"),
+            Segment::Map { key } => {
+                if !path.is_empty() {
+                    path.push('.');
+                }
+                path += &snake_case(key);
+            }
+            Segment::Enum { variant } => {
+                if !path.is_empty() {
+                    path.push('.');
+                }
+                path += variant;
+            }
+            Segment::Unknown => path += ".?",
+        }
+    }
+    if path.is_empty() {
+        path.push('.');
+    }
+    path
+}
+
+/// Remove the value at `segments` so its default is used instead. Returns
+/// `false` if there is nothing left to remove.
+fn remove(value: &mut Value, segments: &[Segment]) -> bool {
+    let Some((last, parents)) = segments.split_last() else {
+        return false;
+    };
+    let mut target = value;
+    for segment in parents {
+        target = match (segment, target) {
+            (Segment::Map { key }, Value::Object(map)) => match map.get_mut(key) {
+                Some(child) => child,
+                None => return false,
+            },
+            (Segment::Seq { index }, Value::Array(items)) => match items.get_mut(*index) {
+                Some(child) => child,
+                None => return false,
+            },
+            _ => return false,
+        };
+    }
+    match (last, target) {
+        (Segment::Map { key }, Value::Object(map)) => map.remove(key).is_some(),
+        (Segment::Seq { index }, Value::Array(items)) if *index < items.len() => {
+            items.remove(*index);
+            true
+        }
+        _ => false,
+    }
+}
@@ -0,0 +1,48 @@
+//! Values that deserialise but cannot be used are rejected at load time.
+
+use config::Config;
+
+fn overridden(vars: &[(&str, &str)]) -> Result<Config, Vec<String>> {
+    let vars = vars
+        .iter()
+        .map(|(name, value)| (name.to_string(), value.to_string()));
+    Config::default()
+        .with_overrides(vars)
+        .map_err(|e| e.0.into_iter().map(|issue| issue.path).collect())
+}
+
+#[test]
+fn defaults_pass() {
+    assert!(overridden(&[]).is_ok());
+}
+
+#[test]
+fn default_rights_must_be_a_bitfield() {
+    for rights in ["admin", "", "-1", "1.5", "18446744073709551615"] {
+        assert_eq!(
+            overridden(&[("SPACEBAR__REGISTER__DEFAULT_RIGHTS", rights)]).unwrap_err(),
+            ["register.default_rights"],
+            "{rights:?}"
+        );
+    }
+    let config = overridden(&[("SPACEBAR__REGISTER__DEFAULT_RIGHTS", "0")]).unwrap();
+    assert_eq!(config.register.default_rights, "0");
+}
+
+#[test]
+fn every_unusable_value_is_reported() {
+    let issues = overridden(&[
+        ("SPACEBAR__REGISTER__DEFAULT_RIGHTS", "everything"),
+        ("SPACEBAR__SECURITY__CDN_SIGNATURE_DURATION", "soon"),
+        ("SPACEBAR__LIMITS__RATE__IP__WINDOW", "0"),
+    ])
+    .unwrap_err();
+    assert_eq!(
+        issues,
+        [
+            "security.cdn_signature_duration",
+            "register.default_rights",
+            "limits.rate.ip.window",
+        ]
+    );
+}
@@ -0,0 +1,49 @@
+//! `SPACEBAR__*` overrides take the type of the field they set.
+
+use config::Config;
+
+fn overridden(vars: &[(&str, &str)]) -> Config {
+    let vars = vars
+        .iter()
+        .map(|(name, value)| (name.to_string(), value.to_string()));
+    Config::default().with_overrides(vars).unwrap()
+}
+
+#[test]
+fn numbers_stay_strings_in_string_fields() {
+    let config = overridden(&[
+        ("SPACEBAR__EMAIL__SMTP__PASSWORD", "12345678"),
+        ("SPACEBAR__EMAIL__SMTP__USERNAME", "true"),
+        ("SPACEBAR__SECURITY__JWT_SECRET", "42"),
+    ]);
+    assert_eq!(config.email.smtp.password.as_deref(), Some("12345678"));
+    assert_eq!(config.email.smtp.username.as_deref(), Some("true"));
+    assert_eq!(config.security.jwt_secret, "42");
+}
+
+#[test]
+fn typed_fields_are_parsed() {
+    let config = overridden(&[
+        ("SPACEBAR__EMAIL__SMTP__PORT", "587"),
+        ("SPACEBAR__EMAIL__SMTP__SECURE", "true"),
+        ("SPACEBAR__GUILD__AUTO_JOIN__GUILDS", r#"["1","2"]"#),
+    ]);
+    assert_eq!(config.email.smtp.port, Some(587));
+    assert_eq!(config.email.smtp.secure, Some(true));
+    assert_eq!(config.guild.auto_join.guilds, ["1", "2"]);
+}
+
+#[test]
+fn null_clears_optional_fields() {
+    let config = overridden(&[
+        ("SPACEBAR__EMAIL__SMTP__PASSWORD", "secret"),
+        ("SPACEBAR__EMAIL__SMTP__PASSWORD", "null"),
+    ]);
+    assert_eq!(config.email.smtp.password, None);
+}
+
+#[test]
+fn other_variables_are_ignored() {
+    let config = overridden(&[("SMTP_PASSWORD", "12345678")]);
+    assert_eq!(config.email.smtp.password, None);
+}
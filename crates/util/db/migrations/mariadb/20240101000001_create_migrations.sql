@@ -0,0 +1,5 @@
+CREATE TABLE IF NOT EXISTS migrations (
+    id BIGINT NOT NULL PRIMARY KEY,
+    timestamp BIGINT NOT NULL,
+    name VARCHAR(255) NOT NULL
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4;
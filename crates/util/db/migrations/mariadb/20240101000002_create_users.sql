@@ -0,0 +1,62 @@
+CREATE TABLE IF NOT EXISTS users (
+    id VARCHAR(255) NOT NULL PRIMARY KEY,
+    username VARCHAR(255) NOT NULL,
+    discriminator VARCHAR(255) NOT NULL,
+    avatar VARCHAR(255),
+    accent_color INT,
+    banner VARCHAR(255),
+    theme_colors TEXT,
+    pronouns VARCHAR(255),
+    phone VARCHAR(255),
+    desktop SMALLINT NOT NULL DEFAULT 0,
+    mobile SMALLINT NOT NULL DEFAULT 0,
+    premium SMALLINT NOT NULL,
+    premium_type INT NOT NULL,
+    bot SMALLINT NOT NULL DEFAULT 0,
+    bio VARCHAR(255) NOT NULL DEFAULT '',
+    `system` SMALLINT NOT NULL DEFAULT 0,
+    nsfw_allowed SMALLINT NOT NULL DEFAULT 1,
+    mfa_enabled SMALLINT NOT NULL DEFAULT 0,
+    webauthn_enabled SMALLINT NOT NULL DEFAULT 0,
+    totp_secret VARCHAR(255),
+    totp_last_ticket VARCHAR(255),
+    created_at BIGINT NOT NULL,
+    premium_since BIGINT,
+    verified SMALLINT NOT NULL,
+    disabled SMALLINT NOT NULL DEFAULT 0,
+    deleted SMALLINT NOT NULL DEFAULT 0,
+    email VARCHAR(255),
+    flags BIGINT NOT NULL DEFAULT 0,
+    public_flags BIGINT NOT NULL DEFAULT 0,
+    purchased_flags INT NOT NULL DEFAULT 0,
+    premium_usage_flags INT NOT NULL DEFAULT 0,
+    rights BIGINT NOT NULL,
+    data TEXT NOT NULL,
+    fingerprints TEXT NOT NULL,
+    -- Plain string columns use VARCHAR: the Any driver reads MySQL TEXT as a blob.
+    extended_settings VARCHAR(4096) NOT NULL,
+    badge_ids TEXT,
+    KEY idx_users_email (email)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4;
+
+CREATE TABLE IF NOT EXISTS relationships (
+    id VARCHAR(255) NOT NULL PRIMARY KEY,
+    from_id VARCHAR(255) NOT NULL,
+    to_id VARCHAR(255) NOT NULL,
+    nickname VARCHAR(255),
+    type INT NOT NULL,
+    UNIQUE KEY idx_relationships_from_to (from_id, to_id),
+    FOREIGN KEY (from_id) REFERENCES users (id) ON DELETE CASCADE,
+    FOREIGN KEY (to_id) REFERENCES users (id) ON DELETE CASCADE
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4;
+
+CREATE TABLE IF NOT EXISTS sessions (
+    id VARCHAR(255) NOT NULL PRIMARY KEY,
+    user_id VARCHAR(255),
+    session_id VARCHAR(255) NOT NULL,
+    activities TEXT,
+    client_info TEXT NOT NULL,
+    client_status TEXT NOT NULL,
+    status VARCHAR(255) NOT NULL,
+    FOREIGN KEY (user_id) REFERENCES users (id) ON DELETE CASCADE
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4;
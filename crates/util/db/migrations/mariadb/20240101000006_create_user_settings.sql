@@ -0,0 +1,43 @@
+-- Settings of each user, referenced by users.settingsIndex like in the
+-- TypeORM schema. Every column is nullable there as well.
+CREATE TABLE IF NOT EXISTS user_settings (
+    `index` INT NOT NULL AUTO_INCREMENT PRIMARY KEY,
+    afk_timeout INT,
+    allow_accessibility_detection SMALLINT,
+    animate_emoji SMALLINT,
+    animate_stickers INT,
+    contact_sync_enabled SMALLINT,
+    convert_emoticons SMALLINT,
+    custom_status TEXT,
+    default_guilds_restricted SMALLINT,
+    detect_platform_accounts SMALLINT,
+    developer_mode SMALLINT,
+    disable_games_tab SMALLINT,
+    enable_tts_command SMALLINT,
+    explicit_content_filter INT,
+    friend_discovery_flags INT,
+    friend_source_flags TEXT,
+    gateway_connected SMALLINT,
+    gif_auto_play SMALLINT,
+    guild_folders TEXT,
+    guild_positions TEXT,
+    inline_attachment_media SMALLINT,
+    inline_embed_media SMALLINT,
+    locale VARCHAR(255),
+    message_display_compact SMALLINT,
+    native_phone_integration_enabled SMALLINT,
+    render_embeds SMALLINT,
+    render_reactions SMALLINT,
+    restricted_guilds TEXT,
+    show_current_game SMALLINT,
+    status VARCHAR(255),
+    stream_notifications_enabled SMALLINT,
+    theme VARCHAR(255),
+    timezone_offset INT,
+    view_nsfw_guilds SMALLINT
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4;
+
+ALTER TABLE users
+    ADD COLUMN settingsIndex INT NULL,
+    ADD UNIQUE KEY idx_users_settings_index (settingsIndex),
+    ADD FOREIGN KEY (settingsIndex) REFERENCES user_settings (`index`) ON DELETE SET NULL;
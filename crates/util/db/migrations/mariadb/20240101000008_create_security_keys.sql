@@ -0,0 +1,15 @@
+-- WebAuthn security keys. `public_key` holds the whole serialised credential
+-- rather than the PEM public key the TypeScript server stores.
+CREATE TABLE IF NOT EXISTS security_keys (
+    id VARCHAR(255) NOT NULL PRIMARY KEY,
+    user_id VARCHAR(255),
+    key_id VARCHAR(255) NOT NULL,
+    public_key VARCHAR(4096) NOT NULL,
+    counter INT NOT NULL,
+    name VARCHAR(255) NOT NULL,
+    KEY idx_security_keys_user_id (user_id),
+    FOREIGN KEY (user_id) REFERENCES users (id) ON DELETE CASCADE
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4;
+
+-- Login tickets of users with security keys carry the challenge state.
+ALTER TABLE users MODIFY totp_last_ticket VARCHAR(4096) NULL;
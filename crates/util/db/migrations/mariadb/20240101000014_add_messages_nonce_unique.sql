@@ -0,0 +1,13 @@
+-- A nonce identifies one message of its author in a channel, so that a
+-- client retrying a send gets the message back instead of a second one.
+-- Earlier duplicates keep their nonce on the oldest message only.
+UPDATE messages
+JOIN messages AS earlier
+    ON earlier.channel_id = messages.channel_id
+    AND earlier.author_id = messages.author_id
+    AND earlier.nonce = messages.nonce
+    AND earlier.id < messages.id
+SET messages.nonce = NULL;
+
+CREATE UNIQUE INDEX idx_messages_channel_id_author_id_nonce
+    ON messages (channel_id, author_id, nonce);
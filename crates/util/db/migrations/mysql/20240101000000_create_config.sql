@@ -0,0 +1,4 @@
+CREATE TABLE IF NOT EXISTS config (
+    `key` VARCHAR(255) NOT NULL PRIMARY KEY,
+    `value` TEXT
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4;
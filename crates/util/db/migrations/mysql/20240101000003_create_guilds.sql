@@ -0,0 +1,131 @@
+CREATE TABLE IF NOT EXISTS guilds (
+    id VARCHAR(255) NOT NULL PRIMARY KEY,
+    afk_channel_id VARCHAR(255),
+    afk_timeout INT,
+    banner VARCHAR(255),
+    default_message_notifications INT,
+    description VARCHAR(255),
+    discovery_splash VARCHAR(255),
+    explicit_content_filter INT,
+    features TEXT NOT NULL,
+    primary_category_id VARCHAR(255),
+    icon VARCHAR(255),
+    large SMALLINT NOT NULL DEFAULT 0,
+    max_members INT,
+    max_presences INT,
+    max_video_channel_users INT,
+    member_count INT,
+    presence_count INT,
+    template_id VARCHAR(255),
+    mfa_level INT,
+    name VARCHAR(255) NOT NULL,
+    owner_id VARCHAR(255),
+    preferred_locale VARCHAR(255),
+    premium_subscription_count INT,
+    premium_tier INT NOT NULL,
+    public_updates_channel_id VARCHAR(255),
+    rules_channel_id VARCHAR(255),
+    region VARCHAR(255),
+    splash VARCHAR(255),
+    system_channel_id VARCHAR(255),
+    system_channel_flags INT,
+    unavailable SMALLINT NOT NULL DEFAULT 0,
+    verification_level INT,
+    welcome_screen TEXT NOT NULL,
+    widget_channel_id VARCHAR(255),
+    widget_enabled SMALLINT NOT NULL DEFAULT 1,
+    nsfw_level INT,
+    nsfw SMALLINT NOT NULL DEFAULT 0,
+    parent VARCHAR(255),
+    premium_progress_bar_enabled SMALLINT DEFAULT 0,
+    channel_ordering TEXT NOT NULL
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4;
+
+CREATE TABLE IF NOT EXISTS roles (
+    id VARCHAR(255) NOT NULL PRIMARY KEY,
+    guild_id VARCHAR(255) NOT NULL,
+    color INT NOT NULL,
+    hoist SMALLINT NOT NULL,
+    managed SMALLINT NOT NULL,
+    mentionable SMALLINT NOT NULL,
+    name VARCHAR(255) NOT NULL,
+    permissions VARCHAR(255) NOT NULL,
+    position INT NOT NULL,
+    icon VARCHAR(255),
+    unicode_emoji VARCHAR(255),
+    tags TEXT,
+    flags INT NOT NULL DEFAULT 0,
+    KEY idx_roles_guild_id (guild_id),
+    FOREIGN KEY (guild_id) REFERENCES guilds (id) ON DELETE CASCADE
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4;
+
+CREATE TABLE IF NOT EXISTS members (
+    `index` INT NOT NULL AUTO_INCREMENT PRIMARY KEY,
+    id VARCHAR(255) NOT NULL,
+    guild_id VARCHAR(255) NOT NULL,
+    nick VARCHAR(255),
+    joined_at BIGINT NOT NULL,
+    premium_since BIGINT,
+    deaf SMALLINT NOT NULL,
+    mute SMALLINT NOT NULL,
+    pending SMALLINT NOT NULL,
+    settings TEXT NOT NULL,
+    last_message_id VARCHAR(255),
+    joined_by VARCHAR(255),
+    avatar VARCHAR(255),
+    banner VARCHAR(255),
+    bio VARCHAR(255) NOT NULL DEFAULT '',
+    theme_colors TEXT,
+    pronouns VARCHAR(255),
+    communication_disabled_until BIGINT,
+    UNIQUE KEY idx_members_id_guild_id (id, guild_id),
+    FOREIGN KEY (id) REFERENCES users (id) ON DELETE CASCADE,
+    FOREIGN KEY (guild_id) REFERENCES guilds (id) ON DELETE CASCADE
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4;
+
+CREATE TABLE IF NOT EXISTS member_roles (
+    `index` INT NOT NULL,
+    role_id VARCHAR(255) NOT NULL,
+    PRIMARY KEY (`index`, role_id),
+    FOREIGN KEY (`index`) REFERENCES members (`index`) ON DELETE CASCADE,
+    FOREIGN KEY (role_id) REFERENCES roles (id) ON DELETE CASCADE
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4;
+
+CREATE TABLE IF NOT EXISTS bans (
+    id VARCHAR(255) NOT NULL PRIMARY KEY,
+    user_id VARCHAR(255),
+    guild_id VARCHAR(255),
+    executor_id VARCHAR(255),
+    ip VARCHAR(255) NOT NULL,
+    reason VARCHAR(255),
+    FOREIGN KEY (user_id) REFERENCES users (id) ON DELETE CASCADE,
+    FOREIGN KEY (guild_id) REFERENCES guilds (id) ON DELETE CASCADE
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4;
+
+CREATE TABLE IF NOT EXISTS emojis (
+    id VARCHAR(255) NOT NULL PRIMARY KEY,
+    animated SMALLINT NOT NULL,
+    available SMALLINT NOT NULL,
+    guild_id VARCHAR(255) NOT NULL,
+    user_id VARCHAR(255),
+    managed SMALLINT NOT NULL,
+    name VARCHAR(255) NOT NULL,
+    require_colons SMALLINT NOT NULL,
+    roles TEXT NOT NULL,
+    `groups` TEXT,
+    FOREIGN KEY (guild_id) REFERENCES guilds (id) ON DELETE CASCADE
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4;
+
+CREATE TABLE IF NOT EXISTS stickers (
+    id VARCHAR(255) NOT NULL PRIMARY KEY,
+    name VARCHAR(255) NOT NULL,
+    description VARCHAR(255),
+    available SMALLINT,
+    tags VARCHAR(255),
+    pack_id VARCHAR(255),
+    guild_id VARCHAR(255),
+    user_id VARCHAR(255),
+    type INT NOT NULL,
+    format_type INT NOT NULL,
+    FOREIGN KEY (guild_id) REFERENCES guilds (id) ON DELETE CASCADE
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4;
@@ -0,0 +1,77 @@
+CREATE TABLE IF NOT EXISTS channels (
+    id VARCHAR(255) NOT NULL PRIMARY KEY,
+    created_at BIGINT NOT NULL,
+    name VARCHAR(255),
+    icon VARCHAR(255),
+    type INT NOT NULL,
+    last_message_id VARCHAR(255),
+    guild_id VARCHAR(255),
+    parent_id VARCHAR(255),
+    owner_id VARCHAR(255),
+    last_pin_timestamp BIGINT,
+    default_auto_archive_duration INT,
+    permission_overwrites TEXT,
+    video_quality_mode INT,
+    bitrate INT,
+    user_limit INT,
+    nsfw SMALLINT NOT NULL DEFAULT 0,
+    rate_limit_per_user INT,
+    topic VARCHAR(1024),
+    retention_policy_id VARCHAR(255),
+    flags INT NOT NULL DEFAULT 0,
+    default_thread_rate_limit_per_user INT NOT NULL DEFAULT 0,
+    KEY idx_channels_guild_id (guild_id),
+    FOREIGN KEY (guild_id) REFERENCES guilds (id) ON DELETE CASCADE
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4;
+
+CREATE TABLE IF NOT EXISTS invites (
+    code VARCHAR(255) NOT NULL PRIMARY KEY,
+    temporary SMALLINT NOT NULL,
+    uses INT NOT NULL,
+    max_uses INT NOT NULL,
+    max_age INT NOT NULL,
+    created_at BIGINT NOT NULL,
+    expires_at BIGINT,
+    guild_id VARCHAR(255),
+    channel_id VARCHAR(255),
+    inviter_id VARCHAR(255),
+    target_user_id VARCHAR(255),
+    target_user_type INT,
+    vanity_url SMALLINT,
+    flags INT NOT NULL DEFAULT 0,
+    FOREIGN KEY (guild_id) REFERENCES guilds (id) ON DELETE CASCADE,
+    FOREIGN KEY (channel_id) REFERENCES channels (id) ON DELETE CASCADE,
+    FOREIGN KEY (inviter_id) REFERENCES users (id) ON DELETE CASCADE,
+    FOREIGN KEY (target_user_id) REFERENCES users (id) ON DELETE CASCADE
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4;
+
+CREATE TABLE IF NOT EXISTS webhooks (
+    id VARCHAR(255) NOT NULL PRIMARY KEY,
+    type INT NOT NULL,
+    name VARCHAR(255),
+    avatar VARCHAR(255),
+    token VARCHAR(255),
+    guild_id VARCHAR(255),
+    channel_id VARCHAR(255),
+    application_id VARCHAR(255),
+    user_id VARCHAR(255),
+    source_guild_id VARCHAR(255),
+    source_channel_id VARCHAR(255),
+    FOREIGN KEY (guild_id) REFERENCES guilds (id) ON DELETE CASCADE,
+    FOREIGN KEY (channel_id) REFERENCES channels (id) ON DELETE CASCADE,
+    FOREIGN KEY (user_id) REFERENCES users (id) ON DELETE CASCADE
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4;
+
+CREATE TABLE IF NOT EXISTS read_states (
+    id VARCHAR(255) NOT NULL PRIMARY KEY,
+    channel_id VARCHAR(255) NOT NULL,
+    user_id VARCHAR(255) NOT NULL,
+    last_message_id VARCHAR(255),
+    public_ack VARCHAR(255),
+    notifications_cursor VARCHAR(255),
+    last_pin_timestamp BIGINT,
+    mention_count INT,
+    UNIQUE KEY idx_read_states_channel_user (channel_id, user_id),
+    FOREIGN KEY (channel_id) REFERENCES channels (id) ON DELETE CASCADE,
+    FOREIGN KEY (user_id) REFERENCES users (id) ON DELETE CASCADE
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4;
@@ -0,0 +1,79 @@
+CREATE TABLE IF NOT EXISTS messages (
+    id VARCHAR(255) NOT NULL PRIMARY KEY,
+    channel_id VARCHAR(255),
+    guild_id VARCHAR(255),
+    author_id VARCHAR(255),
+    member_id VARCHAR(255),
+    webhook_id VARCHAR(255),
+    application_id VARCHAR(255),
+    -- Plain string columns use VARCHAR: the Any driver reads MySQL TEXT as a blob.
+    content VARCHAR(4000),
+    timestamp BIGINT NOT NULL,
+    edited_timestamp BIGINT,
+    tts SMALLINT,
+    mention_everyone SMALLINT,
+    embeds TEXT NOT NULL,
+    reactions TEXT NOT NULL,
+    nonce VARCHAR(255),
+    pinned SMALLINT,
+    type INT NOT NULL,
+    activity TEXT,
+    flags INT NOT NULL DEFAULT 0,
+    message_reference TEXT,
+    message_reference_id VARCHAR(255),
+    interaction TEXT,
+    components TEXT,
+    poll TEXT,
+    username VARCHAR(255),
+    avatar VARCHAR(255),
+    UNIQUE KEY idx_messages_channel_id_id (channel_id, id),
+    KEY idx_messages_author_id (author_id),
+    FOREIGN KEY (channel_id) REFERENCES channels (id) ON DELETE CASCADE,
+    FOREIGN KEY (guild_id) REFERENCES guilds (id) ON DELETE CASCADE,
+    FOREIGN KEY (author_id) REFERENCES users (id) ON DELETE CASCADE
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4;
+
+CREATE TABLE IF NOT EXISTS attachments (
+    id VARCHAR(255) NOT NULL PRIMARY KEY,
+    filename VARCHAR(255) NOT NULL,
+    size INT NOT NULL,
+    url VARCHAR(2048) NOT NULL,
+    proxy_url VARCHAR(2048) NOT NULL,
+    height INT,
+    width INT,
+    content_type VARCHAR(255),
+    message_id VARCHAR(255),
+    FOREIGN KEY (message_id) REFERENCES messages (id) ON DELETE CASCADE
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4;
+
+CREATE TABLE IF NOT EXISTS message_user_mentions (
+    messagesId VARCHAR(255) NOT NULL,
+    usersId VARCHAR(255) NOT NULL,
+    PRIMARY KEY (messagesId, usersId),
+    FOREIGN KEY (messagesId) REFERENCES messages (id) ON DELETE CASCADE,
+    FOREIGN KEY (usersId) REFERENCES users (id) ON DELETE CASCADE
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4;
+
+CREATE TABLE IF NOT EXISTS message_role_mentions (
+    messagesId VARCHAR(255) NOT NULL,
+    rolesId VARCHAR(255) NOT NULL,
+    PRIMARY KEY (messagesId, rolesId),
+    FOREIGN KEY (messagesId) REFERENCES messages (id) ON DELETE CASCADE,
+    FOREIGN KEY (rolesId) REFERENCES roles (id) ON DELETE CASCADE
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4;
+
+CREATE TABLE IF NOT EXISTS message_channel_mentions (
+    messagesId VARCHAR(255) NOT NULL,
+    channelsId VARCHAR(255) NOT NULL,
+    PRIMARY KEY (messagesId, channelsId),
+    FOREIGN KEY (messagesId) REFERENCES messages (id) ON DELETE CASCADE,
+    FOREIGN KEY (channelsId) REFERENCES channels (id) ON DELETE CASCADE
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4;
+
+CREATE TABLE IF NOT EXISTS message_stickers (
+    messagesId VARCHAR(255) NOT NULL,
+    stickersId VARCHAR(255) NOT NULL,
+    PRIMARY KEY (messagesId, stickersId),
+    FOREIGN KEY (messagesId) REFERENCES messages (id) ON DELETE CASCADE,
+    FOREIGN KEY (stickersId) REFERENCES stickers (id) ON DELETE CASCADE
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4;
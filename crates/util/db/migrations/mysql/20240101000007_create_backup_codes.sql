@@ -0,0 +1,11 @@
+-- Single-use MFA backup codes. Unlike the TypeORM schema, `code` holds the
+-- SHA-256 hash of the code rather than the code itself.
+CREATE TABLE IF NOT EXISTS backup_codes (
+    id VARCHAR(255) NOT NULL PRIMARY KEY,
+    code VARCHAR(255) NOT NULL,
+    consumed SMALLINT NOT NULL,
+    expired SMALLINT NOT NULL,
+    user_id VARCHAR(255),
+    KEY idx_backup_codes_user_id (user_id),
+    FOREIGN KEY (user_id) REFERENCES users (id) ON DELETE CASCADE
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4;
@@ -0,0 +1,8 @@
+-- Sessions are either gateway connections, as in the TypeScript server, or
+-- logins with the token that was issued for them.
+ALTER TABLE sessions
+    ADD COLUMN kind VARCHAR(255) NOT NULL DEFAULT 'gateway',
+    ADD COLUMN ip VARCHAR(255) NULL,
+    ADD COLUMN user_agent VARCHAR(1024) NULL,
+    ADD COLUMN last_seen BIGINT NULL,
+    ADD KEY idx_sessions_session_id (session_id);
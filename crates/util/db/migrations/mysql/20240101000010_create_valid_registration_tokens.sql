@@ -0,0 +1,8 @@
+-- Single-use tokens allowing to register on invite-only instances. The
+-- TypeScript schema already has this table, with the dates converted on
+-- import.
+CREATE TABLE IF NOT EXISTS valid_registration_tokens (
+    token VARCHAR(255) NOT NULL PRIMARY KEY,
+    created_at BIGINT NOT NULL,
+    expires_at BIGINT NOT NULL
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4;
@@ -0,0 +1,12 @@
+-- Members of direct message channels. The TypeScript schema already has
+-- this table, with `closed` converted on import.
+CREATE TABLE IF NOT EXISTS recipients (
+    id VARCHAR(255) NOT NULL PRIMARY KEY,
+    channel_id VARCHAR(255) NOT NULL,
+    user_id VARCHAR(255) NOT NULL,
+    closed SMALLINT NOT NULL DEFAULT 0,
+    KEY idx_recipients_channel_id (channel_id),
+    KEY idx_recipients_user_id (user_id),
+    FOREIGN KEY (channel_id) REFERENCES channels (id) ON DELETE CASCADE,
+    FOREIGN KEY (user_id) REFERENCES users (id) ON DELETE CASCADE
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4;
@@ -0,0 +1,17 @@
+-- Reactions to messages, one row per user and emoji so that concurrent
+-- reactions never overwrite each other. The TypeScript server keeps them as
+-- JSON in `messages.reactions`, which is copied here on import.
+CREATE TABLE IF NOT EXISTS message_reactions (
+    message_id VARCHAR(255) NOT NULL,
+    -- ID of a custom emoji, or the unicode emoji itself.
+    emoji VARCHAR(255) NOT NULL,
+    user_id VARCHAR(255) NOT NULL,
+    emoji_id VARCHAR(255),
+    emoji_name VARCHAR(255) NOT NULL,
+    animated SMALLINT NOT NULL DEFAULT 0,
+    created_at BIGINT NOT NULL,
+    PRIMARY KEY (message_id, emoji, user_id),
+    KEY idx_message_reactions_user_id (user_id),
+    FOREIGN KEY (message_id) REFERENCES messages (id) ON DELETE CASCADE,
+    FOREIGN KEY (user_id) REFERENCES users (id) ON DELETE CASCADE
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4;
@@ -0,0 +1,3 @@
+-- Last TOTP time step a code was accepted for, so that no code is accepted
+-- twice.
+ALTER TABLE users ADD COLUMN totp_last_step BIGINT NULL;
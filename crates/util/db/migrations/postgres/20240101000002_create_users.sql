@@ -0,0 +1,60 @@
+CREATE TABLE IF NOT EXISTS users (
+    id VARCHAR(255) PRIMARY KEY,
+    username VARCHAR(255) NOT NULL,
+    discriminator VARCHAR(255) NOT NULL,
+    avatar VARCHAR(255),
+    accent_color INTEGER,
+    banner VARCHAR(255),
+    theme_colors TEXT,
+    pronouns VARCHAR(255),
+    phone VARCHAR(255),
+    desktop SMALLINT NOT NULL DEFAULT 0,
+    mobile SMALLINT NOT NULL DEFAULT 0,
+    premium SMALLINT NOT NULL,
+    premium_type INTEGER NOT NULL,
+    bot SMALLINT NOT NULL DEFAULT 0,
+    bio VARCHAR(255) NOT NULL DEFAULT '',
+    system SMALLINT NOT NULL DEFAULT 0,
+    nsfw_allowed SMALLINT NOT NULL DEFAULT 1,
+    mfa_enabled SMALLINT NOT NULL DEFAULT 0,
+    webauthn_enabled SMALLINT NOT NULL DEFAULT 0,
+    totp_secret VARCHAR(255),
+    totp_last_ticket VARCHAR(255),
+    created_at BIGINT NOT NULL,
+    premium_since BIGINT,
+    verified SMALLINT NOT NULL,
+    disabled SMALLINT NOT NULL DEFAULT 0,
+    deleted SMALLINT NOT NULL DEFAULT 0,
+    email VARCHAR(255),
+    flags BIGINT NOT NULL DEFAULT 0,
+    public_flags BIGINT NOT NULL DEFAULT 0,
+    purchased_flags INTEGER NOT NULL DEFAULT 0,
+    premium_usage_flags INTEGER NOT NULL DEFAULT 0,
+    rights BIGINT NOT NULL,
+    data TEXT NOT NULL,
+    fingerprints TEXT NOT NULL,
+    extended_settings TEXT NOT NULL,
+    badge_ids TEXT
+);
+
+CREATE INDEX IF NOT EXISTS idx_users_email ON users (email);
+
+CREATE TABLE IF NOT EXISTS relationships (
+    id VARCHAR(255) PRIMARY KEY,
+    from_id VARCHAR(255) NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+    to_id VARCHAR(255) NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+    nickname VARCHAR(255),
+    type INTEGER NOT NULL
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_relationships_from_to ON relationships (from_id, to_id);
+
+CREATE TABLE IF NOT EXISTS sessions (
+    id VARCHAR(255) PRIMARY KEY,
+    user_id VARCHAR(255) REFERENCES users (id) ON DELETE CASCADE,
+    session_id VARCHAR(255) NOT NULL,
+    activities TEXT,
+    client_info TEXT NOT NULL,
+    client_status TEXT NOT NULL,
+    status VARCHAR(255) NOT NULL
+);
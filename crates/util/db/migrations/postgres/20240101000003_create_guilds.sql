@@ -0,0 +1,124 @@
+CREATE TABLE IF NOT EXISTS guilds (
+    id VARCHAR(255) PRIMARY KEY,
+    afk_channel_id VARCHAR(255),
+    afk_timeout INTEGER,
+    banner VARCHAR(255),
+    default_message_notifications INTEGER,
+    description VARCHAR(255),
+    discovery_splash VARCHAR(255),
+    explicit_content_filter INTEGER,
+    features TEXT NOT NULL,
+    primary_category_id VARCHAR(255),
+    icon VARCHAR(255),
+    large SMALLINT NOT NULL DEFAULT 0,
+    max_members INTEGER,
+    max_presences INTEGER,
+    max_video_channel_users INTEGER,
+    member_count INTEGER,
+    presence_count INTEGER,
+    template_id VARCHAR(255),
+    mfa_level INTEGER,
+    name VARCHAR(255) NOT NULL,
+    owner_id VARCHAR(255),
+    preferred_locale VARCHAR(255),
+    premium_subscription_count INTEGER,
+    premium_tier INTEGER NOT NULL,
+    public_updates_channel_id VARCHAR(255),
+    rules_channel_id VARCHAR(255),
+    region VARCHAR(255),
+    splash VARCHAR(255),
+    system_channel_id VARCHAR(255),
+    system_channel_flags INTEGER,
+    unavailable SMALLINT NOT NULL DEFAULT 0,
+    verification_level INTEGER,
+    welcome_screen TEXT NOT NULL,
+    widget_channel_id VARCHAR(255),
+    widget_enabled SMALLINT NOT NULL DEFAULT 1,
+    nsfw_level INTEGER,
+    nsfw SMALLINT NOT NULL DEFAULT 0,
+    parent VARCHAR(255),
+    premium_progress_bar_enabled SMALLINT DEFAULT 0,
+    channel_ordering TEXT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS roles (
+    id VARCHAR(255) PRIMARY KEY,
+    guild_id VARCHAR(255) NOT NULL REFERENCES guilds (id) ON DELETE CASCADE,
+    color INTEGER NOT NULL,
+    hoist SMALLINT NOT NULL,
+    managed SMALLINT NOT NULL,
+    mentionable SMALLINT NOT NULL,
+    name VARCHAR(255) NOT NULL,
+    permissions VARCHAR(255) NOT NULL,
+    position INTEGER NOT NULL,
+    icon VARCHAR(255),
+    unicode_emoji VARCHAR(255),
+    tags TEXT,
+    flags INTEGER NOT NULL DEFAULT 0
+);
+
+CREATE INDEX IF NOT EXISTS idx_roles_guild_id ON roles (guild_id);
+
+CREATE TABLE IF NOT EXISTS members (
+    "index" SERIAL PRIMARY KEY,
+    id VARCHAR(255) NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+    guild_id VARCHAR(255) NOT NULL REFERENCES guilds (id) ON DELETE CASCADE,
+    nick VARCHAR(255),
+    joined_at BIGINT NOT NULL,
+    premium_since BIGINT,
+    deaf SMALLINT NOT NULL,
+    mute SMALLINT NOT NULL,
+    pending SMALLINT NOT NULL,
+    settings TEXT NOT NULL,
+    last_message_id VARCHAR(255),
+    joined_by VARCHAR(255),
+    avatar VARCHAR(255),
+    banner VARCHAR(255),
+    bio VARCHAR(255) NOT NULL DEFAULT '',
+    theme_colors TEXT,
+    pronouns VARCHAR(255),
+    communication_disabled_until BIGINT
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_members_id_guild_id ON members (id, guild_id);
+
+CREATE TABLE IF NOT EXISTS member_roles (
+    "index" INTEGER NOT NULL REFERENCES members ("index") ON DELETE CASCADE,
+    role_id VARCHAR(255) NOT NULL REFERENCES roles (id) ON DELETE CASCADE,
+    PRIMARY KEY ("index", role_id)
+);
+
+CREATE TABLE IF NOT EXISTS bans (
+    id VARCHAR(255) PRIMARY KEY,
+    user_id VARCHAR(255) REFERENCES users (id) ON DELETE CASCADE,
+    guild_id VARCHAR(255) REFERENCES guilds (id) ON DELETE CASCADE,
+    executor_id VARCHAR(255),
+    ip VARCHAR(255) NOT NULL,
+    reason VARCHAR(255)
+);
+
+CREATE TABLE IF NOT EXISTS emojis (
+    id VARCHAR(255) PRIMARY KEY,
+    animated SMALLINT NOT NULL,
+    available SMALLINT NOT NULL,
+    guild_id VARCHAR(255) NOT NULL REFERENCES guilds (id) ON DELETE CASCADE,
+    user_id VARCHAR(255),
+    managed SMALLINT NOT NULL,
+    name VARCHAR(255) NOT NULL,
+    require_colons SMALLINT NOT NULL,
+    roles TEXT NOT NULL,
+    "groups" TEXT
+);
+
+CREATE TABLE IF NOT EXISTS stickers (
+    id VARCHAR(255) PRIMARY KEY,
+    name VARCHAR(255) NOT NULL,
+    description VARCHAR(255),
+    available SMALLINT,
+    tags VARCHAR(255),
+    pack_id VARCHAR(255),
+    guild_id VARCHAR(255) REFERENCES guilds (id) ON DELETE CASCADE,
+    user_id VARCHAR(255),
+    type INTEGER NOT NULL,
+    format_type INTEGER NOT NULL
+);
@@ -0,0 +1,42 @@
+-- Settings of each user, referenced by users."settingsIndex" like in the
+-- TypeORM schema. Every column is nullable there as well.
+CREATE TABLE IF NOT EXISTS user_settings (
+    "index" SERIAL PRIMARY KEY,
+    afk_timeout INTEGER,
+    allow_accessibility_detection SMALLINT,
+    animate_emoji SMALLINT,
+    animate_stickers INTEGER,
+    contact_sync_enabled SMALLINT,
+    convert_emoticons SMALLINT,
+    custom_status TEXT,
+    default_guilds_restricted SMALLINT,
+    detect_platform_accounts SMALLINT,
+    developer_mode SMALLINT,
+    disable_games_tab SMALLINT,
+    enable_tts_command SMALLINT,
+    explicit_content_filter INTEGER,
+    friend_discovery_flags INTEGER,
+    friend_source_flags TEXT,
+    gateway_connected SMALLINT,
+    gif_auto_play SMALLINT,
+    guild_folders TEXT,
+    guild_positions TEXT,
+    inline_attachment_media SMALLINT,
+    inline_embed_media SMALLINT,
+    locale VARCHAR(255),
+    message_display_compact SMALLINT,
+    native_phone_integration_enabled SMALLINT,
+    render_embeds SMALLINT,
+    render_reactions SMALLINT,
+    restricted_guilds TEXT,
+    show_current_game SMALLINT,
+    status VARCHAR(255),
+    stream_notifications_enabled SMALLINT,
+    theme VARCHAR(255),
+    timezone_offset INTEGER,
+    view_nsfw_guilds SMALLINT
+);
+
+ALTER TABLE users ADD COLUMN "settingsIndex" INTEGER REFERENCES user_settings ("index") ON DELETE SET NULL;
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_users_settings_index ON users ("settingsIndex");
@@ -0,0 +1,15 @@
+-- WebAuthn security keys. `public_key` holds the whole serialised credential
+-- rather than the PEM public key the TypeScript server stores.
+CREATE TABLE IF NOT EXISTS security_keys (
+    id VARCHAR(255) PRIMARY KEY,
+    user_id VARCHAR(255) REFERENCES users (id) ON DELETE CASCADE,
+    key_id VARCHAR(255) NOT NULL,
+    public_key VARCHAR(4096) NOT NULL,
+    counter INTEGER NOT NULL,
+    name VARCHAR(255) NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_security_keys_user_id ON security_keys (user_id);
+
+-- Login tickets of users with security keys carry the challenge state.
+ALTER TABLE users ALTER COLUMN totp_last_ticket TYPE VARCHAR(4096);
@@ -0,0 +1,9 @@
+-- Sessions are either gateway connections, as in the TypeScript server, or
+-- logins with the token that was issued for them.
+ALTER TABLE sessions ADD COLUMN kind VARCHAR(255) NOT NULL DEFAULT 'gateway';
+ALTER TABLE sessions ADD COLUMN ip VARCHAR(255);
+ALTER TABLE sessions ADD COLUMN user_agent VARCHAR(1024);
+ALTER TABLE sessions ADD COLUMN last_seen BIGINT;
+
+CREATE INDEX IF NOT EXISTS idx_sessions_user_id ON sessions (user_id);
+CREATE INDEX IF NOT EXISTS idx_sessions_session_id ON sessions (session_id);
@@ -0,0 +1,69 @@
+CREATE TABLE IF NOT EXISTS channels (
+    id VARCHAR(255) PRIMARY KEY,
+    created_at BIGINT NOT NULL,
+    name VARCHAR(255),
+    icon TEXT,
+    type INTEGER NOT NULL,
+    last_message_id VARCHAR(255),
+    guild_id VARCHAR(255) REFERENCES guilds (id) ON DELETE CASCADE,
+    parent_id VARCHAR(255),
+    owner_id VARCHAR(255),
+    last_pin_timestamp BIGINT,
+    default_auto_archive_duration INTEGER,
+    permission_overwrites TEXT,
+    video_quality_mode INTEGER,
+    bitrate INTEGER,
+    user_limit INTEGER,
+    nsfw SMALLINT NOT NULL DEFAULT 0,
+    rate_limit_per_user INTEGER,
+    topic VARCHAR(1024),
+    retention_policy_id VARCHAR(255),
+    flags INTEGER NOT NULL DEFAULT 0,
+    default_thread_rate_limit_per_user INTEGER NOT NULL DEFAULT 0
+);
+
+CREATE INDEX IF NOT EXISTS idx_channels_guild_id ON channels (guild_id);
+
+CREATE TABLE IF NOT EXISTS invites (
+    code VARCHAR(255) PRIMARY KEY,
+    temporary SMALLINT NOT NULL,
+    uses INTEGER NOT NULL,
+    max_uses INTEGER NOT NULL,
+    max_age INTEGER NOT NULL,
+    created_at BIGINT NOT NULL,
+    expires_at BIGINT,
+    guild_id VARCHAR(255) REFERENCES guilds (id) ON DELETE CASCADE,
+    channel_id VARCHAR(255) REFERENCES channels (id) ON DELETE CASCADE,
+    inviter_id VARCHAR(255) REFERENCES users (id) ON DELETE CASCADE,
+    target_user_id VARCHAR(255) REFERENCES users (id) ON DELETE CASCADE,
+    target_user_type INTEGER,
+    vanity_url SMALLINT,
+    flags INTEGER NOT NULL DEFAULT 0
+);
+
+CREATE TABLE IF NOT EXISTS webhooks (
+    id VARCHAR(255) PRIMARY KEY,
+    type INTEGER NOT NULL,
+    name VARCHAR(255),
+    avatar VARCHAR(255),
+    token VARCHAR(255),
+    guild_id VARCHAR(255) REFERENCES guilds (id) ON DELETE CASCADE,
+    channel_id VARCHAR(255) REFERENCES channels (id) ON DELETE CASCADE,
+    application_id VARCHAR(255),
+    user_id VARCHAR(255) REFERENCES users (id) ON DELETE CASCADE,
+    source_guild_id VARCHAR(255),
+    source_channel_id VARCHAR(255)
+);
+
+CREATE TABLE IF NOT EXISTS read_states (
+    id VARCHAR(255) PRIMARY KEY,
+    channel_id VARCHAR(255) NOT NULL REFERENCES channels (id) ON DELETE CASCADE,
+    user_id VARCHAR(255) NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+    last_message_id VARCHAR(255),
+    public_ack VARCHAR(255),
+    notifications_cursor VARCHAR(255),
+    last_pin_timestamp BIGINT,
+    mention_count INTEGER
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_read_states_channel_user ON read_states (channel_id, user_id);
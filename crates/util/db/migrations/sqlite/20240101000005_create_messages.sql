@@ -0,0 +1,67 @@
+CREATE TABLE IF NOT EXISTS messages (
+    id VARCHAR(255) PRIMARY KEY,
+    channel_id VARCHAR(255) REFERENCES channels (id) ON DELETE CASCADE,
+    guild_id VARCHAR(255) REFERENCES guilds (id) ON DELETE CASCADE,
+    author_id VARCHAR(255) REFERENCES users (id) ON DELETE CASCADE,
+    member_id VARCHAR(255),
+    webhook_id VARCHAR(255),
+    application_id VARCHAR(255),
+    content TEXT,
+    timestamp BIGINT NOT NULL,
+    edited_timestamp BIGINT,
+    tts SMALLINT,
+    mention_everyone SMALLINT,
+    embeds TEXT NOT NULL,
+    reactions TEXT NOT NULL,
+    nonce TEXT,
+    pinned SMALLINT,
+    type INTEGER NOT NULL,
+    activity TEXT,
+    flags INTEGER NOT NULL DEFAULT 0,
+    message_reference TEXT,
+    message_reference_id VARCHAR(255),
+    interaction TEXT,
+    components TEXT,
+    poll TEXT,
+    username VARCHAR(255),
+    avatar VARCHAR(255)
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_messages_channel_id_id ON messages (channel_id, id);
+CREATE INDEX IF NOT EXISTS idx_messages_author_id ON messages (author_id);
+
+CREATE TABLE IF NOT EXISTS attachments (
+    id VARCHAR(255) PRIMARY KEY,
+    filename VARCHAR(255) NOT NULL,
+    size INTEGER NOT NULL,
+    url TEXT NOT NULL,
+    proxy_url TEXT NOT NULL,
+    height INTEGER,
+    width INTEGER,
+    content_type VARCHAR(255),
+    message_id VARCHAR(255) REFERENCES messages (id) ON DELETE CASCADE
+);
+
+CREATE TABLE IF NOT EXISTS message_user_mentions (
+    "messagesId" VARCHAR(255) NOT NULL REFERENCES messages (id) ON DELETE CASCADE,
+    "usersId" VARCHAR(255) NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+    PRIMARY KEY ("messagesId", "usersId")
+);
+
+CREATE TABLE IF NOT EXISTS message_role_mentions (
+    "messagesId" VARCHAR(255) NOT NULL REFERENCES messages (id) ON DELETE CASCADE,
+    "rolesId" VARCHAR(255) NOT NULL REFERENCES roles (id) ON DELETE CASCADE,
+    PRIMARY KEY ("messagesId", "rolesId")
+);
+
+CREATE TABLE IF NOT EXISTS message_channel_mentions (
+    "messagesId" VARCHAR(255) NOT NULL REFERENCES messages (id) ON DELETE CASCADE,
+    "channelsId" VARCHAR(255) NOT NULL REFERENCES channels (id) ON DELETE CASCADE,
+    PRIMARY KEY ("messagesId", "channelsId")
+);
+
+CREATE TABLE IF NOT EXISTS message_stickers (
+    "messagesId" VARCHAR(255) NOT NULL REFERENCES messages (id) ON DELETE CASCADE,
+    "stickersId" VARCHAR(255) NOT NULL REFERENCES stickers (id) ON DELETE CASCADE,
+    PRIMARY KEY ("messagesId", "stickersId")
+);
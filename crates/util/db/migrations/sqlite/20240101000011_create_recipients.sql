@@ -0,0 +1,11 @@
+-- Members of direct message channels. The TypeScript schema already has
+-- this table, with `closed` converted on import.
+CREATE TABLE IF NOT EXISTS recipients (
+    id VARCHAR(255) PRIMARY KEY,
+    channel_id VARCHAR(255) NOT NULL REFERENCES channels (id) ON DELETE CASCADE,
+    user_id VARCHAR(255) NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+    closed SMALLINT NOT NULL DEFAULT 0
+);
+
+CREATE INDEX IF NOT EXISTS idx_recipients_channel_id ON recipients (channel_id);
+CREATE INDEX IF NOT EXISTS idx_recipients_user_id ON recipients (user_id);
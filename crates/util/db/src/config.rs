@@ -0,0 +1,206 @@
+//! Configuration stored in the `config` table.
+
+use std::{sync::Arc, time::Duration};
+
+use ::config::Config as ConfigValue;
+use events::{emit_event, listen_event, Event, CONFIG_EVENT_ID};
+use serde_json::{json, Value};
+use tokio::{sync::Notify, task::JoinHandle};
+
+use crate::{entities::Config, types::Json, DbPool};
+
+/// How often the table and the configuration file are checked for changes.
+const RELOAD_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Load the configuration from the database and install it as the
+/// process-wide [`ConfigValue`].
+///
+/// An empty table is seeded from `config.json` (or `CONFIG_PATH`) when that
+/// file exists and from the defaults otherwise. Fields added since the table
+/// was last written are stored with their default value, and empty secrets
+/// are replaced with random ones. `SPACEBAR__*`
+/// environment variables override the stored values without being saved.
+pub async fn init_config(pool: &DbPool) -> Result<Arc<ConfigValue>, sqlx::Error> {
+    println!("[Config] Loading configuration...");
+
+    let rows = Config::all(pool).await?;
+    let mut config = if rows.is_empty() {
+        match ConfigValue::from_file()
+            .await
+            .map_err(|e| sqlx::Error::Configuration(e.into()))?
+        {
+            Some(config) => {
+                println!("[Config] Seeding the database from the configuration file.");
+                config
+            }
+            None => ConfigValue::default(),
+        }
+    } else {
+        from_rows(&rows)?
+    };
+
+    let generated = config.generate_secrets();
+    let missing: Vec<Config> = to_rows(&config)
+        .into_iter()
+        .filter(|pair| {
+            generated.contains(&pair.key.as_str()) || !rows.iter().any(|row| row.key == pair.key)
+        })
+        .collect();
+    Config::save_all(pool, &missing).await?;
+    log_generated(&generated);
+
+    let config = with_env(&config)?;
+    Ok(ConfigValue::init_with(config).await)
+}
+
+/// Write every field of `config` to the database, make it the process-wide
+/// configuration and tell the other services about it.
+pub async fn save_config(pool: &DbPool, config: &ConfigValue) -> Result<(), sqlx::Error> {
+    Config::save_all(pool, &to_rows(config)).await?;
+    apply(with_env(config)?, true).await;
+    Ok(())
+}
+
+/// Keep the process-wide configuration in sync with the `config` table and
+/// the configuration file.
+///
+/// Edits to the file are written to the table. Changes are picked up every
+/// few seconds, or as soon as another service sends a `CONFIG_UPDATE` event.
+/// Changes found here are announced with the same event.
+pub fn watch_config(pool: DbPool) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let notify = Arc::new(Notify::new());
+        let listener = notify.clone();
+        let _cancel = listen_event(CONFIG_EVENT_ID, move |_| listener.notify_one())
+            .await
+            .ok();
+
+        let mut modified = file_modified().await;
+        let mut interval = tokio::time::interval(RELOAD_INTERVAL);
+        loop {
+            let announced = tokio::select! {
+                _ = interval.tick() => false,
+                _ = notify.notified() => true,
+            };
+
+            let current = file_modified().await;
+            if current != modified {
+                modified = current;
+                if let Err(e) = save_file(&pool).await {
+                    eprintln!("[Config] Ignoring configuration file: {e}");
+                }
+            }
+
+            let reloaded = match Config::all(&pool).await.and_then(|rows| from_rows(&rows)) {
+                Ok(config) => regenerate_secrets(&pool, config).await,
+                Err(e) => Err(e),
+            }
+            .and_then(|config| with_env(&config));
+            match reloaded {
+                Ok(config) => apply(config, !announced).await,
+                Err(e) => eprintln!("[Config] Failed to reload configuration: {e}"),
+            }
+        }
+    })
+}
+
+/// Install `config` if it differs from the current configuration, sending a
+/// `CONFIG_UPDATE` event with the changed keys when `announce` is set.
+async fn apply(config: ConfigValue, announce: bool) {
+    let current = ConfigValue::get().to_pairs();
+    let changed: Vec<String> = config
+        .to_pairs()
+        .into_iter()
+        .filter(|pair| !current.contains(pair))
+        .map(|(key, _)| key)
+        .collect();
+    if changed.is_empty() {
+        return;
+    }
+
+    println!("[Config] Reloaded {}", changed.join(", "));
+    ConfigValue::set(config);
+
+    if announce {
+        let event = Event {
+            event: "CONFIG_UPDATE".into(),
+            data: json!({ "keys": changed }),
+            guild_id: Some(CONFIG_EVENT_ID.into()),
+            channel_id: None,
+            user_id: None,
+        };
+        if let Err(e) = emit_event(event).await {
+            eprintln!("[Config] Failed to announce configuration change: {e}");
+        }
+    }
+}
+
+/// Replace secrets that were cleared since the last reload.
+async fn regenerate_secrets(
+    pool: &DbPool,
+    mut config: ConfigValue,
+) -> Result<ConfigValue, sqlx::Error> {
+    let generated = config.generate_secrets();
+    if !generated.is_empty() {
+        let rows: Vec<Config> = to_rows(&config)
+            .into_iter()
+            .filter(|row| generated.contains(&row.key.as_str()))
+            .collect();
+        Config::save_all(pool, &rows).await?;
+        log_generated(&generated);
+    }
+    Ok(config)
+}
+
+fn log_generated(keys: &[&str]) {
+    for key in keys {
+        println!("[Config] Generated a random {key} and saved it to the database.");
+    }
+}
+
+/// Write the fields set in the configuration file over the stored ones.
+async fn save_file(pool: &DbPool) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let Some(patch) = ConfigValue::read_file().await? else {
+        return Ok(());
+    };
+    // Merge onto the stored rows rather than the live configuration so
+    // environment overrides are not persisted.
+    let stored = from_rows(&Config::all(pool).await?)?;
+    let config = stored.merged(&patch)?;
+    println!("[Config] Configuration file changed, saving it to the database.");
+    Config::save_all(pool, &to_rows(&config)).await?;
+    Ok(())
+}
+
+async fn file_modified() -> Option<std::time::SystemTime> {
+    tokio::fs::metadata(ConfigValue::path())
+        .await
+        .and_then(|meta| meta.modified())
+        .ok()
+}
+
+fn from_rows(rows: &[Config]) -> Result<ConfigValue, sqlx::Error> {
+    let pairs = rows.iter().map(|row| {
+        let value = row.value.clone().map(|value| value.0);
+        (row.key.clone(), value.unwrap_or_default())
+    });
+    ConfigValue::from_pairs(pairs).map_err(|e| sqlx::Error::Configuration(e.into()))
+}
+
+fn with_env(config: &ConfigValue) -> Result<ConfigValue, sqlx::Error> {
+    config
+        .with_env()
+        .map_err(|e| sqlx::Error::Configuration(e.into()))
+}
+
+fn to_rows(config: &ConfigValue) -> Vec<Config> {
+    config
+        .to_pairs()
+        .into_iter()
+        .map(|(key, value)| Config {
+            key,
+            // Like TypeORM, store unset fields as NULL rather than `null`.
+            value: (value != Value::Null).then_some(Json(value)),
+        })
+        .collect()
+}
@@ -0,0 +1,59 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+use crate::{DbPool, Dialect};
+
+/// Row of the `attachments` table.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct Attachment {
+    pub id: String,
+    pub filename: String,
+    pub size: i64,
+    pub url: String,
+    pub proxy_url: String,
+    pub height: Option<i32>,
+    pub width: Option<i32>,
+    pub content_type: Option<String>,
+    pub message_id: Option<String>,
+}
+
+impl Attachment {
+    /// Attachments of the message `message_id`.
+    pub async fn find_by_message(
+        pool: &DbPool,
+        message_id: &str,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        let dialect = Dialect::of(pool);
+        sqlx::query_as(&format!(
+            "SELECT * FROM attachments WHERE message_id = {}",
+            dialect.placeholder(1)
+        ))
+        .bind(message_id)
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Insert a new attachment.
+    pub async fn insert<'c, E>(&self, executor: E, dialect: Dialect) -> Result<(), sqlx::Error>
+    where
+        E: sqlx::Executor<'c, Database = sqlx::Any>,
+    {
+        let placeholders: Vec<String> = (1..=9).map(|n| dialect.placeholder(n)).collect();
+        sqlx::query(&format!(
+            "INSERT INTO attachments (id, filename, size, url, proxy_url, height, width, content_type, message_id) VALUES ({})",
+            placeholders.join(", ")
+        ))
+        .bind(&self.id)
+        .bind(&self.filename)
+        .bind(self.size)
+        .bind(&self.url)
+        .bind(&self.proxy_url)
+        .bind(self.height)
+        .bind(self.width)
+        .bind(&self.content_type)
+        .bind(&self.message_id)
+        .execute(executor)
+        .await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,100 @@
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use sqlx::FromRow;
+use util::Snowflake;
+
+use crate::{types::Bool, DbPool, Dialect};
+
+/// Row of the `backup_codes` table, a single-use MFA recovery code.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct BackupCode {
+    pub id: String,
+    /// SHA-256 hash of the code, see [`BackupCode::hash`].
+    pub code: String,
+    pub consumed: Bool,
+    pub expired: Bool,
+    pub user_id: Option<String>,
+}
+
+impl BackupCode {
+    /// Hex SHA-256 hash of `code` as stored in the `code` column.
+    ///
+    /// Case, spaces and dashes are ignored, so `ABCD-1234` matches `abcd1234`.
+    pub fn hash(code: &str) -> String {
+        let normalized: String = code
+            .chars()
+            .filter(|c| !c.is_whitespace() && *c != '-')
+            .map(|c| c.to_ascii_lowercase())
+            .collect();
+        format!("{:x}", Sha256::digest(normalized.as_bytes()))
+    }
+
+    /// Codes of the user `user_id` that are neither consumed nor expired.
+    pub async fn find_active(pool: &DbPool, user_id: &str) -> Result<Vec<Self>, sqlx::Error> {
+        let dialect = Dialect::of(pool);
+        sqlx::query_as(&format!(
+            "SELECT * FROM backup_codes WHERE user_id = {} AND consumed = 0 AND expired = 0",
+            dialect.placeholder(1)
+        ))
+        .bind(user_id)
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Expire every code of the user `user_id` and store `codes` instead.
+    pub async fn replace(
+        pool: &DbPool,
+        user_id: &str,
+        codes: &[String],
+    ) -> Result<(), sqlx::Error> {
+        let dialect = Dialect::of(pool);
+        let mut tx = pool.begin().await?;
+        sqlx::query(&format!(
+            "UPDATE backup_codes SET expired = 1 WHERE user_id = {}",
+            dialect.placeholder(1)
+        ))
+        .bind(user_id)
+        .execute(&mut *tx)
+        .await?;
+
+        let sql = format!(
+            "INSERT INTO backup_codes (id, code, consumed, expired, user_id) VALUES ({}, {}, 0, 0, {})",
+            dialect.placeholder(1),
+            dialect.placeholder(2),
+            dialect.placeholder(3)
+        );
+        for code in codes {
+            sqlx::query(&sql)
+                .bind(Snowflake::generate().to_string())
+                .bind(Self::hash(code))
+                .bind(user_id)
+                .execute(&mut *tx)
+                .await?;
+        }
+        tx.commit().await
+    }
+
+    /// Expire every code of the user `user_id`.
+    pub async fn expire_all(pool: &DbPool, user_id: &str) -> Result<(), sqlx::Error> {
+        Self::replace(pool, user_id, &[]).await
+    }
+
+    /// Mark the active code `code` of the user `user_id` as consumed.
+    ///
+    /// Returns whether there was such a code, so each code is accepted at
+    /// most once even under concurrent requests.
+    pub async fn consume(pool: &DbPool, user_id: &str, code: &str) -> Result<bool, sqlx::Error> {
+        let dialect = Dialect::of(pool);
+        let result = sqlx::query(&format!(
+            "UPDATE backup_codes SET consumed = 1 \
+             WHERE user_id = {} AND code = {} AND consumed = 0 AND expired = 0",
+            dialect.placeholder(1),
+            dialect.placeholder(2)
+        ))
+        .bind(user_id)
+        .bind(Self::hash(code))
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+}
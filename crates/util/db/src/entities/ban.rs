@@ -0,0 +1,13 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+/// Row of the `bans` table.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct Ban {
+    pub id: String,
+    pub user_id: Option<String>,
+    pub guild_id: Option<String>,
+    pub executor_id: Option<String>,
+    pub ip: String,
+    pub reason: Option<String>,
+}
@@ -0,0 +1,289 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+pub use util::PermissionOverwrite as ChannelPermissionOverwrite;
+
+use crate::{
+    types::{Bool, Json, Timestamp},
+    DbPool, Dialect,
+};
+
+/// `type` of a text channel in a guild.
+pub const CHANNEL_GUILD_TEXT: i32 = 0;
+/// `type` of a direct message between two users.
+pub const CHANNEL_DM: i32 = 1;
+/// `type` of a voice channel in a guild.
+pub const CHANNEL_GUILD_VOICE: i32 = 2;
+/// `type` of a direct message between several users.
+pub const CHANNEL_GROUP_DM: i32 = 3;
+/// `type` of a category grouping the channels of a guild.
+pub const CHANNEL_GUILD_CATEGORY: i32 = 4;
+/// `type` of a text channel other guilds can follow.
+pub const CHANNEL_GUILD_ANNOUNCEMENT: i32 = 5;
+/// `type` of a voice channel for hosting events with an audience.
+pub const CHANNEL_GUILD_STAGE_VOICE: i32 = 13;
+/// `type` of a channel holding only threads.
+pub const CHANNEL_GUILD_FORUM: i32 = 15;
+
+/// Row of the `channels` table.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct Channel {
+    pub id: String,
+    pub created_at: Timestamp,
+    pub name: Option<String>,
+    pub icon: Option<String>,
+    #[sqlx(rename = "type")]
+    #[serde(rename = "type")]
+    pub kind: i32,
+    pub last_message_id: Option<String>,
+    pub guild_id: Option<String>,
+    pub parent_id: Option<String>,
+    pub owner_id: Option<String>,
+    pub last_pin_timestamp: Option<i64>,
+    pub default_auto_archive_duration: Option<i32>,
+    pub permission_overwrites: Option<Json<Vec<ChannelPermissionOverwrite>>>,
+    pub video_quality_mode: Option<i32>,
+    pub bitrate: Option<i32>,
+    pub user_limit: Option<i32>,
+    pub nsfw: Bool,
+    pub rate_limit_per_user: Option<i32>,
+    pub topic: Option<String>,
+    pub retention_policy_id: Option<String>,
+    pub flags: i32,
+    pub default_thread_rate_limit_per_user: i32,
+}
+
+/// Columns of the `channels` table in the order [`Channel::bind`] binds
+/// them.
+const COLUMNS: &[&str] = &[
+    "id",
+    "created_at",
+    "name",
+    "icon",
+    "type",
+    "last_message_id",
+    "guild_id",
+    "parent_id",
+    "owner_id",
+    "last_pin_timestamp",
+    "default_auto_archive_duration",
+    "permission_overwrites",
+    "video_quality_mode",
+    "bitrate",
+    "user_limit",
+    "nsfw",
+    "rate_limit_per_user",
+    "topic",
+    "retention_policy_id",
+    "flags",
+    "default_thread_rate_limit_per_user",
+];
+
+impl Channel {
+    /// The channel with the given ID, if any.
+    pub async fn find(pool: &DbPool, id: &str) -> Result<Option<Self>, sqlx::Error> {
+        let dialect = Dialect::of(pool);
+        sqlx::query_as(&format!(
+            "SELECT * FROM channels WHERE id = {}",
+            dialect.placeholder(1)
+        ))
+        .bind(id)
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Channels of the guild `guild_id`.
+    pub async fn find_by_guild(pool: &DbPool, guild_id: &str) -> Result<Vec<Self>, sqlx::Error> {
+        let dialect = Dialect::of(pool);
+        sqlx::query_as(&format!(
+            "SELECT * FROM channels WHERE guild_id = {}",
+            dialect.placeholder(1)
+        ))
+        .bind(guild_id)
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Channels of the category `parent_id`.
+    pub async fn find_by_parent(pool: &DbPool, parent_id: &str) -> Result<Vec<Self>, sqlx::Error> {
+        let dialect = Dialect::of(pool);
+        sqlx::query_as(&format!(
+            "SELECT * FROM channels WHERE parent_id = {}",
+            dialect.placeholder(1)
+        ))
+        .bind(parent_id)
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Direct messages the user `user_id` has not closed.
+    pub async fn find_open_dms(pool: &DbPool, user_id: &str) -> Result<Vec<Self>, sqlx::Error> {
+        let dialect = Dialect::of(pool);
+        sqlx::query_as(&format!(
+            "SELECT channels.* FROM channels \
+             JOIN recipients ON recipients.channel_id = channels.id \
+             WHERE recipients.user_id = {} AND recipients.closed = 0",
+            dialect.placeholder(1)
+        ))
+        .bind(user_id)
+        .fetch_all(pool)
+        .await
+    }
+
+    /// The direct message between the users `user_id` and `recipient_id`,
+    /// if they already have one.
+    pub async fn find_dm(
+        pool: &DbPool,
+        user_id: &str,
+        recipient_id: &str,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        let dialect = Dialect::of(pool);
+        sqlx::query_as(&format!(
+            "SELECT channels.* FROM channels \
+             JOIN recipients a ON a.channel_id = channels.id AND a.user_id = {} \
+             JOIN recipients b ON b.channel_id = channels.id AND b.user_id = {} \
+             WHERE channels.{} = {}",
+            dialect.placeholder(1),
+            dialect.placeholder(2),
+            dialect.quote("type"),
+            dialect.placeholder(3)
+        ))
+        .bind(user_id)
+        .bind(recipient_id)
+        .bind(CHANNEL_DM)
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Number of channels in the guild `guild_id`.
+    pub async fn count_by_guild(pool: &DbPool, guild_id: &str) -> Result<i64, sqlx::Error> {
+        let dialect = Dialect::of(pool);
+        sqlx::query_scalar(&format!(
+            "SELECT COUNT(*) FROM channels WHERE guild_id = {}",
+            dialect.placeholder(1)
+        ))
+        .bind(guild_id)
+        .fetch_one(pool)
+        .await
+    }
+
+    /// Number of channels in the category `parent_id`.
+    pub async fn count_by_parent(pool: &DbPool, parent_id: &str) -> Result<i64, sqlx::Error> {
+        let dialect = Dialect::of(pool);
+        sqlx::query_scalar(&format!(
+            "SELECT COUNT(*) FROM channels WHERE parent_id = {}",
+            dialect.placeholder(1)
+        ))
+        .bind(parent_id)
+        .fetch_one(pool)
+        .await
+    }
+
+    /// Bind every column of the channel in the order of [`COLUMNS`].
+    fn bind<'q>(
+        &'q self,
+        query: sqlx::query::Query<'q, sqlx::Any, sqlx::any::AnyArguments<'q>>,
+    ) -> sqlx::query::Query<'q, sqlx::Any, sqlx::any::AnyArguments<'q>> {
+        query
+            .bind(&self.id)
+            .bind(self.created_at)
+            .bind(&self.name)
+            .bind(&self.icon)
+            .bind(self.kind)
+            .bind(&self.last_message_id)
+            .bind(&self.guild_id)
+            .bind(&self.parent_id)
+            .bind(&self.owner_id)
+            .bind(self.last_pin_timestamp)
+            .bind(self.default_auto_archive_duration)
+            .bind(&self.permission_overwrites)
+            .bind(self.video_quality_mode)
+            .bind(self.bitrate)
+            .bind(self.user_limit)
+            .bind(self.nsfw)
+            .bind(self.rate_limit_per_user)
+            .bind(&self.topic)
+            .bind(&self.retention_policy_id)
+            .bind(self.flags)
+            .bind(self.default_thread_rate_limit_per_user)
+    }
+
+    /// Insert a new channel.
+    pub async fn insert<'c, E>(&self, executor: E, dialect: Dialect) -> Result<(), sqlx::Error>
+    where
+        E: sqlx::Executor<'c, Database = sqlx::Any>,
+    {
+        let columns: Vec<String> = COLUMNS.iter().map(|column| dialect.quote(column)).collect();
+        let placeholders: Vec<String> = (1..=COLUMNS.len())
+            .map(|n| dialect.placeholder(n))
+            .collect();
+        let sql = format!(
+            "INSERT INTO channels ({}) VALUES ({})",
+            columns.join(", "),
+            placeholders.join(", ")
+        );
+        self.bind(sqlx::query(&sql)).execute(executor).await?;
+        Ok(())
+    }
+
+    /// Write every column of the channel back to its row.
+    pub async fn update<'c, E>(&self, executor: E, dialect: Dialect) -> Result<(), sqlx::Error>
+    where
+        E: sqlx::Executor<'c, Database = sqlx::Any>,
+    {
+        let assignments: Vec<String> = COLUMNS
+            .iter()
+            .enumerate()
+            .map(|(i, column)| {
+                format!("{} = {}", dialect.quote(column), dialect.placeholder(i + 1))
+            })
+            .collect();
+        let sql = format!(
+            "UPDATE channels SET {} WHERE id = {}",
+            assignments.join(", "),
+            dialect.placeholder(COLUMNS.len() + 1)
+        );
+        self.bind(sqlx::query(&sql))
+            .bind(&self.id)
+            .execute(executor)
+            .await?;
+        Ok(())
+    }
+
+    /// Record `message_id` as the latest message of the channel `id`.
+    pub async fn set_last_message_id<'c, E>(
+        executor: E,
+        dialect: Dialect,
+        id: &str,
+        message_id: &str,
+    ) -> Result<(), sqlx::Error>
+    where
+        E: sqlx::Executor<'c, Database = sqlx::Any>,
+    {
+        sqlx::query(&format!(
+            "UPDATE channels SET last_message_id = {} WHERE id = {}",
+            dialect.placeholder(1),
+            dialect.placeholder(2)
+        ))
+        .bind(message_id)
+        .bind(id)
+        .execute(executor)
+        .await?;
+        Ok(())
+    }
+
+    /// Delete the channel with the given ID along with its messages.
+    pub async fn delete<'c, E>(executor: E, dialect: Dialect, id: &str) -> Result<(), sqlx::Error>
+    where
+        E: sqlx::Executor<'c, Database = sqlx::Any>,
+    {
+        sqlx::query(&format!(
+            "DELETE FROM channels WHERE id = {}",
+            dialect.placeholder(1)
+        ))
+        .bind(id)
+        .execute(executor)
+        .await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,50 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sqlx::FromRow;
+
+use crate::{types::Json, DbPool, Dialect};
+
+/// Row of the `config` table: one configuration field under its dotted key.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct Config {
+    pub key: String,
+    pub value: Option<Json<Value>>,
+}
+
+impl Config {
+    /// Every stored configuration field.
+    pub async fn all(pool: &DbPool) -> Result<Vec<Self>, sqlx::Error> {
+        let dialect = Dialect::of(pool);
+        sqlx::query_as(&format!(
+            "SELECT {}, value FROM config",
+            dialect.quote("key")
+        ))
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Insert the given rows, replacing the value of existing keys.
+    pub async fn save_all(pool: &DbPool, rows: &[Self]) -> Result<(), sqlx::Error> {
+        let dialect = Dialect::of(pool);
+        let sql = match dialect {
+            Dialect::Mysql | Dialect::MariaDb => {
+                "INSERT INTO config (`key`, value) VALUES (?, ?) \
+                 ON DUPLICATE KEY UPDATE value = VALUES(value)"
+            }
+            Dialect::Sqlite | Dialect::Postgres => {
+                "INSERT INTO config (key, value) VALUES ($1, $2) \
+                 ON CONFLICT (key) DO UPDATE SET value = excluded.value"
+            }
+        };
+
+        let mut tx = pool.begin().await?;
+        for row in rows {
+            sqlx::query(sql)
+                .bind(&row.key)
+                .bind(&row.value)
+                .execute(&mut *tx)
+                .await?;
+        }
+        tx.commit().await
+    }
+}
@@ -0,0 +1,36 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+use crate::{
+    types::{Bool, SimpleArray},
+    DbPool, Dialect,
+};
+
+/// Row of the `emojis` table.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct Emoji {
+    pub id: String,
+    pub animated: Bool,
+    pub available: Bool,
+    pub guild_id: String,
+    pub user_id: Option<String>,
+    pub managed: Bool,
+    pub name: String,
+    pub require_colons: Bool,
+    pub roles: SimpleArray,
+    pub groups: Option<SimpleArray>,
+}
+
+impl Emoji {
+    /// The custom emoji `id`, if any.
+    pub async fn find(pool: &DbPool, id: &str) -> Result<Option<Self>, sqlx::Error> {
+        let dialect = Dialect::of(pool);
+        sqlx::query_as(&format!(
+            "SELECT * FROM emojis WHERE id = {}",
+            dialect.placeholder(1)
+        ))
+        .bind(id)
+        .fetch_optional(pool)
+        .await
+    }
+}
@@ -0,0 +1,270 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sqlx::FromRow;
+
+use crate::{
+    types::{Bool, Json, SimpleArray},
+    DbPool, Dialect,
+};
+
+/// Row of the `guilds` table.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct Guild {
+    pub id: String,
+    pub afk_channel_id: Option<String>,
+    pub afk_timeout: Option<i32>,
+    pub banner: Option<String>,
+    pub default_message_notifications: Option<i32>,
+    pub description: Option<String>,
+    pub discovery_splash: Option<String>,
+    pub explicit_content_filter: Option<i32>,
+    pub features: SimpleArray,
+    pub primary_category_id: Option<String>,
+    pub icon: Option<String>,
+    pub large: Bool,
+    pub max_members: Option<i32>,
+    pub max_presences: Option<i32>,
+    pub max_video_channel_users: Option<i32>,
+    pub member_count: Option<i32>,
+    pub presence_count: Option<i32>,
+    pub template_id: Option<String>,
+    pub mfa_level: Option<i32>,
+    pub name: String,
+    pub owner_id: Option<String>,
+    pub preferred_locale: Option<String>,
+    pub premium_subscription_count: Option<i32>,
+    pub premium_tier: i32,
+    pub public_updates_channel_id: Option<String>,
+    pub rules_channel_id: Option<String>,
+    pub region: Option<String>,
+    pub splash: Option<String>,
+    pub system_channel_id: Option<String>,
+    pub system_channel_flags: Option<i32>,
+    pub unavailable: Bool,
+    pub verification_level: Option<i32>,
+    pub welcome_screen: Json<Value>,
+    pub widget_channel_id: Option<String>,
+    pub widget_enabled: Bool,
+    pub nsfw_level: Option<i32>,
+    pub nsfw: Bool,
+    pub parent: Option<String>,
+    pub premium_progress_bar_enabled: Option<Bool>,
+    #[serde(skip)]
+    pub channel_ordering: SimpleArray,
+}
+
+/// Columns of the `guilds` table in the order [`Guild::bind`] binds them.
+const COLUMNS: &[&str] = &[
+    "id",
+    "afk_channel_id",
+    "afk_timeout",
+    "banner",
+    "default_message_notifications",
+    "description",
+    "discovery_splash",
+    "explicit_content_filter",
+    "features",
+    "primary_category_id",
+    "icon",
+    "large",
+    "max_members",
+    "max_presences",
+    "max_video_channel_users",
+    "member_count",
+    "presence_count",
+    "template_id",
+    "mfa_level",
+    "name",
+    "owner_id",
+    "preferred_locale",
+    "premium_subscription_count",
+    "premium_tier",
+    "public_updates_channel_id",
+    "rules_channel_id",
+    "region",
+    "splash",
+    "system_channel_id",
+    "system_channel_flags",
+    "unavailable",
+    "verification_level",
+    "welcome_screen",
+    "widget_channel_id",
+    "widget_enabled",
+    "nsfw_level",
+    "nsfw",
+    "parent",
+    "premium_progress_bar_enabled",
+    "channel_ordering",
+];
+
+impl Guild {
+    /// The guild with the given ID, if any.
+    pub async fn find(pool: &DbPool, id: &str) -> Result<Option<Self>, sqlx::Error> {
+        let dialect = Dialect::of(pool);
+        sqlx::query_as(&format!(
+            "SELECT * FROM guilds WHERE id = {}",
+            dialect.placeholder(1)
+        ))
+        .bind(id)
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Guilds the user `user_id` is a member of, in the order they joined.
+    pub async fn find_by_member(pool: &DbPool, user_id: &str) -> Result<Vec<Self>, sqlx::Error> {
+        let dialect = Dialect::of(pool);
+        sqlx::query_as(&format!(
+            "SELECT guilds.* FROM guilds JOIN members ON members.guild_id = guilds.id \
+             WHERE members.id = {} ORDER BY members.{}",
+            dialect.placeholder(1),
+            dialect.quote("index")
+        ))
+        .bind(user_id)
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Bind every column of the guild in the order of [`COLUMNS`].
+    fn bind<'q>(
+        &'q self,
+        query: sqlx::query::Query<'q, sqlx::Any, sqlx::any::AnyArguments<'q>>,
+    ) -> sqlx::query::Query<'q, sqlx::Any, sqlx::any::AnyArguments<'q>> {
+        query
+            .bind(&self.id)
+            .bind(&self.afk_channel_id)
+            .bind(self.afk_timeout)
+            .bind(&self.banner)
+            .bind(self.default_message_notifications)
+            .bind(&self.description)
+            .bind(&self.discovery_splash)
+            .bind(self.explicit_content_filter)
+            .bind(&self.features)
+            .bind(&self.primary_category_id)
+            .bind(&self.icon)
+            .bind(self.large)
+            .bind(self.max_members)
+            .bind(self.max_presences)
+            .bind(self.max_video_channel_users)
+            .bind(self.member_count)
+            .bind(self.presence_count)
+            .bind(&self.template_id)
+            .bind(self.mfa_level)
+            .bind(&self.name)
+            .bind(&self.owner_id)
+            .bind(&self.preferred_locale)
+            .bind(self.premium_subscription_count)
+            .bind(self.premium_tier)
+            .bind(&self.public_updates_channel_id)
+            .bind(&self.rules_channel_id)
+            .bind(&self.region)
+            .bind(&self.splash)
+            .bind(&self.system_channel_id)
+            .bind(self.system_channel_flags)
+            .bind(self.unavailable)
+            .bind(self.verification_level)
+            .bind(&self.welcome_screen)
+            .bind(&self.widget_channel_id)
+            .bind(self.widget_enabled)
+            .bind(self.nsfw_level)
+            .bind(self.nsfw)
+            .bind(&self.parent)
+            .bind(self.premium_progress_bar_enabled)
+            .bind(&self.channel_ordering)
+    }
+
+    /// Insert a new guild.
+    pub async fn insert<'c, E>(&self, executor: E, dialect: Dialect) -> Result<(), sqlx::Error>
+    where
+        E: sqlx::Executor<'c, Database = sqlx::Any>,
+    {
+        let placeholders: Vec<String> = (1..=COLUMNS.len())
+            .map(|n| dialect.placeholder(n))
+            .collect();
+        let sql = format!(
+            "INSERT INTO guilds ({}) VALUES ({})",
+            COLUMNS.join(", "),
+            placeholders.join(", ")
+        );
+        self.bind(sqlx::query(&sql)).execute(executor).await?;
+        Ok(())
+    }
+
+    /// Write every column of the guild back to its row.
+    pub async fn update<'c, E>(&self, executor: E, dialect: Dialect) -> Result<(), sqlx::Error>
+    where
+        E: sqlx::Executor<'c, Database = sqlx::Any>,
+    {
+        let assignments: Vec<String> = COLUMNS
+            .iter()
+            .enumerate()
+            .map(|(i, column)| format!("{column} = {}", dialect.placeholder(i + 1)))
+            .collect();
+        let sql = format!(
+            "UPDATE guilds SET {} WHERE id = {}",
+            assignments.join(", "),
+            dialect.placeholder(COLUMNS.len() + 1)
+        );
+        self.bind(sqlx::query(&sql))
+            .bind(&self.id)
+            .execute(executor)
+            .await?;
+        Ok(())
+    }
+
+    /// Delete the guild with the given ID along with everything in it.
+    pub async fn delete(pool: &DbPool, id: &str) -> Result<(), sqlx::Error> {
+        let dialect = Dialect::of(pool);
+        sqlx::query(&format!(
+            "DELETE FROM guilds WHERE id = {}",
+            dialect.placeholder(1)
+        ))
+        .bind(id)
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Replace the order of the channels of the guild `id`.
+    pub async fn set_channel_ordering<'c, E>(
+        executor: E,
+        dialect: Dialect,
+        id: &str,
+        ordering: &[String],
+    ) -> Result<(), sqlx::Error>
+    where
+        E: sqlx::Executor<'c, Database = sqlx::Any>,
+    {
+        sqlx::query(&format!(
+            "UPDATE guilds SET channel_ordering = {} WHERE id = {}",
+            dialect.placeholder(1),
+            dialect.placeholder(2)
+        ))
+        .bind(SimpleArray(ordering.to_vec()))
+        .bind(id)
+        .execute(executor)
+        .await?;
+        Ok(())
+    }
+
+    /// Add `delta` to the member count of the guild `id`.
+    pub async fn add_member_count<'c, E>(
+        executor: E,
+        dialect: Dialect,
+        id: &str,
+        delta: i32,
+    ) -> Result<(), sqlx::Error>
+    where
+        E: sqlx::Executor<'c, Database = sqlx::Any>,
+    {
+        sqlx::query(&format!(
+            "UPDATE guilds SET member_count = COALESCE(member_count, 0) + {} WHERE id = {}",
+            dialect.placeholder(1),
+            dialect.placeholder(2)
+        ))
+        .bind(delta)
+        .bind(id)
+        .execute(executor)
+        .await?;
+        Ok(())
+    }
+}
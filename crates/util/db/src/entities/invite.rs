@@ -0,0 +1,81 @@
+use serde::{Deserialize, Serialize};
+use sqlx::{AnyConnection, FromRow};
+
+use crate::{
+    types::{Bool, Timestamp},
+    DbPool, Dialect,
+};
+
+/// Row of the `invites` table, keyed by invite code.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct Invite {
+    pub code: String,
+    pub temporary: Bool,
+    pub uses: i32,
+    pub max_uses: i32,
+    pub max_age: i32,
+    pub created_at: Timestamp,
+    pub expires_at: Option<Timestamp>,
+    pub guild_id: Option<String>,
+    pub channel_id: Option<String>,
+    pub inviter_id: Option<String>,
+    pub target_user_id: Option<String>,
+    pub target_user_type: Option<i32>,
+    pub vanity_url: Option<Bool>,
+    pub flags: i32,
+}
+
+impl Invite {
+    /// The invite with the given code, if any.
+    pub async fn find(pool: &DbPool, code: &str) -> Result<Option<Self>, sqlx::Error> {
+        let dialect = Dialect::of(pool);
+        sqlx::query_as(&format!(
+            "SELECT * FROM invites WHERE code = {}",
+            dialect.placeholder(1)
+        ))
+        .bind(code)
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Count a use of the invite `code` unless it expired or is used up,
+    /// deleting it once it reaches its maximum number of uses.
+    ///
+    /// Returns whether it could be used, so concurrent requests never use an
+    /// invite more than `max_uses` times.
+    pub async fn consume(
+        conn: &mut AnyConnection,
+        dialect: Dialect,
+        code: &str,
+    ) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query(&format!(
+            "UPDATE invites SET uses = uses + 1 WHERE code = {} \
+             AND (max_uses = 0 OR uses < max_uses) AND (expires_at IS NULL OR expires_at > {})",
+            dialect.placeholder(1),
+            dialect.placeholder(2)
+        ))
+        .bind(code)
+        .bind(Timestamp::now())
+        .execute(&mut *conn)
+        .await?;
+        if result.rows_affected() == 0 {
+            return Ok(false);
+        }
+
+        sqlx::query(&format!(
+            "DELETE FROM invites WHERE code = {} AND max_uses > 0 AND uses >= max_uses",
+            dialect.placeholder(1)
+        ))
+        .bind(code)
+        .execute(&mut *conn)
+        .await?;
+        Ok(true)
+    }
+
+    /// Whether the invite can no longer be used.
+    pub fn is_expired(&self) -> bool {
+        let expired = self.expires_at.is_some_and(|at| *at < chrono::Utc::now());
+        let used_up = self.max_uses > 0 && self.uses >= self.max_uses;
+        expired || used_up
+    }
+}
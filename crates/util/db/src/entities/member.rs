@@ -0,0 +1,177 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sqlx::{FromRow, Row};
+
+use crate::{
+    types::{Bool, Json, SimpleArray, Timestamp},
+    DbPool, Dialect,
+};
+
+/// Row of the `members` table, one per user per guild.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct Member {
+    #[serde(skip)]
+    pub index: i64,
+    pub id: String,
+    pub guild_id: String,
+    pub nick: Option<String>,
+    pub joined_at: Timestamp,
+    pub premium_since: Option<i64>,
+    pub deaf: Bool,
+    pub mute: Bool,
+    pub pending: Bool,
+    pub settings: Json<Value>,
+    pub last_message_id: Option<String>,
+    pub joined_by: Option<String>,
+    pub avatar: Option<String>,
+    pub banner: Option<String>,
+    pub bio: String,
+    pub theme_colors: Option<SimpleArray<i32>>,
+    pub pronouns: Option<String>,
+    pub communication_disabled_until: Option<Timestamp>,
+}
+
+/// Row of the `member_roles` join table.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct MemberRole {
+    pub index: i64,
+    pub role_id: String,
+}
+
+/// Columns written by [`Member::insert`], in the order they are bound.
+const COLUMNS: &[&str] = &[
+    "id",
+    "guild_id",
+    "nick",
+    "joined_at",
+    "premium_since",
+    "deaf",
+    "mute",
+    "pending",
+    "settings",
+    "last_message_id",
+    "joined_by",
+    "avatar",
+    "banner",
+    "bio",
+    "theme_colors",
+    "pronouns",
+    "communication_disabled_until",
+];
+
+impl Member {
+    /// The member `user_id` of the guild `guild_id`, if any.
+    pub async fn find(
+        pool: &DbPool,
+        guild_id: &str,
+        user_id: &str,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        let dialect = Dialect::of(pool);
+        sqlx::query_as(&format!(
+            "SELECT * FROM members WHERE guild_id = {} AND id = {}",
+            dialect.placeholder(1),
+            dialect.placeholder(2)
+        ))
+        .bind(guild_id)
+        .bind(user_id)
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Number of guilds the user `user_id` is a member of.
+    pub async fn count_by_user(pool: &DbPool, user_id: &str) -> Result<i64, sqlx::Error> {
+        let dialect = Dialect::of(pool);
+        sqlx::query_scalar(&format!(
+            "SELECT COUNT(*) FROM members WHERE id = {}",
+            dialect.placeholder(1)
+        ))
+        .bind(user_id)
+        .fetch_one(pool)
+        .await
+    }
+
+    /// Insert a new member, returning its index.
+    pub async fn insert<'c, E>(&self, executor: E, dialect: Dialect) -> Result<i64, sqlx::Error>
+    where
+        E: sqlx::Executor<'c, Database = sqlx::Any>,
+    {
+        let placeholders: Vec<String> = (1..=COLUMNS.len())
+            .map(|n| dialect.placeholder(n))
+            .collect();
+        let mut sql = format!(
+            "INSERT INTO members ({}) VALUES ({})",
+            COLUMNS.join(", "),
+            placeholders.join(", ")
+        );
+        // MySQL has no RETURNING, but reports the generated key instead.
+        let returning = !matches!(dialect, Dialect::Mysql | Dialect::MariaDb);
+        if returning {
+            sql += &format!(" RETURNING {}", dialect.quote("index"));
+        }
+
+        let query = sqlx::query(&sql)
+            .bind(&self.id)
+            .bind(&self.guild_id)
+            .bind(&self.nick)
+            .bind(self.joined_at)
+            .bind(self.premium_since)
+            .bind(self.deaf)
+            .bind(self.mute)
+            .bind(self.pending)
+            .bind(&self.settings)
+            .bind(&self.last_message_id)
+            .bind(&self.joined_by)
+            .bind(&self.avatar)
+            .bind(&self.banner)
+            .bind(&self.bio)
+            .bind(&self.theme_colors)
+            .bind(&self.pronouns)
+            .bind(self.communication_disabled_until);
+
+        if returning {
+            query.fetch_one(executor).await?.try_get(0)
+        } else {
+            query
+                .execute(executor)
+                .await?
+                .last_insert_id()
+                .ok_or_else(|| sqlx::Error::Protocol("no index generated for members".into()))
+        }
+    }
+
+    /// Give the member with the index `index` the role `role_id`.
+    pub async fn add_role<'c, E>(
+        executor: E,
+        dialect: Dialect,
+        index: i64,
+        role_id: &str,
+    ) -> Result<(), sqlx::Error>
+    where
+        E: sqlx::Executor<'c, Database = sqlx::Any>,
+    {
+        sqlx::query(&format!(
+            "INSERT INTO member_roles ({}, role_id) VALUES ({}, {})",
+            dialect.quote("index"),
+            dialect.placeholder(1),
+            dialect.placeholder(2)
+        ))
+        .bind(index)
+        .bind(role_id)
+        .execute(executor)
+        .await?;
+        Ok(())
+    }
+
+    /// IDs of the roles of the member with the index `index`.
+    pub async fn role_ids(pool: &DbPool, index: i64) -> Result<Vec<String>, sqlx::Error> {
+        let dialect = Dialect::of(pool);
+        sqlx::query_scalar(&format!(
+            "SELECT role_id FROM member_roles WHERE {} = {}",
+            dialect.quote("index"),
+            dialect.placeholder(1)
+        ))
+        .bind(index)
+        .fetch_all(pool)
+        .await
+    }
+}
@@ -0,0 +1,322 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sqlx::{AnyConnection, FromRow};
+
+use crate::{
+    types::{Bool, Json, Timestamp},
+    DbPool, Dialect,
+};
+
+/// `type` of a message sent by a user.
+pub const MESSAGE_DEFAULT: i32 = 0;
+/// `type` of a message replying to another.
+pub const MESSAGE_REPLY: i32 = 19;
+
+/// Row of the `messages` table.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct Message {
+    pub id: String,
+    pub channel_id: Option<String>,
+    pub guild_id: Option<String>,
+    pub author_id: Option<String>,
+    pub member_id: Option<String>,
+    pub webhook_id: Option<String>,
+    pub application_id: Option<String>,
+    pub content: Option<String>,
+    pub timestamp: Timestamp,
+    pub edited_timestamp: Option<Timestamp>,
+    pub tts: Option<Bool>,
+    pub mention_everyone: Option<Bool>,
+    pub embeds: Json<Vec<Value>>,
+    pub reactions: Json<Vec<Value>>,
+    pub nonce: Option<String>,
+    pub pinned: Option<Bool>,
+    #[sqlx(rename = "type")]
+    #[serde(rename = "type")]
+    pub kind: i32,
+    pub activity: Option<Json<Value>>,
+    pub flags: i32,
+    pub message_reference: Option<Json<Value>>,
+    pub message_reference_id: Option<String>,
+    pub interaction: Option<Json<Value>>,
+    pub components: Option<Json<Value>>,
+    pub poll: Option<Json<Value>>,
+    pub username: Option<String>,
+    pub avatar: Option<String>,
+}
+
+/// Columns of the `messages` table in the order [`Message::bind`] binds
+/// them.
+const COLUMNS: &[&str] = &[
+    "id",
+    "channel_id",
+    "guild_id",
+    "author_id",
+    "member_id",
+    "webhook_id",
+    "application_id",
+    "content",
+    "timestamp",
+    "edited_timestamp",
+    "tts",
+    "mention_everyone",
+    "embeds",
+    "reactions",
+    "nonce",
+    "pinned",
+    "type",
+    "activity",
+    "flags",
+    "message_reference",
+    "message_reference_id",
+    "interaction",
+    "components",
+    "poll",
+    "username",
+    "avatar",
+];
+
+impl Message {
+    /// The message `id` of the channel `channel_id`, if any.
+    pub async fn find(
+        pool: &DbPool,
+        channel_id: &str,
+        id: &str,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        let dialect = Dialect::of(pool);
+        sqlx::query_as(&format!(
+            "SELECT * FROM messages WHERE channel_id = {} AND id = {}",
+            dialect.placeholder(1),
+            dialect.placeholder(2)
+        ))
+        .bind(channel_id)
+        .bind(id)
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// The message the user `author_id` already sent to the channel
+    /// `channel_id` with `nonce`, if any.
+    pub async fn find_by_nonce(
+        pool: &DbPool,
+        channel_id: &str,
+        author_id: &str,
+        nonce: &str,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        let dialect = Dialect::of(pool);
+        sqlx::query_as(&format!(
+            "SELECT * FROM messages WHERE channel_id = {} AND author_id = {} AND nonce = {}",
+            dialect.placeholder(1),
+            dialect.placeholder(2),
+            dialect.placeholder(3)
+        ))
+        .bind(channel_id)
+        .bind(author_id)
+        .bind(nonce)
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Up to `limit` messages of the channel `channel_id` older than
+    /// `before`, or the latest ones, newest first.
+    pub async fn find_before(
+        pool: &DbPool,
+        channel_id: &str,
+        before: Option<&str>,
+        limit: i64,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        let dialect = Dialect::of(pool);
+        let sql = match before {
+            Some(_) => format!(
+                "SELECT * FROM messages WHERE channel_id = {} AND id < {} ORDER BY id DESC LIMIT {}",
+                dialect.placeholder(1),
+                dialect.placeholder(2),
+                dialect.placeholder(3)
+            ),
+            None => format!(
+                "SELECT * FROM messages WHERE channel_id = {} ORDER BY id DESC LIMIT {}",
+                dialect.placeholder(1),
+                dialect.placeholder(2)
+            ),
+        };
+        let mut query = sqlx::query_as(&sql).bind(channel_id);
+        if let Some(before) = before {
+            query = query.bind(before);
+        }
+        query.bind(limit).fetch_all(pool).await
+    }
+
+    /// Up to `limit` messages of the channel `channel_id` newer than
+    /// `after`, oldest first.
+    pub async fn find_after(
+        pool: &DbPool,
+        channel_id: &str,
+        after: &str,
+        limit: i64,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        let dialect = Dialect::of(pool);
+        sqlx::query_as(&format!(
+            "SELECT * FROM messages WHERE channel_id = {} AND id > {} ORDER BY id ASC LIMIT {}",
+            dialect.placeholder(1),
+            dialect.placeholder(2),
+            dialect.placeholder(3)
+        ))
+        .bind(channel_id)
+        .bind(after)
+        .bind(limit)
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Bind every column of the message in the order of [`COLUMNS`].
+    fn bind<'q>(
+        &'q self,
+        query: sqlx::query::Query<'q, sqlx::Any, sqlx::any::AnyArguments<'q>>,
+    ) -> sqlx::query::Query<'q, sqlx::Any, sqlx::any::AnyArguments<'q>> {
+        query
+            .bind(&self.id)
+            .bind(&self.channel_id)
+            .bind(&self.guild_id)
+            .bind(&self.author_id)
+            .bind(&self.member_id)
+            .bind(&self.webhook_id)
+            .bind(&self.application_id)
+            .bind(&self.content)
+            .bind(self.timestamp)
+            .bind(self.edited_timestamp)
+            .bind(self.tts)
+            .bind(self.mention_everyone)
+            .bind(&self.embeds)
+            .bind(&self.reactions)
+            .bind(&self.nonce)
+            .bind(self.pinned)
+            .bind(self.kind)
+            .bind(&self.activity)
+            .bind(self.flags)
+            .bind(&self.message_reference)
+            .bind(&self.message_reference_id)
+            .bind(&self.interaction)
+            .bind(&self.components)
+            .bind(&self.poll)
+            .bind(&self.username)
+            .bind(&self.avatar)
+    }
+
+    /// Insert a new message.
+    pub async fn insert<'c, E>(&self, executor: E, dialect: Dialect) -> Result<(), sqlx::Error>
+    where
+        E: sqlx::Executor<'c, Database = sqlx::Any>,
+    {
+        let columns: Vec<String> = COLUMNS.iter().map(|column| dialect.quote(column)).collect();
+        let placeholders: Vec<String> = (1..=COLUMNS.len())
+            .map(|n| dialect.placeholder(n))
+            .collect();
+        let sql = format!(
+            "INSERT INTO messages ({}) VALUES ({})",
+            columns.join(", "),
+            placeholders.join(", ")
+        );
+        self.bind(sqlx::query(&sql)).execute(executor).await?;
+        Ok(())
+    }
+
+    /// Write every column of the message back to its row.
+    pub async fn update<'c, E>(&self, executor: E, dialect: Dialect) -> Result<(), sqlx::Error>
+    where
+        E: sqlx::Executor<'c, Database = sqlx::Any>,
+    {
+        let assignments: Vec<String> = COLUMNS
+            .iter()
+            .enumerate()
+            .map(|(i, column)| {
+                format!("{} = {}", dialect.quote(column), dialect.placeholder(i + 1))
+            })
+            .collect();
+        let sql = format!(
+            "UPDATE messages SET {} WHERE id = {}",
+            assignments.join(", "),
+            dialect.placeholder(COLUMNS.len() + 1)
+        );
+        self.bind(sqlx::query(&sql))
+            .bind(&self.id)
+            .execute(executor)
+            .await?;
+        Ok(())
+    }
+
+    /// Delete the message with the given ID along with its attachments.
+    pub async fn delete(pool: &DbPool, id: &str) -> Result<(), sqlx::Error> {
+        let dialect = Dialect::of(pool);
+        sqlx::query(&format!(
+            "DELETE FROM messages WHERE id = {}",
+            dialect.placeholder(1)
+        ))
+        .bind(id)
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Replace the users and roles mentioned by the message `id`.
+    pub async fn set_mentions(
+        conn: &mut AnyConnection,
+        dialect: Dialect,
+        id: &str,
+        user_ids: &[String],
+        role_ids: &[String],
+    ) -> Result<(), sqlx::Error> {
+        let message_column = dialect.quote("messagesId");
+        for (table, column, ids) in [
+            ("message_user_mentions", "usersId", user_ids),
+            ("message_role_mentions", "rolesId", role_ids),
+        ] {
+            sqlx::query(&format!(
+                "DELETE FROM {table} WHERE {message_column} = {}",
+                dialect.placeholder(1)
+            ))
+            .bind(id)
+            .execute(&mut *conn)
+            .await?;
+            let sql = format!(
+                "INSERT INTO {table} ({message_column}, {}) VALUES ({}, {})",
+                dialect.quote(column),
+                dialect.placeholder(1),
+                dialect.placeholder(2)
+            );
+            for mentioned in ids {
+                sqlx::query(&sql)
+                    .bind(id)
+                    .bind(mentioned)
+                    .execute(&mut *conn)
+                    .await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// IDs of the users and of the roles mentioned by the message `id`.
+    pub async fn mentions(
+        pool: &DbPool,
+        id: &str,
+    ) -> Result<(Vec<String>, Vec<String>), sqlx::Error> {
+        let dialect = Dialect::of(pool);
+        let message_column = dialect.quote("messagesId");
+        let users = sqlx::query_scalar(&format!(
+            "SELECT {} FROM message_user_mentions WHERE {message_column} = {}",
+            dialect.quote("usersId"),
+            dialect.placeholder(1)
+        ))
+        .bind(id)
+        .fetch_all(pool)
+        .await?;
+        let roles = sqlx::query_scalar(&format!(
+            "SELECT {} FROM message_role_mentions WHERE {message_column} = {}",
+            dialect.quote("rolesId"),
+            dialect.placeholder(1)
+        ))
+        .bind(id)
+        .fetch_all(pool)
+        .await?;
+        Ok((users, roles))
+    }
+}
@@ -0,0 +1,59 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+mod attachment;
+mod backup_code;
+mod ban;
+mod channel;
+mod config;
+mod emoji;
+mod guild;
+mod invite;
+mod member;
+mod message;
+mod reaction;
+mod read_state;
+mod recipient;
+mod relationship;
+mod role;
+mod security_key;
+mod session;
+mod sticker;
+mod user;
+mod user_settings;
+mod valid_registration_token;
+mod webhook;
+
+pub use attachment::Attachment;
+pub use backup_code::BackupCode;
+pub use ban::Ban;
+pub use channel::{
+    Channel, ChannelPermissionOverwrite, CHANNEL_DM, CHANNEL_GROUP_DM, CHANNEL_GUILD_ANNOUNCEMENT,
+    CHANNEL_GUILD_CATEGORY, CHANNEL_GUILD_FORUM, CHANNEL_GUILD_STAGE_VOICE, CHANNEL_GUILD_TEXT,
+    CHANNEL_GUILD_VOICE,
+};
+pub use config::Config;
+pub use emoji::Emoji;
+pub use guild::Guild;
+pub use invite::Invite;
+pub use member::{Member, MemberRole};
+pub use message::{Message, MESSAGE_DEFAULT, MESSAGE_REPLY};
+pub use reaction::{Reaction, ReactionCount};
+pub use read_state::ReadState;
+pub use recipient::Recipient;
+pub use relationship::Relationship;
+pub use role::Role;
+pub use security_key::SecurityKey;
+pub use session::{Session, KIND_GATEWAY, KIND_TOKEN};
+pub use sticker::Sticker;
+pub use user::{User, UserData};
+pub use user_settings::UserSettings;
+pub use valid_registration_token::ValidRegistrationToken;
+pub use webhook::Webhook;
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct Migration {
+    pub id: i64,
+    pub timestamp: i64,
+    pub name: String,
+}
@@ -0,0 +1,243 @@
+use serde::{Deserialize, Serialize};
+use sqlx::{AnyConnection, FromRow};
+
+use crate::{
+    types::{Bool, Timestamp},
+    DbPool, Dialect,
+};
+
+/// Row of the `message_reactions` table, one per user per emoji per message.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct Reaction {
+    pub message_id: String,
+    /// ID of a custom emoji, or the unicode emoji itself.
+    pub emoji: String,
+    pub user_id: String,
+    pub emoji_id: Option<String>,
+    pub emoji_name: String,
+    pub animated: Bool,
+    pub created_at: Timestamp,
+}
+
+/// Reactions to a message with the same emoji, as returned by
+/// [`Reaction::count_by_message`].
+#[derive(Debug, Clone, FromRow)]
+pub struct ReactionCount {
+    pub emoji: String,
+    pub emoji_id: Option<String>,
+    pub emoji_name: String,
+    pub animated: Bool,
+    pub count: i64,
+    /// How many of the reactions are from the user counted for, 0 or 1.
+    pub me: i64,
+}
+
+impl Reaction {
+    /// Reactions to the message `message_id` grouped by emoji, in the order
+    /// they were first added, with `me` counting those of the user `user_id`.
+    pub async fn count_by_message(
+        pool: &DbPool,
+        message_id: &str,
+        user_id: &str,
+    ) -> Result<Vec<ReactionCount>, sqlx::Error> {
+        let dialect = Dialect::of(pool);
+        sqlx::query_as(&format!(
+            "SELECT emoji, emoji_id, emoji_name, animated, COUNT(*) AS count, \
+             COUNT(CASE WHEN user_id = {} THEN 1 END) AS me \
+             FROM message_reactions WHERE message_id = {} \
+             GROUP BY emoji, emoji_id, emoji_name, animated ORDER BY MIN(created_at)",
+            dialect.placeholder(1),
+            dialect.placeholder(2)
+        ))
+        .bind(user_id)
+        .bind(message_id)
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Number of different emojis the message `message_id` was reacted with.
+    pub async fn count_emojis<'c, E>(
+        executor: E,
+        dialect: Dialect,
+        message_id: &str,
+    ) -> Result<i64, sqlx::Error>
+    where
+        E: sqlx::Executor<'c, Database = sqlx::Any>,
+    {
+        sqlx::query_scalar(&format!(
+            "SELECT COUNT(DISTINCT emoji) FROM message_reactions WHERE message_id = {}",
+            dialect.placeholder(1)
+        ))
+        .bind(message_id)
+        .fetch_one(executor)
+        .await
+    }
+
+    /// Whether anyone reacted to the message `message_id` with `emoji`.
+    pub async fn exists<'c, E>(
+        executor: E,
+        dialect: Dialect,
+        message_id: &str,
+        emoji: &str,
+    ) -> Result<bool, sqlx::Error>
+    where
+        E: sqlx::Executor<'c, Database = sqlx::Any>,
+    {
+        let row = sqlx::query(&format!(
+            "SELECT 1 FROM message_reactions WHERE message_id = {} AND emoji = {} LIMIT 1",
+            dialect.placeholder(1),
+            dialect.placeholder(2)
+        ))
+        .bind(message_id)
+        .bind(emoji)
+        .fetch_optional(executor)
+        .await?;
+        Ok(row.is_some())
+    }
+
+    /// Lock the message `message_id` until the end of the transaction on
+    /// `conn`, so that reactions to it are added one transaction at a time
+    /// and [`Reaction::count_emojis`] stays accurate until the insert.
+    pub async fn lock_message(
+        conn: &mut AnyConnection,
+        dialect: Dialect,
+        message_id: &str,
+    ) -> Result<(), sqlx::Error> {
+        let sql = match dialect {
+            // SQLite locks the whole database for the first write instead.
+            Dialect::Sqlite => format!(
+                "UPDATE messages SET id = id WHERE id = {}",
+                dialect.placeholder(1)
+            ),
+            Dialect::Postgres | Dialect::Mysql | Dialect::MariaDb => format!(
+                "SELECT id FROM messages WHERE id = {} FOR UPDATE",
+                dialect.placeholder(1)
+            ),
+        };
+        sqlx::query(&sql).bind(message_id).execute(conn).await?;
+        Ok(())
+    }
+
+    /// IDs of up to `limit` users who reacted to the message `message_id`
+    /// with `emoji`, in numeric order, starting after the user `after`.
+    pub async fn find_user_ids(
+        pool: &DbPool,
+        message_id: &str,
+        emoji: &str,
+        after: Option<&str>,
+        limit: i64,
+    ) -> Result<Vec<String>, sqlx::Error> {
+        let dialect = Dialect::of(pool);
+        // Snowflakes are stored as strings, so a shorter one is smaller.
+        let after = after.unwrap_or_default();
+        sqlx::query_scalar(&format!(
+            "SELECT user_id FROM message_reactions WHERE message_id = {} AND emoji = {} \
+             AND (LENGTH(user_id) > LENGTH({}) OR (LENGTH(user_id) = LENGTH({}) AND user_id > {})) \
+             ORDER BY LENGTH(user_id), user_id LIMIT {}",
+            dialect.placeholder(1),
+            dialect.placeholder(2),
+            dialect.placeholder(3),
+            dialect.placeholder(4),
+            dialect.placeholder(5),
+            dialect.placeholder(6)
+        ))
+        .bind(message_id)
+        .bind(emoji)
+        .bind(after)
+        .bind(after)
+        .bind(after)
+        .bind(limit)
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Insert the reaction unless the user already reacted with the same
+    /// emoji, returning whether it was added.
+    pub async fn insert<'c, E>(&self, executor: E, dialect: Dialect) -> Result<bool, sqlx::Error>
+    where
+        E: sqlx::Executor<'c, Database = sqlx::Any>,
+    {
+        let placeholders: Vec<String> = (1..=7).map(|n| dialect.placeholder(n)).collect();
+        let columns = "message_id, emoji, user_id, emoji_id, emoji_name, animated, created_at";
+        // Racing requests for the same reaction are settled by the primary
+        // key, the losers inserting nothing.
+        let sql = match dialect {
+            Dialect::Mysql | Dialect::MariaDb => format!(
+                "INSERT IGNORE INTO message_reactions ({columns}) VALUES ({})",
+                placeholders.join(", ")
+            ),
+            Dialect::Sqlite | Dialect::Postgres => format!(
+                "INSERT INTO message_reactions ({columns}) VALUES ({}) ON CONFLICT DO NOTHING",
+                placeholders.join(", ")
+            ),
+        };
+        let result = sqlx::query(&sql)
+            .bind(&self.message_id)
+            .bind(&self.emoji)
+            .bind(&self.user_id)
+            .bind(&self.emoji_id)
+            .bind(&self.emoji_name)
+            .bind(self.animated)
+            .bind(self.created_at)
+            .execute(executor)
+            .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Remove the reaction of the user `user_id` with `emoji` from the
+    /// message `message_id`, returning whether there was one.
+    pub async fn delete(
+        pool: &DbPool,
+        message_id: &str,
+        emoji: &str,
+        user_id: &str,
+    ) -> Result<bool, sqlx::Error> {
+        let dialect = Dialect::of(pool);
+        let result = sqlx::query(&format!(
+            "DELETE FROM message_reactions WHERE message_id = {} AND emoji = {} AND user_id = {}",
+            dialect.placeholder(1),
+            dialect.placeholder(2),
+            dialect.placeholder(3)
+        ))
+        .bind(message_id)
+        .bind(emoji)
+        .bind(user_id)
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Remove every reaction with `emoji` from the message `message_id`,
+    /// returning whether there were any.
+    pub async fn delete_by_emoji(
+        pool: &DbPool,
+        message_id: &str,
+        emoji: &str,
+    ) -> Result<bool, sqlx::Error> {
+        let dialect = Dialect::of(pool);
+        let result = sqlx::query(&format!(
+            "DELETE FROM message_reactions WHERE message_id = {} AND emoji = {}",
+            dialect.placeholder(1),
+            dialect.placeholder(2)
+        ))
+        .bind(message_id)
+        .bind(emoji)
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Remove every reaction from the message `message_id`, returning
+    /// whether there were any.
+    pub async fn delete_by_message(pool: &DbPool, message_id: &str) -> Result<bool, sqlx::Error> {
+        let dialect = Dialect::of(pool);
+        let result = sqlx::query(&format!(
+            "DELETE FROM message_reactions WHERE message_id = {}",
+            dialect.placeholder(1)
+        ))
+        .bind(message_id)
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+}
@@ -0,0 +1,17 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+use crate::types::Timestamp;
+
+/// Row of the `read_states` table, one per user per channel.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct ReadState {
+    pub id: String,
+    pub channel_id: String,
+    pub user_id: String,
+    pub last_message_id: Option<String>,
+    pub public_ack: Option<String>,
+    pub notifications_cursor: Option<String>,
+    pub last_pin_timestamp: Option<Timestamp>,
+    pub mention_count: Option<i32>,
+}
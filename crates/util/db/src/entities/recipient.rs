@@ -0,0 +1,109 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+use crate::{types::Bool, DbPool, Dialect};
+
+/// Row of the `recipients` table, one per user per direct message channel.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct Recipient {
+    pub id: String,
+    pub channel_id: String,
+    pub user_id: String,
+    /// Whether the user closed the channel, hiding it from their list until
+    /// a new message arrives.
+    pub closed: Bool,
+}
+
+impl Recipient {
+    /// Recipients of the channel `channel_id`.
+    pub async fn find_by_channel(
+        pool: &DbPool,
+        channel_id: &str,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        let dialect = Dialect::of(pool);
+        sqlx::query_as(&format!(
+            "SELECT * FROM recipients WHERE channel_id = {}",
+            dialect.placeholder(1)
+        ))
+        .bind(channel_id)
+        .fetch_all(pool)
+        .await
+    }
+
+    /// The user `user_id` as a recipient of the channel `channel_id`, if
+    /// they are one.
+    pub async fn find(
+        pool: &DbPool,
+        channel_id: &str,
+        user_id: &str,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        let dialect = Dialect::of(pool);
+        sqlx::query_as(&format!(
+            "SELECT * FROM recipients WHERE channel_id = {} AND user_id = {}",
+            dialect.placeholder(1),
+            dialect.placeholder(2)
+        ))
+        .bind(channel_id)
+        .bind(user_id)
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Insert a new recipient.
+    pub async fn insert<'c, E>(&self, executor: E, dialect: Dialect) -> Result<(), sqlx::Error>
+    where
+        E: sqlx::Executor<'c, Database = sqlx::Any>,
+    {
+        sqlx::query(&format!(
+            "INSERT INTO recipients (id, channel_id, user_id, closed) VALUES ({}, {}, {}, {})",
+            dialect.placeholder(1),
+            dialect.placeholder(2),
+            dialect.placeholder(3),
+            dialect.placeholder(4)
+        ))
+        .bind(&self.id)
+        .bind(&self.channel_id)
+        .bind(&self.user_id)
+        .bind(self.closed)
+        .execute(executor)
+        .await?;
+        Ok(())
+    }
+
+    /// Close or reopen the channel `channel_id` for the user `user_id`.
+    pub async fn set_closed(
+        pool: &DbPool,
+        channel_id: &str,
+        user_id: &str,
+        closed: bool,
+    ) -> Result<(), sqlx::Error> {
+        let dialect = Dialect::of(pool);
+        sqlx::query(&format!(
+            "UPDATE recipients SET closed = {} WHERE channel_id = {} AND user_id = {}",
+            dialect.placeholder(1),
+            dialect.placeholder(2),
+            dialect.placeholder(3)
+        ))
+        .bind(Bool(closed))
+        .bind(channel_id)
+        .bind(user_id)
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Remove the user `user_id` from the channel `channel_id`.
+    pub async fn delete(pool: &DbPool, channel_id: &str, user_id: &str) -> Result<(), sqlx::Error> {
+        let dialect = Dialect::of(pool);
+        sqlx::query(&format!(
+            "DELETE FROM recipients WHERE channel_id = {} AND user_id = {}",
+            dialect.placeholder(1),
+            dialect.placeholder(2)
+        ))
+        .bind(channel_id)
+        .bind(user_id)
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+}
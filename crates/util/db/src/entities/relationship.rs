@@ -0,0 +1,14 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+/// Row of the `relationships` table.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct Relationship {
+    pub id: String,
+    pub from_id: String,
+    pub to_id: String,
+    pub nickname: Option<String>,
+    #[sqlx(rename = "type")]
+    #[serde(rename = "type")]
+    pub kind: i32,
+}
@@ -0,0 +1,94 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sqlx::FromRow;
+use util::Permissions;
+
+use crate::{
+    types::{Bool, Json},
+    DbPool, Dialect,
+};
+
+/// Row of the `roles` table.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct Role {
+    pub id: String,
+    pub guild_id: String,
+    pub color: i32,
+    pub hoist: Bool,
+    pub managed: Bool,
+    pub mentionable: Bool,
+    pub name: String,
+    pub permissions: String,
+    pub position: i32,
+    pub icon: Option<String>,
+    pub unicode_emoji: Option<String>,
+    pub tags: Option<Json<Value>>,
+    pub flags: i32,
+}
+
+/// Columns written by [`Role::insert`], in the order they are bound.
+const COLUMNS: &[&str] = &[
+    "id",
+    "guild_id",
+    "color",
+    "hoist",
+    "managed",
+    "mentionable",
+    "name",
+    "permissions",
+    "position",
+    "icon",
+    "unicode_emoji",
+    "tags",
+    "flags",
+];
+
+impl Role {
+    /// Insert a new role.
+    pub async fn insert<'c, E>(&self, executor: E, dialect: Dialect) -> Result<(), sqlx::Error>
+    where
+        E: sqlx::Executor<'c, Database = sqlx::Any>,
+    {
+        let placeholders: Vec<String> = (1..=COLUMNS.len())
+            .map(|n| dialect.placeholder(n))
+            .collect();
+        sqlx::query(&format!(
+            "INSERT INTO roles ({}) VALUES ({})",
+            COLUMNS.join(", "),
+            placeholders.join(", ")
+        ))
+        .bind(&self.id)
+        .bind(&self.guild_id)
+        .bind(self.color)
+        .bind(self.hoist)
+        .bind(self.managed)
+        .bind(self.mentionable)
+        .bind(&self.name)
+        .bind(&self.permissions)
+        .bind(self.position)
+        .bind(&self.icon)
+        .bind(&self.unicode_emoji)
+        .bind(&self.tags)
+        .bind(self.flags)
+        .execute(executor)
+        .await?;
+        Ok(())
+    }
+
+    /// Roles of the guild `guild_id`, lowest position first.
+    pub async fn find_by_guild(pool: &DbPool, guild_id: &str) -> Result<Vec<Self>, sqlx::Error> {
+        let dialect = Dialect::of(pool);
+        sqlx::query_as(&format!(
+            "SELECT * FROM roles WHERE guild_id = {} ORDER BY position",
+            dialect.placeholder(1)
+        ))
+        .bind(guild_id)
+        .fetch_all(pool)
+        .await
+    }
+
+    /// The permissions granted by the role. Malformed values grant nothing.
+    pub fn permissions(&self) -> Permissions {
+        self.permissions.parse().unwrap_or_default()
+    }
+}
@@ -0,0 +1,90 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+use crate::{DbPool, Dialect};
+
+/// Row of the `security_keys` table, a WebAuthn credential of a user.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct SecurityKey {
+    pub id: String,
+    pub user_id: Option<String>,
+    /// base64url credential ID.
+    pub key_id: String,
+    /// Serialised credential, see [`util::webauthn::RegisteredKey`].
+    pub public_key: String,
+    pub counter: i64,
+    pub name: String,
+}
+
+impl SecurityKey {
+    /// Keys of the user `user_id`.
+    pub async fn find_by_user(pool: &DbPool, user_id: &str) -> Result<Vec<Self>, sqlx::Error> {
+        let dialect = Dialect::of(pool);
+        sqlx::query_as(&format!(
+            "SELECT * FROM security_keys WHERE user_id = {}",
+            dialect.placeholder(1)
+        ))
+        .bind(user_id)
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Insert a new key.
+    pub async fn insert(&self, pool: &DbPool) -> Result<(), sqlx::Error> {
+        let dialect = Dialect::of(pool);
+        let placeholders: Vec<String> = (1..=6).map(|n| dialect.placeholder(n)).collect();
+        sqlx::query(&format!(
+            "INSERT INTO security_keys (id, user_id, key_id, public_key, counter, name) VALUES ({})",
+            placeholders.join(", ")
+        ))
+        .bind(&self.id)
+        .bind(&self.user_id)
+        .bind(&self.key_id)
+        .bind(&self.public_key)
+        .bind(self.counter)
+        .bind(&self.name)
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Store the credential of the key `key_id` after it was used to log in.
+    pub async fn update_credential(
+        pool: &DbPool,
+        user_id: &str,
+        key_id: &str,
+        public_key: &str,
+        counter: i64,
+    ) -> Result<(), sqlx::Error> {
+        let dialect = Dialect::of(pool);
+        sqlx::query(&format!(
+            "UPDATE security_keys SET public_key = {}, counter = {} WHERE user_id = {} AND key_id = {}",
+            dialect.placeholder(1),
+            dialect.placeholder(2),
+            dialect.placeholder(3),
+            dialect.placeholder(4)
+        ))
+        .bind(public_key)
+        .bind(counter)
+        .bind(user_id)
+        .bind(key_id)
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Delete the key `id` of the user `user_id`, returning whether it existed.
+    pub async fn delete(pool: &DbPool, user_id: &str, id: &str) -> Result<bool, sqlx::Error> {
+        let dialect = Dialect::of(pool);
+        let result = sqlx::query(&format!(
+            "DELETE FROM security_keys WHERE id = {} AND user_id = {}",
+            dialect.placeholder(1),
+            dialect.placeholder(2)
+        ))
+        .bind(id)
+        .bind(user_id)
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+}
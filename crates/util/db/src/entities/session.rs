@@ -0,0 +1,180 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sqlx::FromRow;
+
+use crate::{
+    types::{Json, Timestamp},
+    DbPool, Dialect,
+};
+
+/// `kind` of a session opened by logging in, which lasts as long as its token.
+pub const KIND_TOKEN: &str = "token";
+/// `kind` of a session of a gateway connection, deleted when it closes.
+pub const KIND_GATEWAY: &str = "gateway";
+
+/// Row of the `sessions` table, a login or a gateway connection.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct Session {
+    pub id: String,
+    pub user_id: Option<String>,
+    /// Carried by the token of a login, or generated per gateway socket.
+    pub session_id: String,
+    pub activities: Option<Json<Vec<Value>>>,
+    pub client_info: Json<Value>,
+    pub client_status: Json<Value>,
+    pub status: String,
+    /// [`KIND_TOKEN`] or [`KIND_GATEWAY`].
+    pub kind: String,
+    pub ip: Option<String>,
+    pub user_agent: Option<String>,
+    pub last_seen: Option<Timestamp>,
+}
+
+/// Columns written by [`Session::insert`], in the order they are bound.
+const COLUMNS: &[&str] = &[
+    "id",
+    "user_id",
+    "session_id",
+    "activities",
+    "client_info",
+    "client_status",
+    "status",
+    "kind",
+    "ip",
+    "user_agent",
+    "last_seen",
+];
+
+impl Session {
+    /// Insert a new session.
+    pub async fn insert(&self, pool: &DbPool) -> Result<(), sqlx::Error> {
+        let dialect = Dialect::of(pool);
+        let placeholders: Vec<String> = (1..=COLUMNS.len())
+            .map(|n| dialect.placeholder(n))
+            .collect();
+        sqlx::query(&format!(
+            "INSERT INTO sessions ({}) VALUES ({})",
+            COLUMNS.join(", "),
+            placeholders.join(", ")
+        ))
+        .bind(&self.id)
+        .bind(&self.user_id)
+        .bind(&self.session_id)
+        .bind(&self.activities)
+        .bind(&self.client_info)
+        .bind(&self.client_status)
+        .bind(&self.status)
+        .bind(&self.kind)
+        .bind(&self.ip)
+        .bind(&self.user_agent)
+        .bind(self.last_seen)
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    /// The login `session_id`, if it has not been revoked.
+    pub async fn find_login(pool: &DbPool, session_id: &str) -> Result<Option<Self>, sqlx::Error> {
+        let dialect = Dialect::of(pool);
+        sqlx::query_as(&format!(
+            "SELECT * FROM sessions WHERE session_id = {} AND kind = {}",
+            dialect.placeholder(1),
+            dialect.placeholder(2)
+        ))
+        .bind(session_id)
+        .bind(KIND_TOKEN)
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Logins of the user `user_id`, most recently used first.
+    pub async fn find_logins(pool: &DbPool, user_id: &str) -> Result<Vec<Self>, sqlx::Error> {
+        let dialect = Dialect::of(pool);
+        sqlx::query_as(&format!(
+            "SELECT * FROM sessions WHERE user_id = {} AND kind = {} ORDER BY last_seen DESC",
+            dialect.placeholder(1),
+            dialect.placeholder(2)
+        ))
+        .bind(user_id)
+        .bind(KIND_TOKEN)
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Record that the session `session_id` was just used from `ip`.
+    pub async fn touch(
+        pool: &DbPool,
+        session_id: &str,
+        ip: &str,
+        user_agent: Option<&str>,
+    ) -> Result<(), sqlx::Error> {
+        let dialect = Dialect::of(pool);
+        sqlx::query(&format!(
+            "UPDATE sessions SET ip = {}, user_agent = {}, last_seen = {} WHERE session_id = {}",
+            dialect.placeholder(1),
+            dialect.placeholder(2),
+            dialect.placeholder(3),
+            dialect.placeholder(4)
+        ))
+        .bind(ip)
+        .bind(user_agent)
+        .bind(Timestamp::now())
+        .bind(session_id)
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Delete the login `session_id` of the user `user_id`, returning whether
+    /// it existed.
+    pub async fn delete_login(
+        pool: &DbPool,
+        user_id: &str,
+        session_id: &str,
+    ) -> Result<bool, sqlx::Error> {
+        let dialect = Dialect::of(pool);
+        let result = sqlx::query(&format!(
+            "DELETE FROM sessions WHERE user_id = {} AND session_id = {} AND kind = {}",
+            dialect.placeholder(1),
+            dialect.placeholder(2),
+            dialect.placeholder(3)
+        ))
+        .bind(user_id)
+        .bind(session_id)
+        .bind(KIND_TOKEN)
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Delete every login of the user `user_id`, returning their session IDs.
+    pub async fn delete_logins(pool: &DbPool, user_id: &str) -> Result<Vec<String>, sqlx::Error> {
+        let logins = Self::find_logins(pool, user_id).await?;
+        let dialect = Dialect::of(pool);
+        sqlx::query(&format!(
+            "DELETE FROM sessions WHERE user_id = {} AND kind = {}",
+            dialect.placeholder(1),
+            dialect.placeholder(2)
+        ))
+        .bind(user_id)
+        .bind(KIND_TOKEN)
+        .execute(pool)
+        .await?;
+        Ok(logins.into_iter().map(|login| login.session_id).collect())
+    }
+
+    /// Delete the session of the gateway connection `session_id`.
+    pub async fn delete_gateway(pool: &DbPool, session_id: &str) -> Result<(), sqlx::Error> {
+        let dialect = Dialect::of(pool);
+        sqlx::query(&format!(
+            "DELETE FROM sessions WHERE session_id = {} AND kind = {}",
+            dialect.placeholder(1),
+            dialect.placeholder(2)
+        ))
+        .bind(session_id)
+        .bind(KIND_GATEWAY)
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,21 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+use crate::types::Bool;
+
+/// Row of the `stickers` table.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct Sticker {
+    pub id: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub available: Option<Bool>,
+    pub tags: Option<String>,
+    pub pack_id: Option<String>,
+    pub guild_id: Option<String>,
+    pub user_id: Option<String>,
+    #[sqlx(rename = "type")]
+    #[serde(rename = "type")]
+    pub kind: i32,
+    pub format_type: i32,
+}
@@ -0,0 +1,374 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+use crate::{
+    types::{Bool, Json, SimpleArray, Timestamp},
+    DbPool, Dialect,
+};
+
+/// Row of the `users` table.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct User {
+    pub id: String,
+    pub username: String,
+    pub discriminator: String,
+    pub avatar: Option<String>,
+    pub accent_color: Option<i32>,
+    pub banner: Option<String>,
+    pub theme_colors: Option<SimpleArray<i32>>,
+    pub pronouns: Option<String>,
+    pub phone: Option<String>,
+    pub desktop: Bool,
+    pub mobile: Bool,
+    pub premium: Bool,
+    pub premium_type: i32,
+    pub bot: Bool,
+    pub bio: String,
+    pub system: Bool,
+    pub nsfw_allowed: Bool,
+    pub mfa_enabled: Bool,
+    pub webauthn_enabled: Bool,
+    pub totp_secret: Option<String>,
+    pub totp_last_ticket: Option<String>,
+    pub created_at: Timestamp,
+    pub premium_since: Option<Timestamp>,
+    pub verified: Bool,
+    pub disabled: Bool,
+    pub deleted: Bool,
+    pub email: Option<String>,
+    pub flags: i64,
+    pub public_flags: i64,
+    pub purchased_flags: i32,
+    pub premium_usage_flags: i32,
+    pub rights: i64,
+    pub data: Json<UserData>,
+    pub fingerprints: SimpleArray,
+    pub extended_settings: String,
+    pub badge_ids: Option<SimpleArray>,
+    /// Index of the user's row in `user_settings`.
+    #[serde(skip)]
+    #[sqlx(rename = "settingsIndex")]
+    pub settings_index: Option<i64>,
+}
+
+/// Columns written by [`User::insert`], in the order they are bound.
+const COLUMNS: &[&str] = &[
+    "id",
+    "username",
+    "discriminator",
+    "avatar",
+    "accent_color",
+    "banner",
+    "theme_colors",
+    "pronouns",
+    "phone",
+    "desktop",
+    "mobile",
+    "premium",
+    "premium_type",
+    "bot",
+    "bio",
+    "system",
+    "nsfw_allowed",
+    "mfa_enabled",
+    "webauthn_enabled",
+    "totp_secret",
+    "totp_last_ticket",
+    "created_at",
+    "premium_since",
+    "verified",
+    "disabled",
+    "deleted",
+    "email",
+    "flags",
+    "public_flags",
+    "purchased_flags",
+    "premium_usage_flags",
+    "rights",
+    "data",
+    "fingerprints",
+    "extended_settings",
+    "badge_ids",
+    "settingsIndex",
+];
+
+impl User {
+    /// The user with the given ID, if any.
+    pub async fn find(pool: &DbPool, id: &str) -> Result<Option<Self>, sqlx::Error> {
+        let dialect = Dialect::of(pool);
+        sqlx::query_as(&format!(
+            "SELECT * FROM users WHERE id = {}",
+            dialect.placeholder(1)
+        ))
+        .bind(id)
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Insert a new user.
+    pub async fn insert<'c, E>(&self, executor: E, dialect: Dialect) -> Result<(), sqlx::Error>
+    where
+        E: sqlx::Executor<'c, Database = sqlx::Any>,
+    {
+        let columns: Vec<String> = COLUMNS.iter().map(|column| dialect.quote(column)).collect();
+        let placeholders: Vec<String> = (1..=COLUMNS.len())
+            .map(|n| dialect.placeholder(n))
+            .collect();
+        sqlx::query(&format!(
+            "INSERT INTO users ({}) VALUES ({})",
+            columns.join(", "),
+            placeholders.join(", ")
+        ))
+        .bind(&self.id)
+        .bind(&self.username)
+        .bind(&self.discriminator)
+        .bind(&self.avatar)
+        .bind(self.accent_color)
+        .bind(&self.banner)
+        .bind(self.theme_colors.clone())
+        .bind(&self.pronouns)
+        .bind(&self.phone)
+        .bind(self.desktop)
+        .bind(self.mobile)
+        .bind(self.premium)
+        .bind(self.premium_type)
+        .bind(self.bot)
+        .bind(&self.bio)
+        .bind(self.system)
+        .bind(self.nsfw_allowed)
+        .bind(self.mfa_enabled)
+        .bind(self.webauthn_enabled)
+        .bind(&self.totp_secret)
+        .bind(&self.totp_last_ticket)
+        .bind(self.created_at)
+        .bind(self.premium_since)
+        .bind(self.verified)
+        .bind(self.disabled)
+        .bind(self.deleted)
+        .bind(&self.email)
+        .bind(self.flags)
+        .bind(self.public_flags)
+        .bind(self.purchased_flags)
+        .bind(self.premium_usage_flags)
+        .bind(self.rights)
+        .bind(self.data.clone())
+        .bind(self.fingerprints.clone())
+        .bind(&self.extended_settings)
+        .bind(self.badge_ids.clone())
+        .bind(self.settings_index)
+        .execute(executor)
+        .await?;
+        Ok(())
+    }
+
+    /// Discriminators in use by users named `username`.
+    pub async fn discriminators(pool: &DbPool, username: &str) -> Result<Vec<String>, sqlx::Error> {
+        let dialect = Dialect::of(pool);
+        sqlx::query_scalar(&format!(
+            "SELECT discriminator FROM users WHERE username = {}",
+            dialect.placeholder(1)
+        ))
+        .bind(username)
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Whether an account uses the email address `email`.
+    pub async fn email_exists(pool: &DbPool, email: &str) -> Result<bool, sqlx::Error> {
+        let dialect = Dialect::of(pool);
+        let found: Option<String> = sqlx::query_scalar(&format!(
+            "SELECT id FROM users WHERE email = {}",
+            dialect.placeholder(1)
+        ))
+        .bind(email)
+        .fetch_optional(pool)
+        .await?;
+        Ok(found.is_some())
+    }
+
+    /// Whether an account was registered from the client `fingerprint`.
+    pub async fn fingerprint_exists(pool: &DbPool, fingerprint: &str) -> Result<bool, sqlx::Error> {
+        let dialect = Dialect::of(pool);
+        let found: Option<String> = sqlx::query_scalar(&format!(
+            "SELECT id FROM users WHERE fingerprints = {}",
+            dialect.placeholder(1)
+        ))
+        .bind(fingerprint)
+        .fetch_optional(pool)
+        .await?;
+        Ok(found.is_some())
+    }
+
+    /// Number of accounts created after `since`.
+    pub async fn count_created_since(pool: &DbPool, since: Timestamp) -> Result<i64, sqlx::Error> {
+        let dialect = Dialect::of(pool);
+        sqlx::query_scalar(&format!(
+            "SELECT COUNT(*) FROM users WHERE created_at > {}",
+            dialect.placeholder(1)
+        ))
+        .bind(since)
+        .fetch_one(pool)
+        .await
+    }
+
+    /// The user whose email address or phone number is `login`.
+    pub async fn find_by_login(pool: &DbPool, login: &str) -> Result<Option<Self>, sqlx::Error> {
+        let dialect = Dialect::of(pool);
+        sqlx::query_as(&format!(
+            "SELECT * FROM users WHERE email = {} OR phone = {}",
+            dialect.placeholder(1),
+            dialect.placeholder(2)
+        ))
+        .bind(login)
+        .bind(login)
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Remember the ticket a login must present with its MFA code.
+    pub async fn set_totp_last_ticket(
+        pool: &DbPool,
+        id: &str,
+        ticket: Option<&str>,
+    ) -> Result<(), sqlx::Error> {
+        let dialect = Dialect::of(pool);
+        sqlx::query(&format!(
+            "UPDATE users SET totp_last_ticket = {} WHERE id = {}",
+            dialect.placeholder(1),
+            dialect.placeholder(2)
+        ))
+        .bind(ticket)
+        .bind(id)
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    /// The user a login with MFA issued `ticket` to.
+    pub async fn find_by_totp_ticket(
+        pool: &DbPool,
+        ticket: &str,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        let dialect = Dialect::of(pool);
+        sqlx::query_as(&format!(
+            "SELECT * FROM users WHERE totp_last_ticket = {}",
+            dialect.placeholder(1)
+        ))
+        .bind(ticket)
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Enable TOTP with `secret`, or disable it when `secret` is `None`.
+    pub async fn set_totp_secret(
+        pool: &DbPool,
+        id: &str,
+        secret: Option<&str>,
+    ) -> Result<(), sqlx::Error> {
+        let dialect = Dialect::of(pool);
+        sqlx::query(&format!(
+            "UPDATE users SET mfa_enabled = {}, totp_secret = {}, totp_last_step = NULL WHERE id = {}",
+            dialect.placeholder(1),
+            dialect.placeholder(2),
+            dialect.placeholder(3)
+        ))
+        .bind(Bool::from(secret.is_some()))
+        .bind(secret)
+        .bind(id)
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Record that a TOTP code of the time step `step` was accepted, unless
+    /// one of the same or a later step already was.
+    ///
+    /// Returns whether the code may be accepted, so each one is used at
+    /// most once even under concurrent requests.
+    pub async fn use_totp_step(pool: &DbPool, id: &str, step: u64) -> Result<bool, sqlx::Error> {
+        let dialect = Dialect::of(pool);
+        let result = sqlx::query(&format!(
+            "UPDATE users SET totp_last_step = {} WHERE id = {} \
+             AND (totp_last_step IS NULL OR totp_last_step < {})",
+            dialect.placeholder(1),
+            dialect.placeholder(2),
+            dialect.placeholder(3)
+        ))
+        .bind(step as i64)
+        .bind(id)
+        .bind(step as i64)
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Record whether the user has security keys to log in with.
+    pub async fn set_webauthn_enabled(
+        pool: &DbPool,
+        id: &str,
+        enabled: bool,
+    ) -> Result<(), sqlx::Error> {
+        let dialect = Dialect::of(pool);
+        sqlx::query(&format!(
+            "UPDATE users SET webauthn_enabled = {} WHERE id = {}",
+            dialect.placeholder(1),
+            dialect.placeholder(2)
+        ))
+        .bind(Bool::from(enabled))
+        .bind(id)
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Mark the email address of the user as verified.
+    pub async fn set_verified(pool: &DbPool, id: &str) -> Result<(), sqlx::Error> {
+        let dialect = Dialect::of(pool);
+        sqlx::query(&format!(
+            "UPDATE users SET verified = 1 WHERE id = {}",
+            dialect.placeholder(1)
+        ))
+        .bind(id)
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Replace the private account data, such as after a password change.
+    pub async fn set_data(pool: &DbPool, id: &str, data: &UserData) -> Result<(), sqlx::Error> {
+        let dialect = Dialect::of(pool);
+        sqlx::query(&format!(
+            "UPDATE users SET data = {} WHERE id = {}",
+            dialect.placeholder(1),
+            dialect.placeholder(2)
+        ))
+        .bind(Json(data.clone()))
+        .bind(id)
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Cancel the scheduled deletion of an account and re-enable it.
+    pub async fn undelete(pool: &DbPool, id: &str) -> Result<(), sqlx::Error> {
+        let dialect = Dialect::of(pool);
+        sqlx::query(&format!(
+            "UPDATE users SET deleted = 0, disabled = 0 WHERE id = {}",
+            dialect.placeholder(1)
+        ))
+        .bind(id)
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+}
+
+/// Private account data kept in the `users.data` column.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UserData {
+    /// Tokens issued before this point are rejected.
+    pub valid_tokens_since: chrono::DateTime<chrono::Utc>,
+    /// bcrypt hash of the account password.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hash: Option<String>,
+}
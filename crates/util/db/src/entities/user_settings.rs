@@ -0,0 +1,210 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use sqlx::{FromRow, Row};
+
+use crate::{
+    types::{Bool, Json},
+    DbPool, Dialect,
+};
+
+/// Row of the `user_settings` table, referenced by `users.settingsIndex`.
+///
+/// Every column is nullable, as in the TypeORM schema.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct UserSettings {
+    #[serde(skip)]
+    pub index: i64,
+    pub afk_timeout: Option<i32>,
+    pub allow_accessibility_detection: Option<Bool>,
+    pub animate_emoji: Option<Bool>,
+    pub animate_stickers: Option<i32>,
+    pub contact_sync_enabled: Option<Bool>,
+    pub convert_emoticons: Option<Bool>,
+    pub custom_status: Option<Json<Value>>,
+    pub default_guilds_restricted: Option<Bool>,
+    pub detect_platform_accounts: Option<Bool>,
+    pub developer_mode: Option<Bool>,
+    pub disable_games_tab: Option<Bool>,
+    pub enable_tts_command: Option<Bool>,
+    pub explicit_content_filter: Option<i32>,
+    pub friend_discovery_flags: Option<i32>,
+    pub friend_source_flags: Option<Json<Value>>,
+    pub gateway_connected: Option<Bool>,
+    pub gif_auto_play: Option<Bool>,
+    pub guild_folders: Option<Json<Value>>,
+    pub guild_positions: Option<Json<Vec<String>>>,
+    pub inline_attachment_media: Option<Bool>,
+    pub inline_embed_media: Option<Bool>,
+    pub locale: Option<String>,
+    pub message_display_compact: Option<Bool>,
+    pub native_phone_integration_enabled: Option<Bool>,
+    pub render_embeds: Option<Bool>,
+    pub render_reactions: Option<Bool>,
+    pub restricted_guilds: Option<Json<Vec<String>>>,
+    pub show_current_game: Option<Bool>,
+    pub status: Option<String>,
+    pub stream_notifications_enabled: Option<Bool>,
+    pub theme: Option<String>,
+    pub timezone_offset: Option<i32>,
+    pub view_nsfw_guilds: Option<Bool>,
+}
+
+impl Default for UserSettings {
+    /// The settings of a new account, as in the TypeScript server.
+    fn default() -> Self {
+        Self {
+            index: 0,
+            afk_timeout: Some(3600),
+            allow_accessibility_detection: Some(true.into()),
+            animate_emoji: Some(true.into()),
+            animate_stickers: Some(0),
+            contact_sync_enabled: Some(false.into()),
+            convert_emoticons: Some(false.into()),
+            custom_status: None,
+            default_guilds_restricted: Some(false.into()),
+            detect_platform_accounts: Some(false.into()),
+            developer_mode: Some(true.into()),
+            disable_games_tab: Some(true.into()),
+            enable_tts_command: Some(false.into()),
+            explicit_content_filter: Some(0),
+            friend_discovery_flags: Some(0),
+            friend_source_flags: Some(Json(json!({ "all": true }))),
+            gateway_connected: Some(false.into()),
+            gif_auto_play: Some(false.into()),
+            guild_folders: Some(Json(json!([]))),
+            guild_positions: Some(Json(Vec::new())),
+            inline_attachment_media: Some(true.into()),
+            inline_embed_media: Some(true.into()),
+            locale: Some("en-US".into()),
+            message_display_compact: Some(false.into()),
+            native_phone_integration_enabled: Some(true.into()),
+            render_embeds: Some(true.into()),
+            render_reactions: Some(true.into()),
+            restricted_guilds: Some(Json(Vec::new())),
+            show_current_game: Some(true.into()),
+            status: Some("online".into()),
+            stream_notifications_enabled: Some(false.into()),
+            theme: Some("dark".into()),
+            timezone_offset: Some(0),
+            view_nsfw_guilds: Some(true.into()),
+        }
+    }
+}
+
+/// Columns other than `index`, in the order [`UserSettings::insert`] binds
+/// them.
+const COLUMNS: &[&str] = &[
+    "afk_timeout",
+    "allow_accessibility_detection",
+    "animate_emoji",
+    "animate_stickers",
+    "contact_sync_enabled",
+    "convert_emoticons",
+    "custom_status",
+    "default_guilds_restricted",
+    "detect_platform_accounts",
+    "developer_mode",
+    "disable_games_tab",
+    "enable_tts_command",
+    "explicit_content_filter",
+    "friend_discovery_flags",
+    "friend_source_flags",
+    "gateway_connected",
+    "gif_auto_play",
+    "guild_folders",
+    "guild_positions",
+    "inline_attachment_media",
+    "inline_embed_media",
+    "locale",
+    "message_display_compact",
+    "native_phone_integration_enabled",
+    "render_embeds",
+    "render_reactions",
+    "restricted_guilds",
+    "show_current_game",
+    "status",
+    "stream_notifications_enabled",
+    "theme",
+    "timezone_offset",
+    "view_nsfw_guilds",
+];
+
+impl UserSettings {
+    /// The settings with the given index, if any.
+    pub async fn find(pool: &DbPool, index: i64) -> Result<Option<Self>, sqlx::Error> {
+        let dialect = Dialect::of(pool);
+        sqlx::query_as(&format!(
+            "SELECT * FROM user_settings WHERE {} = {}",
+            dialect.quote("index"),
+            dialect.placeholder(1)
+        ))
+        .bind(index)
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Insert these settings, returning their new index.
+    pub async fn insert<'c, E>(&self, executor: E, dialect: Dialect) -> Result<i64, sqlx::Error>
+    where
+        E: sqlx::Executor<'c, Database = sqlx::Any>,
+    {
+        let placeholders: Vec<String> = (1..=COLUMNS.len())
+            .map(|n| dialect.placeholder(n))
+            .collect();
+        let mut sql = format!(
+            "INSERT INTO user_settings ({}) VALUES ({})",
+            COLUMNS.join(", "),
+            placeholders.join(", ")
+        );
+        // MySQL has no RETURNING, but reports the generated key instead.
+        let returning = !matches!(dialect, Dialect::Mysql | Dialect::MariaDb);
+        if returning {
+            sql += &format!(" RETURNING {}", dialect.quote("index"));
+        }
+
+        let query = sqlx::query(&sql)
+            .bind(self.afk_timeout)
+            .bind(self.allow_accessibility_detection)
+            .bind(self.animate_emoji)
+            .bind(self.animate_stickers)
+            .bind(self.contact_sync_enabled)
+            .bind(self.convert_emoticons)
+            .bind(self.custom_status.clone())
+            .bind(self.default_guilds_restricted)
+            .bind(self.detect_platform_accounts)
+            .bind(self.developer_mode)
+            .bind(self.disable_games_tab)
+            .bind(self.enable_tts_command)
+            .bind(self.explicit_content_filter)
+            .bind(self.friend_discovery_flags)
+            .bind(self.friend_source_flags.clone())
+            .bind(self.gateway_connected)
+            .bind(self.gif_auto_play)
+            .bind(self.guild_folders.clone())
+            .bind(self.guild_positions.clone())
+            .bind(self.inline_attachment_media)
+            .bind(self.inline_embed_media)
+            .bind(self.locale.clone())
+            .bind(self.message_display_compact)
+            .bind(self.native_phone_integration_enabled)
+            .bind(self.render_embeds)
+            .bind(self.render_reactions)
+            .bind(self.restricted_guilds.clone())
+            .bind(self.show_current_game)
+            .bind(self.status.clone())
+            .bind(self.stream_notifications_enabled)
+            .bind(self.theme.clone())
+            .bind(self.timezone_offset)
+            .bind(self.view_nsfw_guilds);
+
+        if returning {
+            query.fetch_one(executor).await?.try_get(0)
+        } else {
+            query
+                .execute(executor)
+                .await?
+                .last_insert_id()
+                .ok_or_else(|| sqlx::Error::Protocol("no index generated for user_settings".into()))
+        }
+    }
+}
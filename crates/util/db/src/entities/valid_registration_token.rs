@@ -0,0 +1,80 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+use crate::{types::Timestamp, DbPool, Dialect};
+
+/// Row of the `valid_registration_tokens` table, a single-use token allowing
+/// to register while registration is otherwise closed.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct ValidRegistrationToken {
+    pub token: String,
+    pub created_at: Timestamp,
+    pub expires_at: Timestamp,
+}
+
+impl ValidRegistrationToken {
+    /// Store `tokens`, each valid until `expires_at`.
+    pub async fn insert_many(
+        pool: &DbPool,
+        tokens: &[String],
+        expires_at: Timestamp,
+    ) -> Result<(), sqlx::Error> {
+        let dialect = Dialect::of(pool);
+        let sql = format!(
+            "INSERT INTO valid_registration_tokens (token, created_at, expires_at) VALUES ({}, {}, {})",
+            dialect.placeholder(1),
+            dialect.placeholder(2),
+            dialect.placeholder(3)
+        );
+        let now = Timestamp::now();
+        let mut tx = pool.begin().await?;
+        for token in tokens {
+            sqlx::query(&sql)
+                .bind(token)
+                .bind(now)
+                .bind(expires_at)
+                .execute(&mut *tx)
+                .await?;
+        }
+        tx.commit().await
+    }
+
+    /// Whether `token` exists and has not expired.
+    pub async fn is_valid(pool: &DbPool, token: &str) -> Result<bool, sqlx::Error> {
+        let dialect = Dialect::of(pool);
+        let found: Option<Self> = sqlx::query_as(&format!(
+            "SELECT * FROM valid_registration_tokens WHERE token = {} AND expires_at > {}",
+            dialect.placeholder(1),
+            dialect.placeholder(2)
+        ))
+        .bind(token)
+        .bind(Timestamp::now())
+        .fetch_optional(pool)
+        .await?;
+        Ok(found.is_some())
+    }
+
+    /// Delete `token` unless it has expired.
+    ///
+    /// Returns whether it was valid, so each token is accepted at most once
+    /// even under concurrent requests.
+    pub async fn consume<'c, E>(
+        executor: E,
+        dialect: Dialect,
+        token: &str,
+    ) -> Result<bool, sqlx::Error>
+    where
+        E: sqlx::Executor<'c, Database = sqlx::Any>,
+    {
+        let result = sqlx::query(&format!(
+            "DELETE FROM valid_registration_tokens WHERE token = {} AND expires_at > {}",
+            dialect.placeholder(1),
+            dialect.placeholder(2)
+        ))
+        .bind(token)
+        .bind(Timestamp::now())
+        .execute(executor)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+}
@@ -0,0 +1,20 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+/// Row of the `webhooks` table.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct Webhook {
+    pub id: String,
+    #[sqlx(rename = "type")]
+    #[serde(rename = "type")]
+    pub kind: i32,
+    pub name: Option<String>,
+    pub avatar: Option<String>,
+    pub token: Option<String>,
+    pub guild_id: Option<String>,
+    pub channel_id: Option<String>,
+    pub application_id: Option<String>,
+    pub user_id: Option<String>,
+    pub source_guild_id: Option<String>,
+    pub source_channel_id: Option<String>,
+}
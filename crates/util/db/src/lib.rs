@@ -1,34 +1,120 @@
-use sqlx::{AnyPool, migrate::Migrator};
+use sqlx::{any::AnyPoolOptions, migrate::Migrator, AnyPool};
+
+mod config;
+pub mod entities;
+mod token;
+mod typeorm;
+pub mod types;
+
+pub use self::config::{init_config, save_config, watch_config};
+pub use self::token::{check_token, issued_before, TokenError};
 
 pub type DbPool = AnyPool;
 
-static MIGRATOR: Migrator = sqlx::migrate!("./migrations");
+static SQLITE_MIGRATOR: Migrator = sqlx::migrate!("./migrations/sqlite");
+static POSTGRES_MIGRATOR: Migrator = sqlx::migrate!("./migrations/postgres");
+static MYSQL_MIGRATOR: Migrator = sqlx::migrate!("./migrations/mysql");
+static MARIADB_MIGRATOR: Migrator = sqlx::migrate!("./migrations/mariadb");
 
-pub async fn init_database(database_url: &str) -> Result<DbPool, sqlx::Error> {
-    let pool = AnyPool::connect(database_url).await?;
-    MIGRATOR.run(&pool).await?;
-    Ok(pool)
+/// Database backends that ship their own migration set.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Dialect {
+    Sqlite,
+    Postgres,
+    Mysql,
+    MariaDb,
 }
 
-pub async fn close_database(pool: DbPool) {
-    pool.close().await;
+impl Dialect {
+    /// Detect the backend from the scheme of a database URL.
+    pub fn from_url(database_url: &str) -> Option<Self> {
+        let (scheme, _) = database_url.split_once(':')?;
+        match scheme.to_ascii_lowercase().as_str() {
+            "sqlite" => Some(Self::Sqlite),
+            "postgres" | "postgresql" => Some(Self::Postgres),
+            "mysql" => Some(Self::Mysql),
+            "mariadb" => Some(Self::MariaDb),
+            _ => None,
+        }
+    }
+
+    /// Migrations written for this backend.
+    pub fn migrator(self) -> &'static Migrator {
+        match self {
+            Self::Sqlite => &SQLITE_MIGRATOR,
+            Self::Postgres => &POSTGRES_MIGRATOR,
+            Self::Mysql => &MYSQL_MIGRATOR,
+            Self::MariaDb => &MARIADB_MIGRATOR,
+        }
+    }
+
+    /// Backend `pool` is connected to. MariaDB reports itself as MySQL, which
+    /// is fine for everything but picking migrations.
+    pub fn of(pool: &DbPool) -> Self {
+        Self::from_url(pool.connect_options().database_url.as_str()).unwrap_or(Self::Sqlite)
+    }
+
+    /// Quote an identifier that may be a reserved word, such as `key`.
+    pub fn quote(self, ident: &str) -> String {
+        match self {
+            Self::Mysql | Self::MariaDb => format!("`{ident}`"),
+            Self::Sqlite | Self::Postgres => format!("\"{ident}\""),
+        }
+    }
+
+    /// Bind parameter marker for the `n`th (1-based) argument of a query.
+    pub fn placeholder(self, n: usize) -> String {
+        match self {
+            Self::Mysql | Self::MariaDb => "?".to_string(),
+            Self::Sqlite | Self::Postgres => format!("${n}"),
+        }
+    }
 }
 
-pub mod entities {
-    use serde::{Deserialize, Serialize};
-    use serde_json::Value;
-    use sqlx::FromRow;
+pub async fn init_database(database_url: &str) -> Result<DbPool, sqlx::Error> {
+    let dialect = Dialect::from_url(database_url).ok_or_else(|| {
+        sqlx::Error::Configuration(format!("unsupported database url: {database_url}").into())
+    })?;
 
-    #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
-    pub struct Migration {
-        pub id: i64,
-        pub timestamp: i64,
-        pub name: String,
+    sqlx::any::install_default_drivers();
+    let mut pool = connect(dialect, database_url).await?;
+    let imported = typeorm::import(dialect, &pool).await?;
+    if imported {
+        // Connections opened before the import may still describe the old
+        // column types, so start over with fresh ones.
+        pool.close().await;
+        pool = connect(dialect, database_url).await?;
     }
+    dialect.migrator().run(&pool).await?;
+    typeorm::import_reactions(dialect, &pool).await?;
+    Ok(pool)
+}
 
-    #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
-    pub struct Config {
-        pub key: String,
-        pub value: Option<Value>,
+async fn connect(dialect: Dialect, database_url: &str) -> Result<DbPool, sqlx::Error> {
+    match dialect {
+        // sqlx has no MariaDB driver of its own; it speaks the MySQL protocol.
+        Dialect::MariaDb => {
+            let url = format!(
+                "mysql:{}",
+                database_url.split_once(':').unwrap_or_default().1
+            );
+            AnyPool::connect(&url).await
+        }
+        // Every connection to `:memory:` opens a separate database, so keep
+        // exactly one alive for the lifetime of the pool.
+        Dialect::Sqlite if database_url.contains(":memory:") => {
+            AnyPoolOptions::new()
+                .min_connections(1)
+                .max_connections(1)
+                .idle_timeout(None)
+                .max_lifetime(None)
+                .connect(database_url)
+                .await
+        }
+        _ => AnyPool::connect(database_url).await,
     }
 }
+
+pub async fn close_database(pool: DbPool) {
+    pool.close().await;
+}
@@ -0,0 +1,82 @@
+//! Validation of user tokens against the `users` table.
+
+use chrono::{DateTime, Timelike, Utc};
+use thiserror::Error;
+use util::decode_token;
+
+use crate::{
+    entities::{Session, User},
+    DbPool,
+};
+
+/// Why a token was rejected. The messages match the TypeScript server.
+#[derive(Debug, Error)]
+pub enum TokenError {
+    #[error("Invalid Token")]
+    Invalid,
+    #[error("User disabled")]
+    Disabled,
+    #[error("User not found")]
+    Deleted,
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+}
+
+/// Resolve the user an `Authorization` header value belongs to.
+///
+/// Tokens prefixed with `Bot ` must belong to a bot account. Tokens issued
+/// before the user's `valid_tokens_since` are rejected, as are tokens whose
+/// login session was revoked and tokens of disabled or deleted accounts.
+///
+/// Returns the login session alongside the user, if the token has one.
+pub async fn check_token(
+    pool: &DbPool,
+    token: &str,
+    secret: &str,
+) -> Result<(User, Option<Session>), TokenError> {
+    let (token, bot) = match token.strip_prefix("Bot ") {
+        Some(token) => (token, true),
+        None => (token, false),
+    };
+    let claims = decode_token(token, secret).map_err(|_| TokenError::Invalid)?;
+    let user = User::find(pool, &claims.id)
+        .await?
+        .ok_or(TokenError::Invalid)?;
+
+    if bot && !*user.bot {
+        return Err(TokenError::Invalid);
+    }
+    if issued_before(claims.iat, user.data.valid_tokens_since) {
+        return Err(TokenError::Invalid);
+    }
+    let session = match &claims.sid {
+        Some(sid) => Some(
+            Session::find_login(pool, sid)
+                .await?
+                .filter(|session| session.user_id.as_deref() == Some(user.id.as_str()))
+                .ok_or(TokenError::Invalid)?,
+        ),
+        None => None,
+    };
+    if *user.disabled {
+        return Err(TokenError::Disabled);
+    }
+    if *user.deleted {
+        return Err(TokenError::Deleted);
+    }
+    Ok((user, session))
+}
+
+/// Whether a token issued at `iat` was revoked by moving the user's
+/// `valid_tokens_since` to `valid_since`.
+pub fn issued_before(iat: i64, valid_since: DateTime<Utc>) -> bool {
+    // `iat` has a resolution of seconds, and the TypeScript server compares
+    // it with `valid_tokens_since` rounded down to the minute.
+    iat * 1000 < truncate_to_minute(valid_since).timestamp_millis()
+}
+
+fn truncate_to_minute(time: DateTime<Utc>) -> DateTime<Utc> {
+    time.with_second(0)
+        .and_then(|time| time.with_nanosecond(0))
+        .unwrap_or(time)
+}
@@ -0,0 +1,641 @@
+//! Import of databases created by the TypeScript server.
+//!
+//! TypeORM records its own `migrations` table and stores booleans and dates
+//! using native column types the `Any` driver cannot read. The first time
+//! such a database is opened those columns are converted to the
+//! representations in [`crate::types`], configuration rows are rekeyed to
+//! the dotted paths [`crate::init_config`] reads, the Rust migrations
+//! equivalent to the TypeORM schema are recorded as applied, and only the
+//! remaining ones are left for the migrator to run. Once they have, the
+//! reactions stored on each message are copied into `message_reactions`.
+//!
+//! MySQL commits every schema change on its own, so there the import is not
+//! atomic. Each step instead skips what an interrupted run already did, and
+//! a database is imported until its migrations are recorded, so starting the
+//! server again finishes the job.
+
+use std::collections::HashSet;
+
+use ::config::Config as ConfigValue;
+use serde::Deserialize;
+use serde_json::Value;
+use sqlx::{migrate::Migrate, AnyConnection, AnyPool, Connection, Row};
+
+use crate::{
+    entities::{BackupCode, Config, Reaction},
+    types::{Bool, Json, Timestamp},
+    Dialect,
+};
+
+/// Last Rust migration whose tables the TypeScript schema already contains.
+const BASELINE_VERSION: i64 = 20240101000008;
+
+/// TypeORM migrations that must have run for the baseline to match.
+fn required_migrations(dialect: Dialect) -> &'static [&'static str] {
+    match dialect {
+        // TypeORM synchronises SQLite databases instead of migrating them.
+        Dialect::Sqlite => &[],
+        Dialect::Postgres => &[
+            "webauthn1675044825710",
+            "guildChannelOrdering1696420827239",
+            "MessageFlagsNotNull1713116476900",
+            "MessagePollObject1720157926878",
+            "Badges1720628601997",
+            "WebhookMessageProperties1721298824927",
+            "client_status1723347738541",
+            "WebhookSourceChannel1723644478176",
+        ],
+        Dialect::Mysql | Dialect::MariaDb => &[
+            "webauthn1675045120206",
+            "guildChannelOrdering1696420827239",
+            "MessageFlagsNotNull1713116476900",
+            "MessagePollObject1720157926878",
+            "Badges1720628601997",
+            "WebhookMessageProperties1721298824927",
+            "client_status1723347738541",
+            "WebhookSourceChannel1723644478176",
+        ],
+    }
+}
+
+/// MySQL columns TypeORM declares as `TEXT` or too short that the Rust schema
+/// reads as plain strings, with their new definition.
+const MYSQL_STRING_COLUMNS: &[(&str, &str, &str)] = &[
+    ("users", "extended_settings", "VARCHAR(4096) NOT NULL"),
+    ("users", "totp_last_ticket", "VARCHAR(4096) NULL"),
+    ("messages", "content", "VARCHAR(4000) NULL"),
+    ("messages", "nonce", "VARCHAR(255) NULL"),
+    ("security_keys", "public_key", "VARCHAR(4096) NOT NULL"),
+];
+
+/// Convert a TypeORM-managed database in place, if `pool` points at one.
+///
+/// Returns whether anything was imported.
+pub(crate) async fn import(dialect: Dialect, pool: &AnyPool) -> Result<bool, sqlx::Error> {
+    // TypeORM always creates `config`; sqlx records its own bookkeeping table.
+    if migrations_recorded(pool).await || !table_exists(pool, "config").await {
+        return Ok(false);
+    }
+
+    println!("[Database] Found a database created by the TypeScript server, importing it.");
+    if matches!(dialect, Dialect::Mysql | Dialect::MariaDb) {
+        println!(
+            "[Database] MySQL cannot roll the import back; if it fails, fix the cause and start again, or restore a backup."
+        );
+    }
+
+    let applied: Vec<String> = if table_exists(pool, "migrations").await {
+        sqlx::query_scalar("SELECT name FROM migrations")
+            .fetch_all(pool)
+            .await?
+    } else {
+        Vec::new()
+    };
+    let missing: Vec<&str> = required_migrations(dialect)
+        .iter()
+        .copied()
+        .filter(|name| !applied.iter().any(|applied| applied == name))
+        .collect();
+    if !missing.is_empty() {
+        return Err(sqlx::Error::Configuration(
+            format!(
+                "database is missing TypeORM migrations {}; start the TypeScript server once to apply them before importing",
+                missing.join(", ")
+            )
+            .into(),
+        ));
+    }
+
+    let mut conn = pool.acquire().await?;
+    // Tables are rebuilt one at a time, so references must not be checked
+    // until all of them are back under their original names.
+    if dialect == Dialect::Sqlite {
+        sqlx::query("PRAGMA foreign_keys = OFF")
+            .execute(&mut *conn)
+            .await?;
+    }
+
+    let mut tx = conn.begin().await?;
+    match dialect {
+        Dialect::Sqlite => convert_sqlite(&mut tx).await?,
+        Dialect::Postgres => convert_postgres(&mut tx).await?,
+        Dialect::Mysql | Dialect::MariaDb => convert_mysql(&mut tx).await?,
+    }
+    hash_backup_codes(dialect, &mut tx).await?;
+    convert_config(dialect, &mut tx).await?;
+    schedule_reaction_import(&mut tx).await?;
+    mark_baseline_applied(dialect, &mut tx).await?;
+    tx.commit().await?;
+
+    if dialect == Dialect::Sqlite {
+        sqlx::query("PRAGMA foreign_keys = ON")
+            .execute(&mut *conn)
+            .await?;
+    }
+
+    println!("[Database] Import finished, applying missing migrations, if any.");
+    Ok(true)
+}
+
+/// Whether sqlx recorded any migration, which a MySQL import interrupted
+/// after creating the table has not.
+async fn migrations_recorded(pool: &AnyPool) -> bool {
+    sqlx::query("SELECT 1 FROM _sqlx_migrations")
+        .fetch_optional(pool)
+        .await
+        .is_ok_and(|row| row.is_some())
+}
+
+async fn table_exists(pool: &AnyPool, table: &str) -> bool {
+    sqlx::query(&format!("SELECT 1 FROM {table} WHERE 1 = 0"))
+        .fetch_optional(pool)
+        .await
+        .is_ok()
+}
+
+/// TypeORM stores MFA backup codes in plain text, replace them with the
+/// hashes [`BackupCode::consume`] looks up.
+async fn hash_backup_codes(dialect: Dialect, conn: &mut AnyConnection) -> Result<(), sqlx::Error> {
+    let codes = sqlx::query("SELECT id, code FROM backup_codes")
+        .fetch_all(&mut *conn)
+        .await?;
+    let sql = format!(
+        "UPDATE backup_codes SET code = {} WHERE id = {}",
+        dialect.placeholder(1),
+        dialect.placeholder(2)
+    );
+    for code in codes {
+        let id: String = code.try_get(0)?;
+        let plain: String = code.try_get(1)?;
+        // Codes are 8 characters long, so this one was hashed by an
+        // interrupted import.
+        if plain.len() == 64 {
+            continue;
+        }
+        sqlx::query(&sql)
+            .bind(BackupCode::hash(&plain))
+            .bind(id)
+            .execute(&mut *conn)
+            .await?;
+    }
+    Ok(())
+}
+
+/// Rewrite the configuration, which the TypeScript server stores under keys
+/// like `security_jwtSecret`, under keys like `security.jwt_secret`.
+async fn convert_config(dialect: Dialect, conn: &mut AnyConnection) -> Result<(), sqlx::Error> {
+    let key = dialect.quote("key");
+    let rows: Vec<Config> = sqlx::query_as(&format!("SELECT {key}, value FROM config"))
+        .fetch_all(&mut *conn)
+        .await?;
+    // Converted keys are dotted, the TypeScript ones never are.
+    if rows.iter().all(|row| row.key.contains('.')) {
+        return Ok(());
+    }
+    let pairs = rows
+        .into_iter()
+        .map(|row| (row.key, row.value.map(|value| value.0).unwrap_or_default()));
+    let config = ConfigValue::from_typescript_pairs(pairs)
+        .map_err(|e| sqlx::Error::Configuration(e.into()))?;
+
+    sqlx::query("DELETE FROM config")
+        .execute(&mut *conn)
+        .await?;
+    let sql = format!(
+        "INSERT INTO config ({key}, value) VALUES ({}, {})",
+        dialect.placeholder(1),
+        dialect.placeholder(2)
+    );
+    for (key, value) in config.to_pairs() {
+        sqlx::query(&sql)
+            .bind(key)
+            .bind((value != Value::Null).then_some(Json(value)))
+            .execute(&mut *conn)
+            .await?;
+    }
+    Ok(())
+}
+
+/// Reaction as the TypeScript server stores it in `messages.reactions`.
+#[derive(Deserialize)]
+struct StoredReaction {
+    emoji: StoredEmoji,
+    #[serde(default)]
+    user_ids: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct StoredEmoji {
+    id: Option<String>,
+    name: Option<String>,
+    #[serde(default)]
+    animated: bool,
+}
+
+/// Table recording that the reactions of an imported database remain to be
+/// copied, and the last message whose reactions were.
+const REACTION_IMPORT_TABLE: &str = "_reaction_import";
+
+/// Messages whose reactions are copied per transaction.
+const REACTION_IMPORT_PAGE: i64 = 500;
+
+/// Remember to copy the reactions once the migrations created their table.
+async fn schedule_reaction_import(conn: &mut AnyConnection) -> Result<(), sqlx::Error> {
+    sqlx::query(&format!(
+        "CREATE TABLE IF NOT EXISTS {REACTION_IMPORT_TABLE} (last_message_id VARCHAR(255))"
+    ))
+    .execute(&mut *conn)
+    .await?;
+    let scheduled = sqlx::query(&format!("SELECT 1 FROM {REACTION_IMPORT_TABLE}"))
+        .fetch_optional(&mut *conn)
+        .await?;
+    if scheduled.is_none() {
+        sqlx::query(&format!(
+            "INSERT INTO {REACTION_IMPORT_TABLE} (last_message_id) VALUES (NULL)"
+        ))
+        .execute(&mut *conn)
+        .await?;
+    }
+    Ok(())
+}
+
+/// Copy the reactions the TypeScript server keeps on each message into
+/// `message_reactions`, if an import scheduled it.
+///
+/// Messages are copied in pages, each committed together with the last
+/// message it covered, so an interrupted copy resumes where it stopped.
+pub(crate) async fn import_reactions(dialect: Dialect, pool: &AnyPool) -> Result<(), sqlx::Error> {
+    if !table_exists(pool, REACTION_IMPORT_TABLE).await {
+        return Ok(());
+    }
+    println!("[Database] Copying message reactions.");
+
+    let mut last: Option<String> = sqlx::query_scalar(&format!(
+        "SELECT last_message_id FROM {REACTION_IMPORT_TABLE}"
+    ))
+    .fetch_optional(pool)
+    .await?
+    .flatten();
+    let first_page = format!(
+        "SELECT id, timestamp, reactions FROM messages \
+         WHERE reactions <> '' AND reactions <> '[]' ORDER BY id LIMIT {REACTION_IMPORT_PAGE}"
+    );
+    let next_page = format!(
+        "SELECT id, timestamp, reactions FROM messages \
+         WHERE reactions <> '' AND reactions <> '[]' AND id > {} ORDER BY id LIMIT {REACTION_IMPORT_PAGE}",
+        dialect.placeholder(1)
+    );
+    let save_progress = format!(
+        "UPDATE {REACTION_IMPORT_TABLE} SET last_message_id = {}",
+        dialect.placeholder(1)
+    );
+    loop {
+        let messages = match &last {
+            None => sqlx::query(&first_page).fetch_all(pool).await?,
+            Some(last) => sqlx::query(&next_page).bind(last).fetch_all(pool).await?,
+        };
+        let Some(last_message) = messages.last() else {
+            break;
+        };
+        last = Some(last_message.try_get(0)?);
+
+        let mut reactions = Vec::new();
+        for message in &messages {
+            let id: String = message.try_get(0)?;
+            let timestamp: Timestamp = message.try_get(1)?;
+            // Anything unreadable is left behind rather than failing the import.
+            let Ok(Json(stored)) = message.try_get::<Json<Vec<Value>>, _>(2) else {
+                continue;
+            };
+            for reaction in stored {
+                let Ok(reaction) = serde_json::from_value::<StoredReaction>(reaction) else {
+                    continue;
+                };
+                let emoji = reaction.emoji;
+                let Some(key) = emoji.id.clone().or_else(|| emoji.name.clone()) else {
+                    continue;
+                };
+                reactions.extend(reaction.user_ids.into_iter().map(|user_id| Reaction {
+                    message_id: id.clone(),
+                    emoji: key.clone(),
+                    user_id,
+                    emoji_id: emoji.id.clone(),
+                    emoji_name: emoji.name.clone().unwrap_or_default(),
+                    animated: Bool(emoji.animated),
+                    created_at: timestamp,
+                }));
+            }
+        }
+        // Deleted users may linger in the lists.
+        let user_ids: Vec<&str> = reactions.iter().map(|r| r.user_id.as_str()).collect();
+        let users = existing_users(dialect, pool, &user_ids).await?;
+
+        let mut tx = pool.begin().await?;
+        for reaction in reactions.iter().filter(|r| users.contains(&r.user_id)) {
+            reaction.insert(&mut *tx, dialect).await?;
+        }
+        sqlx::query(&save_progress)
+            .bind(&last)
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await?;
+
+        if (messages.len() as i64) < REACTION_IMPORT_PAGE {
+            break;
+        }
+    }
+
+    sqlx::query(&format!("DROP TABLE {REACTION_IMPORT_TABLE}"))
+        .execute(pool)
+        .await?;
+    Ok(())
+}
+
+/// Which of the users `ids` exist.
+async fn existing_users(
+    dialect: Dialect,
+    pool: &AnyPool,
+    ids: &[&str],
+) -> Result<HashSet<String>, sqlx::Error> {
+    let mut ids = ids.to_vec();
+    ids.sort_unstable();
+    ids.dedup();
+    let mut existing = HashSet::new();
+    // Stay well below the bind parameter limits of every database.
+    for chunk in ids.chunks(500) {
+        let placeholders: Vec<String> = (1..=chunk.len()).map(|n| dialect.placeholder(n)).collect();
+        let sql = format!(
+            "SELECT id FROM users WHERE id IN ({})",
+            placeholders.join(", ")
+        );
+        let mut query = sqlx::query_scalar(&sql);
+        for id in chunk {
+            query = query.bind(*id);
+        }
+        existing.extend(query.fetch_all(pool).await?);
+    }
+    Ok(existing)
+}
+
+/// Record every baseline migration as applied without running it.
+async fn mark_baseline_applied(
+    dialect: Dialect,
+    conn: &mut AnyConnection,
+) -> Result<(), sqlx::Error> {
+    conn.ensure_migrations_table().await?;
+
+    let sql = format!(
+        "INSERT INTO _sqlx_migrations (version, description, success, checksum, execution_time) VALUES ({}, {}, {}, {}, {})",
+        dialect.placeholder(1),
+        dialect.placeholder(2),
+        dialect.placeholder(3),
+        dialect.placeholder(4),
+        dialect.placeholder(5),
+    );
+    for migration in dialect.migrator().iter() {
+        if migration.version > BASELINE_VERSION || migration.migration_type.is_down_migration() {
+            continue;
+        }
+        sqlx::query(&sql)
+            .bind(migration.version)
+            .bind(migration.description.to_string())
+            .bind(true)
+            .bind(migration.checksum.to_vec())
+            .bind(0_i64)
+            .execute(&mut *conn)
+            .await?;
+    }
+    Ok(())
+}
+
+async fn convert_postgres(conn: &mut AnyConnection) -> Result<(), sqlx::Error> {
+    let columns = sqlx::query(
+        "SELECT table_name::text, column_name::text, data_type::text, column_default::text \
+         FROM information_schema.columns \
+         WHERE table_schema = current_schema() \
+         AND data_type IN ('boolean', 'timestamp without time zone', 'timestamp with time zone')",
+    )
+    .fetch_all(&mut *conn)
+    .await?;
+
+    for column in columns {
+        let table: String = column.try_get(0)?;
+        let name: String = column.try_get(1)?;
+        let data_type: String = column.try_get(2)?;
+        let default: Option<String> = column.try_get(3)?;
+
+        let (target, using, default) = if data_type == "boolean" {
+            let default = match default.as_deref() {
+                Some("true") => Some("1"),
+                Some("false") => Some("0"),
+                _ => None,
+            };
+            ("SMALLINT", format!("\"{name}\"::int"), default)
+        } else {
+            let default = match default.as_deref() {
+                Some("now()" | "CURRENT_TIMESTAMP") => {
+                    Some("(EXTRACT(EPOCH FROM now()) * 1000)::bigint")
+                }
+                _ => None,
+            };
+            (
+                "BIGINT",
+                format!("(EXTRACT(EPOCH FROM \"{name}\") * 1000)::bigint"),
+                default,
+            )
+        };
+
+        let mut sql = format!(
+            "ALTER TABLE \"{table}\" ALTER COLUMN \"{name}\" DROP DEFAULT, \
+             ALTER COLUMN \"{name}\" TYPE {target} USING {using}"
+        );
+        if let Some(default) = default {
+            sql += &format!(", ALTER COLUMN \"{name}\" SET DEFAULT {default}");
+        }
+        sqlx::query(&sql).execute(&mut *conn).await?;
+    }
+    Ok(())
+}
+
+async fn convert_mysql(conn: &mut AnyConnection) -> Result<(), sqlx::Error> {
+    recover_mysql_dates(conn).await?;
+
+    // information_schema reports most of these as TEXT, which arrives as a blob.
+    let columns = sqlx::query(
+        "SELECT CAST(TABLE_NAME AS CHAR(255)), CAST(COLUMN_NAME AS CHAR(255)), \
+         CAST(DATA_TYPE AS CHAR(64)), CAST(IS_NULLABLE AS CHAR(3)), CAST(COLUMN_DEFAULT AS CHAR(255)) \
+         FROM information_schema.COLUMNS \
+         WHERE TABLE_SCHEMA = DATABASE() AND DATA_TYPE IN ('tinyint', 'datetime', 'timestamp') \
+         AND TABLE_NAME <> '_sqlx_migrations'",
+    )
+    .fetch_all(&mut *conn)
+    .await?;
+
+    for column in columns {
+        let table: String = column.try_get(0)?;
+        let name: String = column.try_get(1)?;
+        let data_type: String = column.try_get(2)?;
+        let null = if column.try_get::<String, _>(3)? == "YES" {
+            "NULL"
+        } else {
+            "NOT NULL"
+        };
+        let default: Option<String> = column.try_get(4)?;
+
+        if data_type == "tinyint" {
+            let default = default
+                .map(|default| format!(" DEFAULT {default}"))
+                .unwrap_or_default();
+            sqlx::query(&format!(
+                "ALTER TABLE `{table}` MODIFY `{name}` SMALLINT {null}{default}"
+            ))
+            .execute(&mut *conn)
+            .await?;
+            continue;
+        }
+
+        // Dates cannot be cast to epoch milliseconds in place, so copy them
+        // through a temporary column.
+        let temporary = format!("{name}{MYSQL_TEMPORARY_SUFFIX}");
+        for sql in [
+            format!("ALTER TABLE `{table}` ADD `{temporary}` BIGINT {null}"),
+            format!("UPDATE `{table}` SET `{temporary}` = ROUND(UNIX_TIMESTAMP(`{name}`) * 1000)"),
+            format!("ALTER TABLE `{table}` DROP COLUMN `{name}`"),
+            format!("ALTER TABLE `{table}` CHANGE `{temporary}` `{name}` BIGINT {null}"),
+        ] {
+            sqlx::query(&sql).execute(&mut *conn).await?;
+        }
+    }
+
+    for (table, name, definition) in MYSQL_STRING_COLUMNS {
+        sqlx::query(&format!(
+            "ALTER TABLE `{table}` MODIFY `{name}` {definition}"
+        ))
+        .execute(&mut *conn)
+        .await?;
+    }
+    Ok(())
+}
+
+/// Suffix of the column a MySQL date is copied to while being converted.
+const MYSQL_TEMPORARY_SUFFIX: &str = "__millis";
+
+/// Finish the date conversions an interrupted import left halfway: a
+/// temporary column whose date column is gone replaces it, one whose date
+/// column is still there is dropped so the conversion starts over.
+async fn recover_mysql_dates(conn: &mut AnyConnection) -> Result<(), sqlx::Error> {
+    let columns = sqlx::query(
+        "SELECT CAST(TABLE_NAME AS CHAR(255)), CAST(COLUMN_NAME AS CHAR(255)), \
+         CAST(IS_NULLABLE AS CHAR(3)) \
+         FROM information_schema.COLUMNS WHERE TABLE_SCHEMA = DATABASE()",
+    )
+    .fetch_all(&mut *conn)
+    .await?;
+    let mut names = Vec::with_capacity(columns.len());
+    for column in &columns {
+        let table: String = column.try_get(0)?;
+        let name: String = column.try_get(1)?;
+        let nullable: String = column.try_get(2)?;
+        names.push((table, name, nullable == "YES"));
+    }
+
+    for (table, temporary, nullable) in &names {
+        let Some(name) = temporary.strip_suffix(MYSQL_TEMPORARY_SUFFIX) else {
+            continue;
+        };
+        let sql = if names.iter().any(|(t, n, _)| t == table && n == name) {
+            format!("ALTER TABLE `{table}` DROP COLUMN `{temporary}`")
+        } else {
+            let null = if *nullable { "NULL" } else { "NOT NULL" };
+            format!("ALTER TABLE `{table}` CHANGE `{temporary}` `{name}` BIGINT {null}")
+        };
+        sqlx::query(&sql).execute(&mut *conn).await?;
+    }
+    Ok(())
+}
+
+async fn convert_sqlite(conn: &mut AnyConnection) -> Result<(), sqlx::Error> {
+    let tables = sqlx::query(
+        "SELECT name, sql FROM sqlite_master WHERE type = 'table' \
+         AND name NOT LIKE 'sqlite_%' AND name <> '_sqlx_migrations'",
+    )
+    .fetch_all(&mut *conn)
+    .await?;
+
+    for table in tables {
+        let name: String = table.try_get(0)?;
+        let mut sql: String = table.try_get(1)?;
+
+        let columns = sqlx::query(&format!("PRAGMA table_info(\"{name}\")"))
+            .fetch_all(&mut *conn)
+            .await?;
+        let mut changed = false;
+        let mut select = Vec::with_capacity(columns.len());
+        for column in columns {
+            let column_name: String = column.try_get("name")?;
+            let declared: String = column.try_get("type")?;
+            match declared.to_ascii_lowercase().as_str() {
+                "boolean" => {
+                    sql = sql.replace(
+                        &format!("\"{column_name}\" {declared}"),
+                        &format!("\"{column_name}\" SMALLINT"),
+                    );
+                    select.push(format!("\"{column_name}\""));
+                    changed = true;
+                }
+                "datetime" => {
+                    sql = sql.replace(
+                        &format!("\"{column_name}\" {declared}"),
+                        &format!("\"{column_name}\" BIGINT"),
+                    );
+                    select.push(format!(
+                        "CAST(ROUND((julianday(\"{column_name}\") - 2440587.5) * 86400000) AS INTEGER)"
+                    ));
+                    changed = true;
+                }
+                _ => select.push(format!("\"{column_name}\"")),
+            }
+        }
+        if !changed {
+            continue;
+        }
+
+        // SQLite cannot change a column's type, so rebuild the table under a
+        // temporary name and swap it in, keeping its indexes.
+        let temporary = format!("{name}__typeorm");
+        let sql = sql
+            .replacen(
+                &format!("CREATE TABLE \"{name}\""),
+                &format!("CREATE TABLE \"{temporary}\""),
+                1,
+            )
+            .replace(
+                "datetime('now')",
+                "CAST(ROUND((julianday('now') - 2440587.5) * 86400000) AS INTEGER)",
+            );
+        let indexes: Vec<String> = sqlx::query_scalar(
+            "SELECT sql FROM sqlite_master WHERE type = 'index' AND tbl_name = $1 AND sql IS NOT NULL",
+        )
+        .bind(&name)
+        .fetch_all(&mut *conn)
+        .await?;
+
+        sqlx::query(&sql).execute(&mut *conn).await?;
+        sqlx::query(&format!(
+            "INSERT INTO \"{temporary}\" SELECT {} FROM \"{name}\"",
+            select.join(", ")
+        ))
+        .execute(&mut *conn)
+        .await?;
+        sqlx::query(&format!("DROP TABLE \"{name}\""))
+            .execute(&mut *conn)
+            .await?;
+        sqlx::query(&format!("ALTER TABLE \"{temporary}\" RENAME TO \"{name}\""))
+            .execute(&mut *conn)
+            .await?;
+        for index in indexes {
+            sqlx::query(&index).execute(&mut *conn).await?;
+        }
+    }
+    Ok(())
+}
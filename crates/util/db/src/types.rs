@@ -0,0 +1,284 @@
+//! Column wrapper types for values the `Any` driver cannot decode natively.
+//!
+//! `sqlx`'s `Any` backend only understands integers, floats, text and blobs,
+//! so booleans, timestamps and TypeORM's `simple-json`/`simple-array` columns
+//! are stored using one of those and converted here.
+
+use std::{
+    fmt::{self, Display},
+    ops::{Deref, DerefMut},
+    str::FromStr,
+};
+
+use chrono::{DateTime, Utc};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use sqlx::{
+    any::{Any, AnyTypeInfo},
+    database::Database,
+    decode::Decode,
+    encode::{Encode, IsNull},
+    error::BoxDynError,
+    types::Type,
+};
+
+pub use util::Snowflake;
+
+/// Boolean stored as a `SMALLINT` holding `0` or `1`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Bool(pub bool);
+
+impl From<bool> for Bool {
+    fn from(value: bool) -> Self {
+        Self(value)
+    }
+}
+
+impl From<Bool> for bool {
+    fn from(value: Bool) -> Self {
+        value.0
+    }
+}
+
+impl Deref for Bool {
+    type Target = bool;
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl Type<Any> for Bool {
+    fn type_info() -> AnyTypeInfo {
+        <i16 as Type<Any>>::type_info()
+    }
+
+    fn compatible(ty: &AnyTypeInfo) -> bool {
+        <i16 as Type<Any>>::compatible(ty)
+    }
+}
+
+impl<'q> Encode<'q, Any> for Bool {
+    fn encode_by_ref(
+        &self,
+        buf: &mut <Any as Database>::ArgumentBuffer<'q>,
+    ) -> Result<IsNull, BoxDynError> {
+        <i16 as Encode<'q, Any>>::encode_by_ref(&(self.0 as i16), buf)
+    }
+}
+
+impl<'r> Decode<'r, Any> for Bool {
+    fn decode(value: <Any as Database>::ValueRef<'r>) -> Result<Self, BoxDynError> {
+        Ok(Self(<i64 as Decode<'r, Any>>::decode(value)? != 0))
+    }
+}
+
+/// Point in time stored as a `BIGINT` of milliseconds since the Unix epoch.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Timestamp(pub DateTime<Utc>);
+
+impl Timestamp {
+    /// The current time.
+    pub fn now() -> Self {
+        Self(Utc::now())
+    }
+
+    /// Milliseconds since the Unix epoch, as stored in the database.
+    pub fn as_millis(&self) -> i64 {
+        self.0.timestamp_millis()
+    }
+}
+
+impl From<DateTime<Utc>> for Timestamp {
+    fn from(value: DateTime<Utc>) -> Self {
+        Self(value)
+    }
+}
+
+impl From<Timestamp> for DateTime<Utc> {
+    fn from(value: Timestamp) -> Self {
+        value.0
+    }
+}
+
+impl Deref for Timestamp {
+    type Target = DateTime<Utc>;
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl Type<Any> for Timestamp {
+    fn type_info() -> AnyTypeInfo {
+        <i64 as Type<Any>>::type_info()
+    }
+
+    fn compatible(ty: &AnyTypeInfo) -> bool {
+        <i64 as Type<Any>>::compatible(ty)
+    }
+}
+
+impl<'q> Encode<'q, Any> for Timestamp {
+    fn encode_by_ref(
+        &self,
+        buf: &mut <Any as Database>::ArgumentBuffer<'q>,
+    ) -> Result<IsNull, BoxDynError> {
+        <i64 as Encode<'q, Any>>::encode_by_ref(&self.as_millis(), buf)
+    }
+}
+
+impl<'r> Decode<'r, Any> for Timestamp {
+    fn decode(value: <Any as Database>::ValueRef<'r>) -> Result<Self, BoxDynError> {
+        let millis = <i64 as Decode<'r, Any>>::decode(value)?;
+        DateTime::from_timestamp_millis(millis)
+            .map(Self)
+            .ok_or_else(|| format!("timestamp {millis} out of range").into())
+    }
+}
+
+/// Value serialised as JSON text, equivalent to TypeORM's `simple-json`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Json<T>(pub T);
+
+impl<T> Deref for Json<T> {
+    type Target = T;
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<T> DerefMut for Json<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl<T> Type<Any> for Json<T> {
+    fn type_info() -> AnyTypeInfo {
+        <String as Type<Any>>::type_info()
+    }
+
+    fn compatible(ty: &AnyTypeInfo) -> bool {
+        text_compatible(ty)
+    }
+}
+
+impl<'q, T: Serialize> Encode<'q, Any> for Json<T> {
+    fn encode_by_ref(
+        &self,
+        buf: &mut <Any as Database>::ArgumentBuffer<'q>,
+    ) -> Result<IsNull, BoxDynError> {
+        let text = serde_json::to_string(&self.0)?;
+        <String as Encode<'q, Any>>::encode_by_ref(&text, buf)
+    }
+}
+
+impl<'r, T: DeserializeOwned> Decode<'r, Any> for Json<T> {
+    fn decode(value: <Any as Database>::ValueRef<'r>) -> Result<Self, BoxDynError> {
+        let text = decode_text(value)?;
+        Ok(Self(serde_json::from_str(&text)?))
+    }
+}
+
+/// List stored as comma separated text, equivalent to TypeORM's
+/// `simple-array`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct SimpleArray<T = String>(pub Vec<T>);
+
+impl<T> Default for SimpleArray<T> {
+    fn default() -> Self {
+        Self(Vec::new())
+    }
+}
+
+impl<T> From<Vec<T>> for SimpleArray<T> {
+    fn from(value: Vec<T>) -> Self {
+        Self(value)
+    }
+}
+
+impl<T> Deref for SimpleArray<T> {
+    type Target = Vec<T>;
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<T> DerefMut for SimpleArray<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl<T: Display> Display for SimpleArray<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, item) in self.0.iter().enumerate() {
+            if i > 0 {
+                f.write_str(",")?;
+            }
+            item.fmt(f)?;
+        }
+        Ok(())
+    }
+}
+
+impl<T> FromStr for SimpleArray<T>
+where
+    T: FromStr,
+    T::Err: Into<BoxDynError>,
+{
+    type Err = BoxDynError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.is_empty() {
+            return Ok(Self::default());
+        }
+        s.split(',')
+            .map(|item| item.parse().map_err(Into::into))
+            .collect::<Result<_, _>>()
+            .map(Self)
+    }
+}
+
+impl<T> Type<Any> for SimpleArray<T> {
+    fn type_info() -> AnyTypeInfo {
+        <String as Type<Any>>::type_info()
+    }
+
+    fn compatible(ty: &AnyTypeInfo) -> bool {
+        text_compatible(ty)
+    }
+}
+
+impl<'q, T: Display> Encode<'q, Any> for SimpleArray<T> {
+    fn encode_by_ref(
+        &self,
+        buf: &mut <Any as Database>::ArgumentBuffer<'q>,
+    ) -> Result<IsNull, BoxDynError> {
+        <String as Encode<'q, Any>>::encode_by_ref(&self.to_string(), buf)
+    }
+}
+
+impl<'r, T> Decode<'r, Any> for SimpleArray<T>
+where
+    T: FromStr,
+    T::Err: Into<BoxDynError>,
+{
+    fn decode(value: <Any as Database>::ValueRef<'r>) -> Result<Self, BoxDynError> {
+        decode_text(value)?.parse()
+    }
+}
+
+/// MySQL reports `TEXT` columns as blobs, so accept either representation.
+fn text_compatible(ty: &AnyTypeInfo) -> bool {
+    <String as Type<Any>>::compatible(ty) || <Vec<u8> as Type<Any>>::compatible(ty)
+}
+
+fn decode_text(value: <Any as Database>::ValueRef<'_>) -> Result<String, BoxDynError> {
+    match <String as Decode<Any>>::decode(value.clone()) {
+        Ok(text) => Ok(text),
+        Err(_) => Ok(String::from_utf8(<Vec<u8> as Decode<Any>>::decode(value)?)?),
+    }
+}
@@ -0,0 +1,17 @@
+-- Tables with boolean and date columns as TypeORM creates them on MySQL and
+-- MariaDB, replacing the baseline versions of the same tables.
+SET FOREIGN_KEY_CHECKS = 0;
+DROP TABLE `backup_codes`, `messages`, `users`;
+SET FOREIGN_KEY_CHECKS = 1;
+
+CREATE TABLE `users` (`id` varchar(255) NOT NULL, `username` varchar(255) NOT NULL, `discriminator` varchar(255) NOT NULL, `avatar` varchar(255) NULL, `accent_color` int NULL, `banner` varchar(255) NULL, `theme_colors` text NULL, `pronouns` varchar(255) NULL, `phone` varchar(255) NULL, `desktop` tinyint NOT NULL, `mobile` tinyint NOT NULL, `premium` tinyint NOT NULL, `premium_type` int NOT NULL, `bot` tinyint NOT NULL, `bio` varchar(255) NOT NULL, `system` tinyint NOT NULL, `nsfw_allowed` tinyint NOT NULL, `mfa_enabled` tinyint NOT NULL, `webauthn_enabled` tinyint NOT NULL DEFAULT 0, `totp_secret` varchar(255) NULL, `totp_last_ticket` varchar(255) NULL, `created_at` datetime NOT NULL, `premium_since` datetime NULL, `verified` tinyint NOT NULL, `disabled` tinyint NOT NULL, `deleted` tinyint NOT NULL, `email` varchar(255) NULL, `flags` int NOT NULL, `public_flags` int NOT NULL, `purchased_flags` int NOT NULL, `premium_usage_flags` int NOT NULL, `rights` bigint NOT NULL, `data` text NOT NULL, `fingerprints` text NOT NULL, `extended_settings` text NOT NULL, `badge_ids` text NULL, `settingsIndex` int NULL, UNIQUE INDEX `REL_users_settingsIndex` (`settingsIndex`), PRIMARY KEY (`id`)) ENGINE=InnoDB;
+ALTER TABLE `users` ADD CONSTRAINT `FK_users_settingsIndex` FOREIGN KEY (`settingsIndex`) REFERENCES `user_settings`(`index`) ON DELETE NO ACTION ON UPDATE NO ACTION;
+
+CREATE TABLE `messages` (`id` varchar(255) NOT NULL, `channel_id` varchar(255) NULL, `guild_id` varchar(255) NULL, `author_id` varchar(255) NULL, `member_id` varchar(255) NULL, `webhook_id` varchar(255) NULL, `application_id` varchar(255) NULL, `content` varchar(255) NULL, `timestamp` datetime(6) NOT NULL DEFAULT CURRENT_TIMESTAMP(6), `edited_timestamp` datetime NULL, `tts` tinyint NULL, `mention_everyone` tinyint NULL, `embeds` text NOT NULL, `reactions` text NOT NULL, `nonce` text NULL, `pinned` tinyint NULL, `type` int NOT NULL, `activity` text NULL, `flags` int NOT NULL DEFAULT 0, `message_reference` text NULL, `interaction` text NULL, `components` text NULL, `poll` text NULL, `username` varchar(255) NULL, `avatar` varchar(255) NULL, `message_reference_id` varchar(255) NULL, UNIQUE INDEX `IDX_messages_channel_id_id` (`channel_id`, `id`), INDEX `IDX_messages_author_id` (`author_id`), PRIMARY KEY (`id`)) ENGINE=InnoDB;
+ALTER TABLE `messages` ADD CONSTRAINT `FK_messages_channel_id` FOREIGN KEY (`channel_id`) REFERENCES `channels`(`id`) ON DELETE CASCADE ON UPDATE NO ACTION;
+ALTER TABLE `messages` ADD CONSTRAINT `FK_messages_guild_id` FOREIGN KEY (`guild_id`) REFERENCES `guilds`(`id`) ON DELETE CASCADE ON UPDATE NO ACTION;
+ALTER TABLE `messages` ADD CONSTRAINT `FK_messages_author_id` FOREIGN KEY (`author_id`) REFERENCES `users`(`id`) ON DELETE CASCADE ON UPDATE NO ACTION;
+ALTER TABLE `messages` ADD CONSTRAINT `FK_messages_message_reference_id` FOREIGN KEY (`message_reference_id`) REFERENCES `messages`(`id`) ON DELETE NO ACTION ON UPDATE NO ACTION;
+
+CREATE TABLE `backup_codes` (`id` varchar(255) NOT NULL, `code` varchar(255) NOT NULL, `consumed` tinyint NOT NULL, `expired` tinyint NOT NULL, `user_id` varchar(255) NULL, PRIMARY KEY (`id`)) ENGINE=InnoDB;
+ALTER TABLE `backup_codes` ADD CONSTRAINT `FK_backup_codes_user_id` FOREIGN KEY (`user_id`) REFERENCES `users`(`id`) ON DELETE CASCADE ON UPDATE NO ACTION;
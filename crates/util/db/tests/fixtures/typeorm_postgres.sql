@@ -0,0 +1,17 @@
+-- Tables with boolean and date columns as TypeORM creates them on Postgres,
+-- replacing the baseline versions of the same tables.
+DROP TABLE "backup_codes", "messages", "users" CASCADE;
+
+CREATE TABLE "users" ("id" character varying NOT NULL, "username" character varying NOT NULL, "discriminator" character varying NOT NULL, "avatar" character varying, "accent_color" integer, "banner" character varying, "theme_colors" text, "pronouns" character varying, "phone" character varying, "desktop" boolean NOT NULL, "mobile" boolean NOT NULL, "premium" boolean NOT NULL, "premium_type" integer NOT NULL, "bot" boolean NOT NULL, "bio" character varying NOT NULL, "system" boolean NOT NULL, "nsfw_allowed" boolean NOT NULL, "mfa_enabled" boolean NOT NULL, "webauthn_enabled" boolean NOT NULL DEFAULT false, "totp_secret" character varying, "totp_last_ticket" character varying, "created_at" TIMESTAMP NOT NULL, "premium_since" TIMESTAMP, "verified" boolean NOT NULL, "disabled" boolean NOT NULL, "deleted" boolean NOT NULL, "email" character varying, "flags" integer NOT NULL, "public_flags" integer NOT NULL, "purchased_flags" integer NOT NULL, "premium_usage_flags" integer NOT NULL, "rights" bigint NOT NULL, "data" text NOT NULL, "fingerprints" text NOT NULL, "extended_settings" text NOT NULL, "badge_ids" text, "settingsIndex" integer, CONSTRAINT "REL_users_settingsIndex" UNIQUE ("settingsIndex"), CONSTRAINT "PK_users" PRIMARY KEY ("id"));
+ALTER TABLE "users" ADD CONSTRAINT "FK_users_settingsIndex" FOREIGN KEY ("settingsIndex") REFERENCES "user_settings"("index") ON DELETE NO ACTION ON UPDATE NO ACTION;
+
+CREATE TABLE "messages" ("id" character varying NOT NULL, "channel_id" character varying, "guild_id" character varying, "author_id" character varying, "member_id" character varying, "webhook_id" character varying, "application_id" character varying, "content" character varying, "timestamp" TIMESTAMP NOT NULL DEFAULT now(), "edited_timestamp" TIMESTAMP, "tts" boolean, "mention_everyone" boolean, "embeds" text NOT NULL, "reactions" text NOT NULL, "nonce" text, "pinned" boolean, "type" integer NOT NULL, "activity" text, "flags" integer NOT NULL DEFAULT '0', "message_reference" text, "interaction" text, "components" text, "poll" text, "username" character varying, "avatar" character varying, "message_reference_id" character varying, CONSTRAINT "PK_messages" PRIMARY KEY ("id"));
+CREATE UNIQUE INDEX "IDX_messages_channel_id_id" ON "messages" ("channel_id", "id");
+CREATE INDEX "IDX_messages_author_id" ON "messages" ("author_id");
+ALTER TABLE "messages" ADD CONSTRAINT "FK_messages_channel_id" FOREIGN KEY ("channel_id") REFERENCES "channels"("id") ON DELETE CASCADE ON UPDATE NO ACTION;
+ALTER TABLE "messages" ADD CONSTRAINT "FK_messages_guild_id" FOREIGN KEY ("guild_id") REFERENCES "guilds"("id") ON DELETE CASCADE ON UPDATE NO ACTION;
+ALTER TABLE "messages" ADD CONSTRAINT "FK_messages_author_id" FOREIGN KEY ("author_id") REFERENCES "users"("id") ON DELETE CASCADE ON UPDATE NO ACTION;
+ALTER TABLE "messages" ADD CONSTRAINT "FK_messages_message_reference_id" FOREIGN KEY ("message_reference_id") REFERENCES "messages"("id") ON DELETE NO ACTION ON UPDATE NO ACTION;
+
+CREATE TABLE "backup_codes" ("id" character varying NOT NULL, "code" character varying NOT NULL, "consumed" boolean NOT NULL, "expired" boolean NOT NULL, "user_id" character varying, CONSTRAINT "PK_backup_codes" PRIMARY KEY ("id"));
+ALTER TABLE "backup_codes" ADD CONSTRAINT "FK_backup_codes_user_id" FOREIGN KEY ("user_id") REFERENCES "users"("id") ON DELETE CASCADE ON UPDATE NO ACTION;
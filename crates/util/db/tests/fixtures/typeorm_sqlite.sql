@@ -0,0 +1,15 @@
+-- Tables with boolean and date columns as TypeORM synchronises them on
+-- SQLite, replacing the baseline versions of the same tables.
+PRAGMA foreign_keys = OFF;
+DROP TABLE "backup_codes";
+DROP TABLE "messages";
+DROP TABLE "users";
+
+CREATE TABLE "users" ("id" varchar PRIMARY KEY NOT NULL, "username" varchar NOT NULL, "discriminator" varchar NOT NULL, "avatar" varchar, "accent_color" integer, "banner" varchar, "theme_colors" text, "pronouns" varchar, "phone" varchar, "desktop" boolean NOT NULL, "mobile" boolean NOT NULL, "premium" boolean NOT NULL, "premium_type" integer NOT NULL, "bot" boolean NOT NULL, "bio" varchar NOT NULL, "system" boolean NOT NULL, "nsfw_allowed" boolean NOT NULL, "mfa_enabled" boolean NOT NULL, "webauthn_enabled" boolean NOT NULL DEFAULT (0), "totp_secret" varchar, "totp_last_ticket" varchar, "created_at" datetime NOT NULL, "premium_since" datetime, "verified" boolean NOT NULL, "disabled" boolean NOT NULL, "deleted" boolean NOT NULL, "email" varchar, "flags" integer NOT NULL, "public_flags" integer NOT NULL, "purchased_flags" integer NOT NULL, "premium_usage_flags" integer NOT NULL, "rights" bigint NOT NULL, "data" text NOT NULL, "fingerprints" text NOT NULL, "extended_settings" text NOT NULL, "badge_ids" text, "settingsIndex" integer, CONSTRAINT "REL_users_settingsIndex" UNIQUE ("settingsIndex"), CONSTRAINT "FK_users_settingsIndex" FOREIGN KEY ("settingsIndex") REFERENCES "user_settings" ("index") ON DELETE NO ACTION ON UPDATE NO ACTION);
+
+CREATE TABLE "messages" ("id" varchar PRIMARY KEY NOT NULL, "channel_id" varchar, "guild_id" varchar, "author_id" varchar, "member_id" varchar, "webhook_id" varchar, "application_id" varchar, "content" varchar, "timestamp" datetime NOT NULL DEFAULT (datetime('now')), "edited_timestamp" datetime, "tts" boolean, "mention_everyone" boolean, "embeds" text NOT NULL, "reactions" text NOT NULL, "nonce" text, "pinned" boolean, "type" integer NOT NULL, "activity" text, "flags" integer NOT NULL DEFAULT (0), "message_reference" text, "interaction" text, "components" text, "poll" text, "username" varchar, "avatar" varchar, "message_reference_id" varchar, CONSTRAINT "FK_messages_channel_id" FOREIGN KEY ("channel_id") REFERENCES "channels" ("id") ON DELETE CASCADE ON UPDATE NO ACTION, CONSTRAINT "FK_messages_guild_id" FOREIGN KEY ("guild_id") REFERENCES "guilds" ("id") ON DELETE CASCADE ON UPDATE NO ACTION, CONSTRAINT "FK_messages_author_id" FOREIGN KEY ("author_id") REFERENCES "users" ("id") ON DELETE CASCADE ON UPDATE NO ACTION, CONSTRAINT "FK_messages_message_reference_id" FOREIGN KEY ("message_reference_id") REFERENCES "messages" ("id") ON DELETE NO ACTION ON UPDATE NO ACTION);
+CREATE UNIQUE INDEX "IDX_messages_channel_id_id" ON "messages" ("channel_id", "id");
+CREATE INDEX "IDX_messages_author_id" ON "messages" ("author_id");
+
+CREATE TABLE "backup_codes" ("id" varchar PRIMARY KEY NOT NULL, "code" varchar NOT NULL, "consumed" boolean NOT NULL, "expired" boolean NOT NULL, "user_id" varchar, CONSTRAINT "FK_backup_codes_user_id" FOREIGN KEY ("user_id") REFERENCES "users" ("id") ON DELETE CASCADE ON UPDATE NO ACTION);
+PRAGMA foreign_keys = ON;
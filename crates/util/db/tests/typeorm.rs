@@ -0,0 +1,480 @@
+//! Databases left by the TypeScript server are imported on first start.
+//!
+//! The TypeScript schema is stood in for by the baseline Rust migrations,
+//! applied without sqlx's bookkeeping table, with the tables holding boolean
+//! and date columns replaced by the ones TypeORM creates, from `fixtures/`.
+//!
+//! SQLite runs on a scratch file. Like the migration tests, the other
+//! dialects are ignored unless run with `--ignored`, and read the URL of a
+//! server they may create and drop a scratch database on from
+//! `SPACEBAR_TEST_POSTGRES_URL`, `SPACEBAR_TEST_MYSQL_URL` and
+//! `SPACEBAR_TEST_MARIADB_URL`. The servers are expected to run in UTC.
+
+use chrono::{DateTime, Utc};
+use serde_json::{json, Value};
+use sqlx::{any::install_default_drivers, AnyPool};
+use util_db::{
+    close_database,
+    entities::{BackupCode, Config, User},
+    init_database,
+    types::{Bool, Timestamp},
+    Dialect,
+};
+
+/// Last Rust migration whose tables the TypeScript schema already contains.
+const BASELINE_VERSION: i64 = 20240101000008;
+
+/// Connect to `url`, which sqlx reaches through its MySQL driver for MariaDB.
+async fn connect(url: &str) -> AnyPool {
+    install_default_drivers();
+    match url.strip_prefix("mariadb:") {
+        Some(rest) => AnyPool::connect(&format!("mysql:{rest}")).await,
+        None => AnyPool::connect(url).await,
+    }
+    .unwrap()
+}
+
+/// Create a database at `url` laid out like one the TypeScript server
+/// created, holding the configuration rows `config`.
+async fn typescript_database(url: &str, config: &[(&str, Value)]) {
+    let dialect = Dialect::from_url(url).unwrap();
+    let pool = connect(url).await;
+    for migration in dialect.migrator().iter() {
+        if migration.version <= BASELINE_VERSION && !migration.migration_type.is_down_migration() {
+            sqlx::raw_sql(&migration.sql).execute(&pool).await.unwrap();
+        }
+    }
+    let tables = match dialect {
+        Dialect::Sqlite => include_str!("fixtures/typeorm_sqlite.sql"),
+        Dialect::Postgres => include_str!("fixtures/typeorm_postgres.sql"),
+        Dialect::Mysql | Dialect::MariaDb => include_str!("fixtures/typeorm_mysql.sql"),
+    };
+    sqlx::raw_sql(tables).execute(&pool).await.unwrap();
+
+    // TypeORM migrates the databases it does not synchronise.
+    let webauthn = match dialect {
+        Dialect::Sqlite => None,
+        Dialect::Postgres => Some("webauthn1675044825710"),
+        Dialect::Mysql | Dialect::MariaDb => Some("webauthn1675045120206"),
+    };
+    if let Some(webauthn) = webauthn {
+        let migrations = [
+            webauthn,
+            "guildChannelOrdering1696420827239",
+            "MessageFlagsNotNull1713116476900",
+            "MessagePollObject1720157926878",
+            "Badges1720628601997",
+            "WebhookMessageProperties1721298824927",
+            "client_status1723347738541",
+            "WebhookSourceChannel1723644478176",
+        ];
+        for (id, name) in migrations.into_iter().enumerate() {
+            let timestamp: i64 = name[name.len() - 13..].parse().unwrap();
+            sqlx::query(&format!(
+                "INSERT INTO migrations (id, {}, name) VALUES ({}, {}, {})",
+                dialect.quote("timestamp"),
+                dialect.placeholder(1),
+                dialect.placeholder(2),
+                dialect.placeholder(3)
+            ))
+            .bind(id as i64 + 1)
+            .bind(timestamp)
+            .bind(name)
+            .execute(&pool)
+            .await
+            .unwrap();
+        }
+    }
+
+    for (key, value) in config {
+        sqlx::query(&format!(
+            "INSERT INTO config ({}, value) VALUES ({}, {})",
+            dialect.quote("key"),
+            dialect.placeholder(1),
+            dialect.placeholder(2)
+        ))
+        .bind(*key)
+        .bind(value.to_string())
+        .execute(&pool)
+        .await
+        .unwrap();
+    }
+    pool.close().await;
+}
+
+/// URL of a new SQLite database file, removed along with the returned path.
+fn scratch_database(name: &str) -> (std::path::PathBuf, String) {
+    let path = std::env::temp_dir().join(format!("spacebar_{name}_{}.db", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    let url = format!("sqlite://{}?mode=rwc", path.display());
+    (path, url)
+}
+
+fn value<'a>(rows: &'a [Config], key: &str) -> Option<&'a Value> {
+    rows.iter()
+        .find(|row| row.key == key)
+        .and_then(|row| row.value.as_ref())
+        .map(|value| &value.0)
+}
+
+#[tokio::test]
+async fn typescript_config_keys_are_converted() {
+    let (path, url) = scratch_database("typeorm_config");
+    typescript_database(
+        &url,
+        &[
+            ("security_jwtSecret", json!("imported secret")),
+            ("guild_autoJoin_canLeave", json!(false)),
+            ("guild_autoJoin_guilds_0", json!("1")),
+            ("guild_autoJoin_guilds_1", json!("2")),
+            ("guild_discovery_showAllGuilds", json!(true)),
+        ],
+    )
+    .await;
+
+    let pool = init_database(&url).await.unwrap();
+    let rows = Config::all(&pool).await.unwrap();
+    close_database(pool).await;
+    let _ = std::fs::remove_file(&path);
+
+    assert!(rows.iter().all(|row| row.key.contains('.')), "{rows:?}");
+    assert_eq!(
+        value(&rows, "security.jwt_secret"),
+        Some(&json!("imported secret"))
+    );
+    assert_eq!(
+        value(&rows, "guild.auto_join.can_leave"),
+        Some(&json!(false))
+    );
+    assert_eq!(
+        value(&rows, "guild.auto_join.guilds"),
+        Some(&json!(["1", "2"]))
+    );
+    assert_eq!(
+        value(&rows, "guild.discovery.show_all_guilds"),
+        Some(&json!(true))
+    );
+    // Fields the TypeScript server did not store get their default.
+    assert!(value(&rows, "general.instance_name").is_some());
+}
+
+#[tokio::test]
+async fn interrupted_import_is_finished() {
+    let (path, url) = scratch_database("typeorm_interrupted");
+    typescript_database(&url, &[("security_jwtSecret", json!("imported secret"))]).await;
+
+    // A MySQL import can stop after creating sqlx's table and hashing some
+    // of the backup codes.
+    let hashed = BackupCode::hash("hashed00");
+    let pool = connect(&url).await;
+    sqlx::query(
+        "CREATE TABLE _sqlx_migrations (version BIGINT PRIMARY KEY, description TEXT NOT NULL, \
+         installed_on TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP, success BOOLEAN NOT NULL, \
+         checksum BLOB NOT NULL, execution_time BIGINT NOT NULL)",
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+    for (id, code) in [("1", "plain000"), ("2", hashed.as_str())] {
+        sqlx::query("INSERT INTO backup_codes (id, code, consumed, expired) VALUES ($1, $2, 0, 0)")
+            .bind(id)
+            .bind(code)
+            .execute(&pool)
+            .await
+            .unwrap();
+    }
+    pool.close().await;
+
+    let pool = init_database(&url).await.unwrap();
+    let rows = Config::all(&pool).await.unwrap();
+    let codes: Vec<String> = sqlx::query_scalar("SELECT code FROM backup_codes ORDER BY id")
+        .fetch_all(&pool)
+        .await
+        .unwrap();
+    close_database(pool).await;
+    let _ = std::fs::remove_file(&path);
+
+    assert_eq!(
+        value(&rows, "security.jwt_secret"),
+        Some(&json!("imported secret"))
+    );
+    assert_eq!(codes, [BackupCode::hash("plain000"), hashed]);
+}
+
+/// 2023-01-01T00:00:00Z in seconds.
+const JANUARY_2023: i64 = 1672531200;
+
+/// Add a user and `count` messages reacted to by that user and a deleted
+/// one to the database at `url`.
+async fn reacted_messages(url: &str, count: usize) {
+    let pool = connect(url).await;
+    let mut tx = pool.begin().await.unwrap();
+    sqlx::query(
+        "INSERT INTO users (id, username, discriminator, desktop, mobile, premium, premium_type, \
+         bot, bio, system, nsfw_allowed, mfa_enabled, created_at, verified, disabled, deleted, \
+         flags, public_flags, purchased_flags, premium_usage_flags, rights, data, fingerprints, \
+         extended_settings) VALUES ('1', 'user', '0001', 0, 0, 0, 0, 0, '', 0, 1, 0, \
+         '2023-01-01 00:00:00', 1, 0, 0, 0, 0, 0, 0, 0, '{}', '', '{}')",
+    )
+    .execute(&mut *tx)
+    .await
+    .unwrap();
+    let reactions = json!([
+        { "emoji": { "name": "👍" }, "count": 2, "user_ids": ["1", "2"] },
+        { "emoji": { "id": "50", "name": "party", "animated": true }, "user_ids": ["1"] },
+        { "emoji": {}, "user_ids": ["1"] },
+    ])
+    .to_string();
+    for n in 0..count {
+        sqlx::query(
+            "INSERT INTO messages (id, timestamp, embeds, reactions, type) \
+             VALUES ($1, datetime($2, 'unixepoch'), '[]', $3, 0)",
+        )
+        .bind(format!("{n:04}"))
+        .bind(JANUARY_2023 + n as i64)
+        .bind(&reactions)
+        .execute(&mut *tx)
+        .await
+        .unwrap();
+    }
+    for (id, reactions) in [("a", "[]"), ("b", "not json")] {
+        sqlx::query(
+            "INSERT INTO messages (id, timestamp, embeds, reactions, type) \
+             VALUES ($1, '2023-01-01 00:00:00', '[]', $2, 0)",
+        )
+        .bind(id)
+        .bind(reactions)
+        .execute(&mut *tx)
+        .await
+        .unwrap();
+    }
+    tx.commit().await.unwrap();
+    pool.close().await;
+}
+
+async fn reaction_count(pool: &AnyPool) -> i64 {
+    sqlx::query_scalar("SELECT COUNT(*) FROM message_reactions")
+        .fetch_one(pool)
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn reactions_are_copied_in_pages() {
+    let (path, url) = scratch_database("typeorm_reactions");
+    typescript_database(&url, &[]).await;
+    // More than one page of messages.
+    reacted_messages(&url, 700).await;
+
+    let pool = init_database(&url).await.unwrap();
+    let copied = reaction_count(&pool).await;
+    let party: (String, String, String, i64, i64) = sqlx::query_as(
+        "SELECT emoji, emoji_id, emoji_name, animated, created_at FROM message_reactions \
+         WHERE message_id = '0042' AND emoji_id IS NOT NULL",
+    )
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    close_database(pool).await;
+
+    // Starting again copies nothing twice, even if reactions were removed.
+    let pool = connect(&url).await;
+    sqlx::query("DELETE FROM message_reactions")
+        .execute(&pool)
+        .await
+        .unwrap();
+    pool.close().await;
+    let pool = init_database(&url).await.unwrap();
+    let recopied = reaction_count(&pool).await;
+    close_database(pool).await;
+    let _ = std::fs::remove_file(&path);
+
+    // Two reactions per message by the remaining user.
+    assert_eq!(copied, 1400);
+    assert_eq!(
+        party,
+        (
+            "50".into(),
+            "50".into(),
+            "party".into(),
+            1,
+            (JANUARY_2023 + 42) * 1000
+        )
+    );
+    assert_eq!(recopied, 0);
+}
+
+#[tokio::test]
+async fn interrupted_reaction_copy_resumes() {
+    let (path, url) = scratch_database("typeorm_reactions_resumed");
+    typescript_database(&url, &[]).await;
+    reacted_messages(&url, 100).await;
+    init_database(&url).await.unwrap().close().await;
+
+    // Stopped after copying the reactions of the messages up to 0059.
+    let pool = connect(&url).await;
+    sqlx::query("DELETE FROM message_reactions WHERE message_id > '0059'")
+        .execute(&pool)
+        .await
+        .unwrap();
+    sqlx::query("CREATE TABLE _reaction_import (last_message_id VARCHAR(255))")
+        .execute(&pool)
+        .await
+        .unwrap();
+    sqlx::query("INSERT INTO _reaction_import (last_message_id) VALUES ('0059')")
+        .execute(&pool)
+        .await
+        .unwrap();
+    // Reactions removed before the interruption stay removed.
+    sqlx::query("DELETE FROM message_reactions WHERE message_id = '0010'")
+        .execute(&pool)
+        .await
+        .unwrap();
+    pool.close().await;
+
+    let pool = init_database(&url).await.unwrap();
+    let copied = reaction_count(&pool).await;
+    let pending: Option<i64> = sqlx::query_scalar("SELECT 1 FROM _reaction_import")
+        .fetch_optional(&pool)
+        .await
+        .unwrap_or_default();
+    close_database(pool).await;
+    let _ = std::fs::remove_file(&path);
+
+    assert_eq!(copied, 198);
+    assert_eq!(pending, None);
+}
+
+/// Import a database at `url` holding rows as the TypeScript server wrote
+/// them, and check their boolean and date values.
+async fn typescript_values_are_converted(url: &str) {
+    let dialect = Dialect::from_url(url).unwrap();
+    typescript_database(url, &[]).await;
+    let pool = connect(url).await;
+    sqlx::raw_sql(&format!(
+        "INSERT INTO users (id, username, discriminator, desktop, mobile, premium, premium_type, \
+         bot, bio, {}, nsfw_allowed, mfa_enabled, created_at, premium_since, verified, disabled, \
+         deleted, flags, public_flags, purchased_flags, premium_usage_flags, rights, data, \
+         fingerprints, extended_settings) VALUES ('1', 'user', '0001', TRUE, FALSE, FALSE, 0, \
+         TRUE, '', FALSE, TRUE, FALSE, '2023-05-01 12:34:56', NULL, TRUE, FALSE, FALSE, 0, 0, \
+         0, 0, 0, '{}', '', '{{}}');
+         INSERT INTO messages (id, author_id, content, {}, tts, pinned, embeds, reactions, type) \
+         VALUES ('10', '1', 'dated', '2023-05-01 12:34:56.789', TRUE, FALSE, '[]', '[]', 0);
+         INSERT INTO messages (id, author_id, content, embeds, reactions, type) \
+         VALUES ('11', '1', 'defaulted', '[]', '[]', 0);
+         INSERT INTO backup_codes (id, code, consumed, expired, user_id) \
+         VALUES ('20', '12345678', TRUE, FALSE, '1');",
+        dialect.quote("system"),
+        r#"{"valid_tokens_since":"2023-05-01T12:34:56.000Z"}"#,
+        dialect.quote("timestamp")
+    ))
+    .execute(&pool)
+    .await
+    .unwrap();
+    pool.close().await;
+
+    let pool = init_database(url).await.unwrap();
+    let user = User::find(&pool, "1").await.unwrap().unwrap();
+    let messages: Vec<(Timestamp, Option<Bool>, Option<Bool>)> = sqlx::query_as(&format!(
+        "SELECT {}, tts, pinned FROM messages ORDER BY id",
+        dialect.quote("timestamp")
+    ))
+    .fetch_all(&pool)
+    .await
+    .unwrap();
+    let code: (Bool, Bool) = sqlx::query_as("SELECT consumed, expired FROM backup_codes")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    close_database(pool).await;
+
+    let flags = [
+        *user.desktop,
+        *user.mobile,
+        *user.premium,
+        *user.bot,
+        *user.system,
+        *user.nsfw_allowed,
+        *user.mfa_enabled,
+        *user.verified,
+        *user.disabled,
+        *user.deleted,
+    ];
+    assert_eq!(
+        flags,
+        [true, false, false, true, false, true, false, true, false, false]
+    );
+    assert_eq!(user.created_at.as_millis(), 1682944496000);
+    assert!(user.premium_since.is_none());
+
+    let (timestamp, tts, pinned) = &messages[0];
+    assert_eq!(timestamp.as_millis(), 1682944496789);
+    assert_eq!(
+        (tts.map(|b| *b), pinned.map(|b| *b)),
+        (Some(true), Some(false))
+    );
+    // TypeORM's default dated the other message when it was sent.
+    let age = Utc::now() - DateTime::from(messages[1].0);
+    assert!(age.num_hours().abs() < 1, "{age}");
+
+    assert_eq!((*code.0, *code.1), (true, false));
+}
+
+#[tokio::test]
+async fn sqlite_values_are_converted() {
+    let (path, url) = scratch_database("typeorm_values");
+    typescript_values_are_converted(&url).await;
+    let _ = std::fs::remove_file(&path);
+}
+
+/// Create a scratch database on the server whose URL is in the environment
+/// variable `var`. Returns a pool on the server and the database's URL.
+async fn server_database(dialect: Dialect, var: &str) -> (AnyPool, String) {
+    let admin_url = std::env::var(var).unwrap_or_else(|_| panic!("{var} is not set"));
+    assert_eq!(Dialect::from_url(&admin_url), Some(dialect), "{var}");
+    let admin = connect(&admin_url).await;
+    let name = format!("spacebar_typeorm_{}", std::process::id());
+    sqlx::query(&format!("DROP DATABASE IF EXISTS {name}"))
+        .execute(&admin)
+        .await
+        .unwrap();
+    sqlx::query(&format!("CREATE DATABASE {name}"))
+        .execute(&admin)
+        .await
+        .unwrap();
+    let (base, _) = admin_url.rsplit_once('/').unwrap();
+    (admin, format!("{base}/{name}"))
+}
+
+async fn drop_server_database(admin: AnyPool, url: &str) {
+    let (_, name) = url.rsplit_once('/').unwrap();
+    sqlx::query(&format!("DROP DATABASE {name}"))
+        .execute(&admin)
+        .await
+        .unwrap();
+    admin.close().await;
+}
+
+#[tokio::test]
+#[ignore = "needs a Postgres server at SPACEBAR_TEST_POSTGRES_URL"]
+async fn postgres_values_are_converted() {
+    let (admin, url) = server_database(Dialect::Postgres, "SPACEBAR_TEST_POSTGRES_URL").await;
+    typescript_values_are_converted(&url).await;
+    drop_server_database(admin, &url).await;
+}
+
+#[tokio::test]
+#[ignore = "needs a MySQL server at SPACEBAR_TEST_MYSQL_URL"]
+async fn mysql_values_are_converted() {
+    let (admin, url) = server_database(Dialect::Mysql, "SPACEBAR_TEST_MYSQL_URL").await;
+    typescript_values_are_converted(&url).await;
+    drop_server_database(admin, &url).await;
+}
+
+#[tokio::test]
+#[ignore = "needs a MariaDB server at SPACEBAR_TEST_MARIADB_URL"]
+async fn mariadb_values_are_converted() {
+    let (admin, url) = server_database(Dialect::MariaDb, "SPACEBAR_TEST_MARIADB_URL").await;
+    typescript_values_are_converted(&url).await;
+    drop_server_database(admin, &url).await;
+}
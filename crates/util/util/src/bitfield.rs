@@ -0,0 +1,76 @@
+//! Conversions shared by the bitfields of the TypeScript server, see
+//! `src/util/util/BitField.ts`.
+
+/// Implement the conversions of a `bitflags` type over `u64`: decimal
+/// strings for [`Display`](std::fmt::Display), [`FromStr`](std::str::FromStr)
+/// and serde, since values above 2^53 do not survive a JSON number, and
+/// accepting integers as well when deserialising. Unknown bits are kept, so
+/// values written by newer clients round-trip unchanged.
+macro_rules! bitfield {
+    ($name:ident, $expecting:literal) => {
+        impl $name {
+            /// Names of the known flags set in `self`, separated by commas.
+            pub fn names(self) -> String {
+                self.iter_names()
+                    .map(|(name, _)| name)
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            }
+        }
+
+        impl std::fmt::Display for $name {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                self.bits().fmt(f)
+            }
+        }
+
+        impl std::str::FromStr for $name {
+            type Err = std::num::ParseIntError;
+
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                s.trim().parse().map(Self::from_bits_retain)
+            }
+        }
+
+        impl serde::Serialize for $name {
+            fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                serializer.collect_str(self)
+            }
+        }
+
+        impl<'de> serde::Deserialize<'de> for $name {
+            fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                use serde::de;
+
+                struct Visitor;
+
+                impl de::Visitor<'_> for Visitor {
+                    type Value = $name;
+
+                    fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                        f.write_str($expecting)
+                    }
+
+                    fn visit_u64<E: de::Error>(self, v: u64) -> Result<$name, E> {
+                        Ok($name::from_bits_retain(v))
+                    }
+
+                    fn visit_i64<E: de::Error>(self, v: i64) -> Result<$name, E> {
+                        u64::try_from(v)
+                            .map($name::from_bits_retain)
+                            .map_err(|_| E::invalid_value(de::Unexpected::Signed(v), &self))
+                    }
+
+                    fn visit_str<E: de::Error>(self, v: &str) -> Result<$name, E> {
+                        v.parse()
+                            .map_err(|_| E::invalid_value(de::Unexpected::Str(v), &self))
+                    }
+                }
+
+                deserializer.deserialize_any(Visitor)
+            }
+        }
+    };
+}
+
+pub(crate) use bitfield;
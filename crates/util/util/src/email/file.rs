@@ -0,0 +1,40 @@
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use super::{EmailTransport, Mail, SendFuture};
+use crate::Snowflake;
+
+/// Maildir used when `email.file.path` is not configured.
+pub(super) const DEFAULT_PATH: &str = "emails";
+
+/// Delivery into a local maildir, for development. Any mail client that
+/// reads maildirs can open the result.
+pub struct FileTransport {
+    root: PathBuf,
+}
+
+impl FileTransport {
+    pub fn new(root: PathBuf) -> Self {
+        Self { root }
+    }
+}
+
+impl EmailTransport for FileTransport {
+    fn send<'a>(&'a self, mail: &'a Mail) -> SendFuture<'a> {
+        Box::pin(async move {
+            let message = mail.to_message()?.formatted();
+            for dir in ["tmp", "new", "cur"] {
+                tokio::fs::create_dir_all(self.root.join(dir)).await?;
+            }
+
+            // Write into tmp and move into new, so that readers never see a
+            // partial message.
+            let time = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+            let name = format!("{time}.{}.spacebar.eml", Snowflake::generate());
+            let tmp = self.root.join("tmp").join(&name);
+            tokio::fs::write(&tmp, message).await?;
+            tokio::fs::rename(&tmp, self.root.join("new").join(&name)).await?;
+            Ok(())
+        })
+    }
+}
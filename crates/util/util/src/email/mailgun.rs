@@ -0,0 +1,54 @@
+use anyhow::{anyhow, Result};
+use config::MailGunConfiguration;
+
+use super::{check_response, EmailTransport, Mail, SendFuture};
+
+const DEFAULT_ENDPOINT: &str = "https://api.mailgun.net";
+
+/// Delivery through the Mailgun messages API.
+pub struct MailgunTransport {
+    client: reqwest::Client,
+    endpoint: String,
+    api_key: String,
+    domain: String,
+}
+
+impl MailgunTransport {
+    pub fn new(endpoint: &str, api_key: &str, domain: &str) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            endpoint: endpoint.trim_end_matches('/').to_string(),
+            api_key: api_key.to_string(),
+            domain: domain.to_string(),
+        }
+    }
+
+    pub fn from_config(cfg: &MailGunConfiguration) -> Result<Self> {
+        let (Some(api_key), Some(domain)) = (&cfg.api_key, &cfg.domain) else {
+            return Err(anyhow!("Mailgun has not been configured correctly."));
+        };
+        let endpoint = cfg.endpoint.as_deref().unwrap_or(DEFAULT_ENDPOINT);
+        Ok(Self::new(endpoint, api_key, domain))
+    }
+}
+
+impl EmailTransport for MailgunTransport {
+    fn send<'a>(&'a self, mail: &'a Mail) -> SendFuture<'a> {
+        Box::pin(async move {
+            let form = [
+                ("from", mail.from.as_str()),
+                ("to", mail.to.as_str()),
+                ("subject", mail.subject.as_str()),
+                ("html", mail.html.as_str()),
+            ];
+            let response = self
+                .client
+                .post(format!("{}/v3/{}/messages", self.endpoint, self.domain))
+                .basic_auth("api", Some(&self.api_key))
+                .form(&form)
+                .send()
+                .await?;
+            check_response("Mailgun", response).await
+        })
+    }
+}
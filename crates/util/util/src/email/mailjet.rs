@@ -0,0 +1,57 @@
+use anyhow::{anyhow, Result};
+use config::MailJetConfiguration;
+use serde_json::json;
+
+use super::{check_response, EmailTransport, Mail, SendFuture};
+
+const DEFAULT_ENDPOINT: &str = "https://api.mailjet.com";
+
+/// Delivery through the Mailjet Send API v3.1.
+pub struct MailjetTransport {
+    client: reqwest::Client,
+    endpoint: String,
+    api_key: String,
+    api_secret: String,
+}
+
+impl MailjetTransport {
+    pub fn new(endpoint: &str, api_key: &str, api_secret: &str) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            endpoint: endpoint.trim_end_matches('/').to_string(),
+            api_key: api_key.to_string(),
+            api_secret: api_secret.to_string(),
+        }
+    }
+
+    pub fn from_config(cfg: &MailJetConfiguration) -> Result<Self> {
+        let (Some(api_key), Some(api_secret)) = (&cfg.api_key, &cfg.api_secret) else {
+            return Err(anyhow!("Mailjet has not been configured correctly."));
+        };
+        let endpoint = cfg.endpoint.as_deref().unwrap_or(DEFAULT_ENDPOINT);
+        Ok(Self::new(endpoint, api_key, api_secret))
+    }
+}
+
+impl EmailTransport for MailjetTransport {
+    fn send<'a>(&'a self, mail: &'a Mail) -> SendFuture<'a> {
+        Box::pin(async move {
+            let body = json!({
+                "Messages": [{
+                    "From": { "Email": mail.from },
+                    "To": [{ "Email": mail.to }],
+                    "Subject": mail.subject,
+                    "HTMLPart": mail.html,
+                }],
+            });
+            let response = self
+                .client
+                .post(format!("{}/v3.1/send", self.endpoint))
+                .basic_auth(&self.api_key, Some(&self.api_secret))
+                .json(&body)
+                .send()
+                .await?;
+            check_response("Mailjet", response).await
+        })
+    }
+}
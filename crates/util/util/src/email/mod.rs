@@ -0,0 +1,235 @@
+use std::{
+    future::Future,
+    path::PathBuf,
+    pin::Pin,
+    sync::{Arc, RwLock},
+};
+
+use anyhow::{anyhow, Result};
+use config::{Config, ConfigHandle, EmailConfiguration};
+use lettre::message::header::ContentType;
+use lettre::Message;
+
+mod file;
+mod mailgun;
+mod mailjet;
+mod sendgrid;
+mod smtp;
+
+pub use file::FileTransport;
+pub use mailgun::MailgunTransport;
+pub use mailjet::MailjetTransport;
+pub use sendgrid::SendGridTransport;
+pub use smtp::SmtpTransport;
+
+/// Transport built from the current `email` section for
+/// [`Email::send_template`], or why none could be built.
+static CURRENT: RwLock<Option<Arc<Result<Email>>>> = RwLock::new(None);
+
+/// Sender used when neither `email.senderAddress` nor
+/// `general.correspondenceEmail` is configured.
+const DEFAULT_SENDER: &str = "noreply@localhost";
+
+/// Templates bundled from `assets/email_templates`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Template {
+    VerifyEmail,
+    PasswordResetRequest,
+    PasswordChanged,
+    NewLoginLocation,
+    PhoneRemoved,
+}
+
+impl Template {
+    fn source(self) -> &'static str {
+        match self {
+            Self::VerifyEmail => {
+                include_str!("../../../../../assets/email_templates/verify_email.html")
+            }
+            Self::PasswordResetRequest => {
+                include_str!("../../../../../assets/email_templates/password_reset_request.html")
+            }
+            Self::PasswordChanged => {
+                include_str!("../../../../../assets/email_templates/password_changed.html")
+            }
+            Self::NewLoginLocation => {
+                include_str!("../../../../../assets/email_templates/new_login_location.html")
+            }
+            Self::PhoneRemoved => {
+                include_str!("../../../../../assets/email_templates/phone_removed.html")
+            }
+        }
+    }
+
+    /// Subject and HTML body with every `{name}` placeholder replaced by its
+    /// value in `vars`. The subject is the template's `<title>`.
+    pub fn render(self, vars: &[(&str, &str)]) -> (String, String) {
+        let source = self.source();
+        let title = source
+            .split_once("<title>")
+            .and_then(|(_, rest)| rest.split_once("</title>"))
+            .map(|(title, _)| title.trim())
+            .unwrap_or_default();
+
+        let mut subject = title.to_string();
+        let mut body = source.to_string();
+        for (name, value) in vars {
+            let placeholder = format!("{{{name}}}");
+            subject = subject.replace(&placeholder, value);
+            body = body.replace(&placeholder, &escape_html(value));
+        }
+        (subject, body)
+    }
+}
+
+fn escape_html(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// A rendered email, ready to be handed to a transport.
+#[derive(Debug, Clone)]
+pub struct Mail {
+    pub from: String,
+    pub to: String,
+    pub subject: String,
+    /// HTML body.
+    pub html: String,
+}
+
+impl Mail {
+    /// The message in RFC 5322 format, for transports that deliver raw mail.
+    pub fn to_message(&self) -> Result<Message> {
+        Ok(Message::builder()
+            .from(self.from.parse()?)
+            .to(self.to.parse()?)
+            .subject(&self.subject)
+            .header(ContentType::TEXT_HTML)
+            .body(self.html.clone())?)
+    }
+}
+
+/// Future of [`EmailTransport::send`].
+pub type SendFuture<'a> = Pin<Box<dyn Future<Output = Result<()>> + Send + 'a>>;
+
+/// A way of delivering email, selected by `email.provider`.
+pub trait EmailTransport: Send + Sync {
+    fn send<'a>(&'a self, mail: &'a Mail) -> SendFuture<'a>;
+}
+
+/// Turn an error status from the HTTP API of `provider` into an error
+/// carrying the reason it gave.
+async fn check_response(provider: &str, response: reqwest::Response) -> Result<()> {
+    let status = response.status();
+    if status.is_success() {
+        return Ok(());
+    }
+    let body = response.text().await.unwrap_or_default();
+    Err(anyhow!(
+        "{provider} rejected the email with {status}: {}",
+        body.trim()
+    ))
+}
+
+/// Email helper sending through the configured [`EmailTransport`].
+pub struct Email {
+    transport: Box<dyn EmailTransport>,
+}
+
+impl Email {
+    pub fn new(transport: Box<dyn EmailTransport>) -> Self {
+        Self { transport }
+    }
+
+    /// Initialise the transport of `email.provider`.
+    pub fn init(cfg: &EmailConfiguration) -> Result<Self> {
+        let provider = cfg.provider.as_deref().unwrap_or("").to_lowercase();
+        let transport: Box<dyn EmailTransport> = match provider.as_str() {
+            "smtp" => Box::new(SmtpTransport::from_config(&cfg.smtp)?),
+            "mailgun" => Box::new(MailgunTransport::from_config(&cfg.mailgun)?),
+            "mailjet" => Box::new(MailjetTransport::from_config(&cfg.mailjet)?),
+            "sendgrid" => Box::new(SendGridTransport::from_config(&cfg.sendgrid)?),
+            "file" => Box::new(FileTransport::new(PathBuf::from(
+                cfg.file.path.as_deref().unwrap_or(file::DEFAULT_PATH),
+            ))),
+            "" => return Err(anyhow!("no email provider configured")),
+            other => return Err(anyhow!("unsupported email provider {other}")),
+        };
+        Ok(Self::new(transport))
+    }
+
+    /// Send `mail` using the configured transport.
+    pub async fn send(&self, mail: &Mail) -> Result<()> {
+        self.transport.send(mail).await
+    }
+
+    /// Build the transport [`Email::send_template`] uses, and build it
+    /// again each time the configuration behind `config` is reloaded.
+    pub async fn follow(mut config: ConfigHandle) {
+        loop {
+            Self::install(&config.get().email);
+            if !config.changed().await {
+                return;
+            }
+        }
+    }
+
+    fn install(cfg: &EmailConfiguration) -> Arc<Result<Self>> {
+        let email = Arc::new(Self::init(cfg));
+        *CURRENT.write().unwrap_or_else(|e| e.into_inner()) = Some(email.clone());
+        email
+    }
+
+    /// The transport of the current configuration, built from `cfg` if
+    /// [`Email::follow`] has not run yet.
+    fn current(cfg: &EmailConfiguration) -> Arc<Result<Self>> {
+        let current = CURRENT.read().unwrap_or_else(|e| e.into_inner()).clone();
+        current.unwrap_or_else(|| Self::install(cfg))
+    }
+
+    /// Render `template` and send it to `to` with the configured transport.
+    ///
+    /// `instanceName` and `instanceUrl` are filled in from the configuration
+    /// in addition to `vars`.
+    pub async fn send_template(
+        config: &Config,
+        to: &str,
+        template: Template,
+        vars: &[(&str, &str)],
+    ) -> Result<()> {
+        let instance_url = config.general.front_page.as_deref().unwrap_or_default();
+        let mut all = vec![
+            ("instanceName", config.general.instance_name.as_str()),
+            ("instanceUrl", instance_url),
+        ];
+        all.extend_from_slice(vars);
+        let (subject, html) = template.render(&all);
+
+        let from = config
+            .email
+            .sender_address
+            .as_deref()
+            .or(config.general.correspondence_email.as_deref())
+            .unwrap_or(DEFAULT_SENDER);
+        let mail = Mail {
+            from: from.to_string(),
+            to: to.to_string(),
+            subject,
+            html,
+        };
+        match &*Self::current(&config.email) {
+            Ok(email) => email.send(&mail).await,
+            Err(e) => Err(anyhow!("{e}")),
+        }
+    }
+}
@@ -0,0 +1,53 @@
+use anyhow::{anyhow, Result};
+use config::SendGridConfiguration;
+use serde_json::json;
+
+use super::{check_response, EmailTransport, Mail, SendFuture};
+
+const DEFAULT_ENDPOINT: &str = "https://api.sendgrid.com";
+
+/// Delivery through the SendGrid v3 mail send API.
+pub struct SendGridTransport {
+    client: reqwest::Client,
+    endpoint: String,
+    api_key: String,
+}
+
+impl SendGridTransport {
+    pub fn new(endpoint: &str, api_key: &str) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            endpoint: endpoint.trim_end_matches('/').to_string(),
+            api_key: api_key.to_string(),
+        }
+    }
+
+    pub fn from_config(cfg: &SendGridConfiguration) -> Result<Self> {
+        let Some(api_key) = &cfg.api_key else {
+            return Err(anyhow!("SendGrid has not been configured correctly."));
+        };
+        let endpoint = cfg.endpoint.as_deref().unwrap_or(DEFAULT_ENDPOINT);
+        Ok(Self::new(endpoint, api_key))
+    }
+}
+
+impl EmailTransport for SendGridTransport {
+    fn send<'a>(&'a self, mail: &'a Mail) -> SendFuture<'a> {
+        Box::pin(async move {
+            let body = json!({
+                "personalizations": [{ "to": [{ "email": mail.to }] }],
+                "from": { "email": mail.from },
+                "subject": mail.subject,
+                "content": [{ "type": "text/html", "value": mail.html }],
+            });
+            let response = self
+                .client
+                .post(format!("{}/v3/mail/send", self.endpoint))
+                .bearer_auth(&self.api_key)
+                .json(&body)
+                .send()
+                .await?;
+            check_response("SendGrid", response).await
+        })
+    }
+}
@@ -0,0 +1,52 @@
+use anyhow::{anyhow, Result};
+use config::SMTPConfiguration;
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{AsyncSmtpTransport, AsyncTransport, Tokio1Executor};
+
+use super::{EmailTransport, Mail, SendFuture};
+
+/// Delivery to an SMTP relay.
+pub struct SmtpTransport {
+    transport: AsyncSmtpTransport<Tokio1Executor>,
+}
+
+impl SmtpTransport {
+    pub fn from_config(cfg: &SMTPConfiguration) -> Result<Self> {
+        let host = cfg
+            .host
+            .clone()
+            .ok_or_else(|| anyhow!("smtp.host missing"))?;
+        let port = cfg.port.unwrap_or(587);
+
+        // Choose secure or plain connection
+        let mut builder = if cfg.secure.unwrap_or(true) {
+            AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(&host)
+                .map_err(|e| anyhow!("{e}"))?
+        } else {
+            AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(&host)
+        };
+
+        builder = builder.port(port);
+
+        if let (Some(user), Some(pass)) = (&cfg.username, &cfg.password) {
+            let creds = Credentials::new(user.clone(), pass.clone());
+            builder = builder.credentials(creds);
+        }
+
+        Ok(Self {
+            transport: builder.build(),
+        })
+    }
+}
+
+impl EmailTransport for SmtpTransport {
+    fn send<'a>(&'a self, mail: &'a Mail) -> SendFuture<'a> {
+        Box::pin(async move {
+            self.transport
+                .send(mail.to_message()?)
+                .await
+                .map(|_| ())
+                .map_err(|e| anyhow!(e))
+        })
+    }
+}
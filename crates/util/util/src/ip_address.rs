@@ -0,0 +1,45 @@
+use std::net::IpAddr;
+
+use anyhow::Result;
+use serde_json::Value;
+
+/// Lookups of client IP addresses through ipdata.co.
+pub struct IpAddress;
+
+impl IpAddress {
+    /// Fetch what ipdata.co knows about `ip`. Private and loopback addresses
+    /// are not looked up and yield `None`.
+    pub async fn analyse(ip: IpAddr, api_key: &str) -> Result<Option<Value>> {
+        let public = match ip {
+            IpAddr::V4(ip) => !(ip.is_private() || ip.is_loopback() || ip.is_link_local()),
+            IpAddr::V6(ip) => !(ip.is_loopback() || ip.is_unspecified()),
+        };
+        if !public {
+            return Ok(None);
+        }
+        let data = reqwest::Client::new()
+            .get(format!("https://api.ipdata.co/{ip}"))
+            .query(&[("api-key", api_key)])
+            .send()
+            .await?
+            .json()
+            .await?;
+        Ok(Some(data))
+    }
+
+    /// Whether an ipdata.co result describes a proxy, VPN, hosting provider
+    /// or known threat.
+    pub fn is_proxy(data: &Value) -> bool {
+        let (Some(asn), Some(Value::Object(threat))) = (data.get("asn"), data.get("threat")) else {
+            return false;
+        };
+        if asn.get("type").and_then(Value::as_str) != Some("isp") {
+            return true;
+        }
+        threat.values().any(|value| match value {
+            Value::Bool(flag) => *flag,
+            Value::Array(list) => !list.is_empty(),
+            _ => false,
+        })
+    }
+}
@@ -1,9 +1,26 @@
+mod bitfield;
 pub mod email;
+pub mod ip_address;
 pub mod json;
+pub mod permissions;
+pub mod rights;
 pub mod sentry;
+pub mod snowflake;
+mod ticket;
+pub mod token;
+pub mod totp;
 pub mod webauthn;
 
-pub use email::Email;
+pub use email::{Email, EmailTransport, Mail, SendFuture, Template};
+pub use ip_address::IpAddress;
 pub use json::json_replacer;
+pub use permissions::{PermissionOverwrite, Permissions, OVERWRITE_MEMBER, OVERWRITE_ROLE};
+pub use rights::Rights;
 pub use sentry::Sentry;
+pub use snowflake::{Snowflake, SnowflakeGenerator};
+pub use token::{
+    decode_email_token, decode_token, generate_email_token, generate_token, EmailTokenClaims,
+    EmailTokenPurpose, TokenClaims,
+};
+pub use totp::Totp;
 pub use webauthn::WebAuthn;
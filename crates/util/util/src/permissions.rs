@@ -0,0 +1,180 @@
+//! Guild and channel permissions, ported from `src/util/util/Permissions.ts`.
+
+use bitflags::bitflags;
+use serde::{Deserialize, Serialize};
+
+use crate::bitfield::bitfield;
+
+bitflags! {
+    /// What a member may do in a guild or channel, with the bits Discord
+    /// clients expect.
+    #[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+    pub struct Permissions: u64 {
+        const CREATE_INSTANT_INVITE = 1 << 0;
+        const KICK_MEMBERS = 1 << 1;
+        const BAN_MEMBERS = 1 << 2;
+        /// Grants every permission and bypasses channel overwrites.
+        const ADMINISTRATOR = 1 << 3;
+        const MANAGE_CHANNELS = 1 << 4;
+        const MANAGE_GUILD = 1 << 5;
+        const ADD_REACTIONS = 1 << 6;
+        const VIEW_AUDIT_LOG = 1 << 7;
+        const PRIORITY_SPEAKER = 1 << 8;
+        const STREAM = 1 << 9;
+        const VIEW_CHANNEL = 1 << 10;
+        const SEND_MESSAGES = 1 << 11;
+        const SEND_TTS_MESSAGES = 1 << 12;
+        const MANAGE_MESSAGES = 1 << 13;
+        const EMBED_LINKS = 1 << 14;
+        const ATTACH_FILES = 1 << 15;
+        const READ_MESSAGE_HISTORY = 1 << 16;
+        const MENTION_EVERYONE = 1 << 17;
+        const USE_EXTERNAL_EMOJIS = 1 << 18;
+        const VIEW_GUILD_INSIGHTS = 1 << 19;
+        const CONNECT = 1 << 20;
+        const SPEAK = 1 << 21;
+        const MUTE_MEMBERS = 1 << 22;
+        const DEAFEN_MEMBERS = 1 << 23;
+        const MOVE_MEMBERS = 1 << 24;
+        const USE_VAD = 1 << 25;
+        const CHANGE_NICKNAME = 1 << 26;
+        const MANAGE_NICKNAMES = 1 << 27;
+        const MANAGE_ROLES = 1 << 28;
+        const MANAGE_WEBHOOKS = 1 << 29;
+        const MANAGE_EMOJIS_AND_STICKERS = 1 << 30;
+        const USE_APPLICATION_COMMANDS = 1 << 31;
+        const REQUEST_TO_SPEAK = 1 << 32;
+        const MANAGE_EVENTS = 1 << 33;
+        const MANAGE_THREADS = 1 << 34;
+        const USE_PUBLIC_THREADS = 1 << 35;
+        const USE_PRIVATE_THREADS = 1 << 36;
+        const USE_EXTERNAL_STICKERS = 1 << 37;
+        const SEND_MESSAGES_IN_THREADS = 1 << 38;
+        const USE_EMBEDDED_ACTIVITIES = 1 << 39;
+        const MODERATE_MEMBERS = 1 << 40;
+        const VIEW_CREATOR_MONETIZATION_ANALYTICS = 1 << 41;
+        const USE_SOUNDBOARD = 1 << 42;
+        const CREATE_GUILD_EXPRESSIONS = 1 << 43;
+        const CREATE_EVENTS = 1 << 44;
+        const USE_EXTERNAL_SOUNDS = 1 << 45;
+        const SEND_VOICE_MESSAGES = 1 << 46;
+        const SEND_POLLS = 1 << 49;
+        const USE_EXTERNAL_APPS = 1 << 50;
+    }
+}
+
+bitfield!(Permissions, "permissions as a string or integer");
+
+/// `type` of an overwrite for a role.
+pub const OVERWRITE_ROLE: i32 = 0;
+/// `type` of an overwrite for a single member.
+pub const OVERWRITE_MEMBER: i32 = 1;
+
+/// Role or member specific permission override stored on a channel.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PermissionOverwrite {
+    pub allow: Permissions,
+    pub deny: Permissions,
+    /// ID of the role or user, depending on `kind`.
+    pub id: String,
+    /// [`OVERWRITE_ROLE`] or [`OVERWRITE_MEMBER`].
+    #[serde(rename = "type")]
+    pub kind: i32,
+}
+
+impl Permissions {
+    /// Whether every permission in `permissions` is granted, always true for
+    /// administrators.
+    pub fn has(self, permissions: Permissions) -> bool {
+        self.contains(Self::ADMINISTRATOR) || self.contains(permissions)
+    }
+
+    /// Whether any permission in `permissions` is granted, always true for
+    /// administrators.
+    pub fn any(self, permissions: Permissions) -> bool {
+        self.contains(Self::ADMINISTRATOR) || self.intersects(permissions)
+    }
+
+    /// What the recipients of a DM or group DM may do in it. The owner of a
+    /// group DM has every permission instead.
+    pub fn dm_recipient() -> Self {
+        Self::VIEW_CHANNEL
+            | Self::SEND_MESSAGES
+            | Self::STREAM
+            | Self::ADD_REACTIONS
+            | Self::EMBED_LINKS
+            | Self::ATTACH_FILES
+            | Self::READ_MESSAGE_HISTORY
+            | Self::MENTION_EVERYONE
+            | Self::USE_EXTERNAL_EMOJIS
+            | Self::CONNECT
+            | Self::SPEAK
+            | Self::MANAGE_CHANNELS
+    }
+
+    /// Guild-wide permissions of a member holding `roles` on top of the
+    /// `@everyone` role. The guild owner and administrators get every
+    /// permission.
+    pub fn base(
+        is_owner: bool,
+        everyone: Permissions,
+        roles: impl IntoIterator<Item = Permissions>,
+    ) -> Self {
+        if is_owner {
+            return Self::all();
+        }
+        let permissions = roles.into_iter().fold(everyone, |total, role| total | role);
+        if permissions.contains(Self::ADMINISTRATOR) {
+            return Self::all();
+        }
+        permissions
+    }
+
+    /// Apply the channel `overwrites` to the guild-wide permissions `self` of
+    /// the member `user_id` holding `roles` in the guild `guild_id`.
+    ///
+    /// Like Discord, the `@everyone` overwrite, whose ID is the guild ID,
+    /// applies first, then the overwrites of all the member's roles at once,
+    /// then the overwrite of the member itself. Administrators are not
+    /// affected by overwrites.
+    pub fn overwrite<S: AsRef<str>>(
+        self,
+        guild_id: &str,
+        user_id: &str,
+        roles: &[S],
+        overwrites: &[PermissionOverwrite],
+    ) -> Self {
+        if self.contains(Self::ADMINISTRATOR) {
+            return Self::all();
+        }
+        let apply = |permissions: Self, allow: Self, deny: Self| (permissions & !deny) | allow;
+
+        let mut permissions = self;
+        if let Some(everyone) = overwrites
+            .iter()
+            .find(|o| o.kind == OVERWRITE_ROLE && o.id == guild_id)
+        {
+            permissions = apply(permissions, everyone.allow, everyone.deny);
+        }
+
+        let (allow, deny) = overwrites
+            .iter()
+            .filter(|o| {
+                o.kind == OVERWRITE_ROLE
+                    && o.id != guild_id
+                    && roles.iter().any(|role| role.as_ref() == o.id)
+            })
+            .fold((Self::empty(), Self::empty()), |(allow, deny), o| {
+                (allow | o.allow, deny | o.deny)
+            });
+        permissions = apply(permissions, allow, deny);
+
+        if let Some(member) = overwrites
+            .iter()
+            .find(|o| o.kind == OVERWRITE_MEMBER && o.id == user_id)
+        {
+            permissions = apply(permissions, member.allow, member.deny);
+        }
+        permissions
+    }
+}
@@ -0,0 +1,103 @@
+//! Instance-wide rights of a user, ported from `src/util/util/Rights.ts`.
+
+use bitflags::bitflags;
+
+use crate::bitfield::bitfield;
+
+bitflags! {
+    /// What a user may do on the instance, independent of any guild.
+    #[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+    pub struct Rights: u64 {
+        /// Has every right.
+        const OPERATOR = 1 << 0;
+        const MANAGE_APPLICATIONS = 1 << 1;
+        /// Manage all guilds instance-wide.
+        const MANAGE_GUILDS = 1 << 2;
+        /// Delete or edit messages in the channels the user can see.
+        const MANAGE_MESSAGES = 1 << 3;
+        const MANAGE_RATE_LIMITS = 1 << 4;
+        /// Create custom message routes to any channel or guild.
+        const MANAGE_ROUTING = 1 << 5;
+        /// Respond to and resolve support tickets.
+        const MANAGE_TICKETS = 1 << 6;
+        const MANAGE_USERS = 1 << 7;
+        /// Manually add any member to the guilds of the user.
+        const ADD_MEMBERS = 1 << 8;
+        const BYPASS_RATE_LIMITS = 1 << 9;
+        const CREATE_APPLICATIONS = 1 << 10;
+        /// Create channels or threads in the guilds the user has permission in.
+        const CREATE_CHANNELS = 1 << 11;
+        const CREATE_DMS = 1 << 12;
+        /// Create group DMs or custom orphan channels.
+        const CREATE_DM_GROUPS = 1 << 13;
+        const CREATE_GUILDS = 1 << 14;
+        /// Create mass invites in the guilds the user has
+        /// `CREATE_INSTANT_INVITE` in.
+        const CREATE_INVITES = 1 << 15;
+        const CREATE_ROLES = 1 << 16;
+        const CREATE_TEMPLATES = 1 << 17;
+        const CREATE_WEBHOOKS = 1 << 18;
+        const JOIN_GUILDS = 1 << 19;
+        const PIN_MESSAGES = 1 << 20;
+        const SELF_ADD_REACTIONS = 1 << 21;
+        const SELF_DELETE_MESSAGES = 1 << 22;
+        const SELF_EDIT_MESSAGES = 1 << 23;
+        const SELF_EDIT_NAME = 1 << 24;
+        const SEND_MESSAGES = 1 << 25;
+        /// Use activities in voice channels, such as Watch Together.
+        const USE_ACTIVITIES = 1 << 26;
+        const USE_VIDEO = 1 << 27;
+        const USE_VOICE = 1 << 28;
+        /// Create user-specific invites in the guilds the user has
+        /// `INVITE_USERS` in.
+        const INVITE_USERS = 1 << 29;
+        /// Disable or delete the own account.
+        const SELF_DELETE_DISABLE = 1 << 30;
+        /// Use pay-to-use features.
+        const DEBTABLE = 1 << 31;
+        /// Receive money from monetisation features.
+        const CREDITABLE = 1 << 32;
+        const KICK_BAN_MEMBERS = 1 << 33;
+        const SELF_LEAVE_GROUPS = 1 << 34;
+        const PRESENCE = 1 << 35;
+        /// Mark guilds discoverable that the user may mark as discoverable.
+        const SELF_ADD_DISCOVERABLE = 1 << 36;
+        /// Change anything in the primary guild directory.
+        const MANAGE_GUILD_DIRECTORY = 1 << 37;
+        /// Send confetti, screenshake and random user mentions (`@someone`).
+        const POGGERS = 1 << 38;
+        /// Use achievements and cheers.
+        const USE_ACHIEVEMENTS = 1 << 39;
+        const INITIATE_INTERACTIONS = 1 << 40;
+        const RESPOND_TO_INTERACTIONS = 1 << 41;
+        const SEND_BACKDATED_EVENTS = 1 << 42;
+        /// Accept mass invites.
+        const USE_MASS_INVITES = 1 << 43;
+        /// Accept user-specific invites and DM requests.
+        const ACCEPT_INVITES = 1 << 44;
+        const SELF_EDIT_FLAGS = 1 << 45;
+        /// Set the flags of other users.
+        const EDIT_FLAGS = 1 << 46;
+        /// Manage the groups of other users.
+        const MANAGE_GROUPS = 1 << 47;
+        const VIEW_SERVER_STATS = 1 << 48;
+        /// Resend verification emails, `/auth/verify/resend`.
+        const RESEND_VERIFICATION_EMAIL = 1 << 49;
+        /// Create registration tokens, `/auth/generate-registration-tokens`.
+        const CREATE_REGISTRATION_TOKENS = 1 << 50;
+    }
+}
+
+bitfield!(Rights, "rights as a string or integer");
+
+impl Rights {
+    /// Whether every right in `rights` is granted, always true for operators.
+    pub fn has(self, rights: Rights) -> bool {
+        self.contains(Self::OPERATOR) || self.contains(rights)
+    }
+
+    /// Whether any right in `rights` is granted, always true for operators.
+    pub fn any(self, rights: Rights) -> bool {
+        self.contains(Self::OPERATOR) || self.intersects(rights)
+    }
+}
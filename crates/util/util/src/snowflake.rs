@@ -0,0 +1,240 @@
+use std::{
+    fmt::{self, Display},
+    num::ParseIntError,
+    str::FromStr,
+    sync::{Mutex, OnceLock},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use chrono::{DateTime, Utc};
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+
+/// Discord epoch (2015-01-01T00:00:00.000Z) in milliseconds.
+pub const DISCORD_EPOCH: u64 = 1_420_070_400_000;
+
+const WORKER_SHIFT: u64 = 17;
+const PROCESS_SHIFT: u64 = 12;
+const TIMESTAMP_SHIFT: u64 = 22;
+const ID_MASK: u64 = 0x1f;
+const INCREMENT_MASK: u64 = 0xfff;
+
+static GENERATOR: OnceLock<SnowflakeGenerator> = OnceLock::new();
+
+/// A Twitter-like snowflake, laid out as
+///
+/// ```text
+/// 64                                          22     17     12          0
+///  000000111011000111100001101001000101000000  00001  00000  000000000000
+///       number of ms since the epoch           worker  pid    increment
+/// ```
+///
+/// Serialised as a decimal string since the value does not fit in a JSON
+/// number without losing precision.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Snowflake(pub u64);
+
+impl Snowflake {
+    /// Generate a new ID using the installed [`SnowflakeGenerator`].
+    pub fn generate() -> Self {
+        generator().generate()
+    }
+
+    /// Milliseconds since the epoch of the installed generator.
+    pub fn timestamp_millis(&self) -> u64 {
+        (self.0 >> TIMESTAMP_SHIFT) + generator().epoch
+    }
+
+    /// The time this ID was created at.
+    pub fn timestamp(&self) -> DateTime<Utc> {
+        DateTime::from_timestamp_millis(self.timestamp_millis() as i64).unwrap_or_default()
+    }
+
+    pub fn worker_id(&self) -> u64 {
+        (self.0 >> WORKER_SHIFT) & ID_MASK
+    }
+
+    pub fn process_id(&self) -> u64 {
+        (self.0 >> PROCESS_SHIFT) & ID_MASK
+    }
+
+    pub fn increment(&self) -> u64 {
+        self.0 & INCREMENT_MASK
+    }
+}
+
+impl From<u64> for Snowflake {
+    fn from(value: u64) -> Self {
+        Self(value)
+    }
+}
+
+impl From<Snowflake> for u64 {
+    fn from(value: Snowflake) -> Self {
+        value.0
+    }
+}
+
+impl Display for Snowflake {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl FromStr for Snowflake {
+    type Err = ParseIntError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.parse().map(Self)
+    }
+}
+
+impl Serialize for Snowflake {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Snowflake {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct Visitor;
+
+        impl de::Visitor<'_> for Visitor {
+            type Value = Snowflake;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("a snowflake as a string or integer")
+            }
+
+            fn visit_u64<E: de::Error>(self, v: u64) -> Result<Snowflake, E> {
+                Ok(Snowflake(v))
+            }
+
+            fn visit_i64<E: de::Error>(self, v: i64) -> Result<Snowflake, E> {
+                u64::try_from(v)
+                    .map(Snowflake)
+                    .map_err(|_| E::invalid_value(de::Unexpected::Signed(v), &self))
+            }
+
+            fn visit_str<E: de::Error>(self, v: &str) -> Result<Snowflake, E> {
+                v.parse()
+                    .map_err(|_| E::invalid_value(de::Unexpected::Str(v), &self))
+            }
+        }
+
+        deserializer.deserialize_any(Visitor)
+    }
+}
+
+/// Mints [`Snowflake`]s for one worker process.
+#[derive(Debug)]
+pub struct SnowflakeGenerator {
+    epoch: u64,
+    worker_id: u64,
+    process_id: u64,
+    /// Timestamp of the last ID handed out and the increment used with it.
+    state: Mutex<(u64, u64)>,
+}
+
+impl Default for SnowflakeGenerator {
+    /// Discord epoch, worker 0 and a process ID derived from the PID, like
+    /// the TypeScript server.
+    fn default() -> Self {
+        Self::new(DISCORD_EPOCH, 0, u64::from(std::process::id()) % 31)
+    }
+}
+
+impl SnowflakeGenerator {
+    /// Worker and process IDs are limited to 5 bits each.
+    pub fn new(epoch: u64, worker_id: u64, process_id: u64) -> Self {
+        Self {
+            epoch,
+            worker_id: worker_id & ID_MASK,
+            process_id: process_id & ID_MASK,
+            state: Mutex::new((0, 0)),
+        }
+    }
+
+    /// Use this generator for [`Snowflake::generate`]. Fails if IDs were
+    /// already generated or another generator was installed first.
+    pub fn install(self) -> Result<(), Self> {
+        GENERATOR.set(self)
+    }
+
+    /// Generate an ID greater than every ID this generator returned before,
+    /// even if the clock goes backwards or the increment runs out.
+    pub fn generate(&self) -> Snowflake {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_millis() as u64)
+            .unwrap_or_default();
+        self.generate_at(now)
+    }
+
+    /// [`generate`](Self::generate) as if the clock read `unix_millis`,
+    /// milliseconds since the Unix epoch.
+    pub fn generate_at(&self, unix_millis: u64) -> Snowflake {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        let (last, increment) = *state;
+        let now = unix_millis.saturating_sub(self.epoch);
+
+        *state = if now > last {
+            (now, 0)
+        } else if increment < INCREMENT_MASK {
+            (last, increment + 1)
+        } else {
+            // Borrow the next millisecond rather than wrapping around.
+            (last + 1, 0)
+        };
+
+        let (time, increment) = *state;
+        Snowflake(
+            time << TIMESTAMP_SHIFT
+                | self.worker_id << WORKER_SHIFT
+                | self.process_id << PROCESS_SHIFT
+                | increment,
+        )
+    }
+}
+
+fn generator() -> &'static SnowflakeGenerator {
+    GENERATOR.get_or_init(SnowflakeGenerator::default)
+}
+
+#[cfg(feature = "sqlx")]
+mod sqlx_impls {
+    use super::Snowflake;
+    use sqlx::{
+        any::{Any, AnyTypeInfo},
+        database::Database,
+        decode::Decode,
+        encode::{Encode, IsNull},
+        error::BoxDynError,
+        types::Type,
+    };
+
+    /// Stored as text like every other ID column.
+    impl Type<Any> for Snowflake {
+        fn type_info() -> AnyTypeInfo {
+            <String as Type<Any>>::type_info()
+        }
+
+        fn compatible(ty: &AnyTypeInfo) -> bool {
+            <String as Type<Any>>::compatible(ty)
+        }
+    }
+
+    impl<'q> Encode<'q, Any> for Snowflake {
+        fn encode_by_ref(
+            &self,
+            buf: &mut <Any as Database>::ArgumentBuffer<'q>,
+        ) -> Result<IsNull, BoxDynError> {
+            <String as Encode<'q, Any>>::encode_by_ref(&self.to_string(), buf)
+        }
+    }
+
+    impl<'r> Decode<'r, Any> for Snowflake {
+        fn decode(value: <Any as Database>::ValueRef<'r>) -> Result<Self, BoxDynError> {
+            Ok(<&str as Decode<'r, Any>>::decode(value)?.parse()?)
+        }
+    }
+}
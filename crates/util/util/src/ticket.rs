@@ -0,0 +1,58 @@
+//! Short-lived tickets that carry the state of a login or registration step
+//! between two requests, signed with the JWT secret.
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use anyhow::{anyhow, Result};
+use config::Config;
+use jsonwebtoken::{decode, encode, Algorithm, DecodingKey, EncodingKey, Header, Validation};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+/// Seconds a client has to use a ticket.
+const TICKET_LIFETIME: i64 = 5 * 60;
+
+#[derive(Serialize, Deserialize)]
+struct TicketClaims<S> {
+    user_id: String,
+    state: S,
+    exp: i64,
+}
+
+/// Sign a ticket for `user_id` holding `state`.
+pub(crate) fn sign_ticket<S: Serialize>(
+    config: &Config,
+    user_id: &str,
+    state: S,
+) -> Result<String> {
+    let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs() as i64;
+    let claims = TicketClaims {
+        user_id: user_id.to_string(),
+        state,
+        exp: now + TICKET_LIFETIME,
+    };
+    Ok(encode(
+        &Header::new(Algorithm::HS256),
+        &claims,
+        &EncodingKey::from_secret(config.security.jwt_secret.as_bytes()),
+    )?)
+}
+
+/// The state in `ticket`, if it was issued to `user_id` and has not expired.
+pub(crate) fn open_ticket<S: DeserializeOwned>(
+    config: &Config,
+    user_id: &str,
+    ticket: &str,
+) -> Result<S> {
+    let mut validation = Validation::new(Algorithm::HS256);
+    validation.leeway = 0;
+    let claims: TicketClaims<S> = decode(
+        ticket,
+        &DecodingKey::from_secret(config.security.jwt_secret.as_bytes()),
+        &validation,
+    )?
+    .claims;
+    if claims.user_id != user_id {
+        return Err(anyhow!("ticket was issued to another user"));
+    }
+    Ok(claims.state)
+}
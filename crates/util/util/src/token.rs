@@ -0,0 +1,135 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use jsonwebtoken::{
+    decode, encode, errors::Error, Algorithm, DecodingKey, EncodingKey, Header, Validation,
+};
+use serde::{Deserialize, Serialize};
+
+/// Claims of a user token, as issued by the TypeScript server.
+///
+/// Tokens carry no expiry. They are revoked by moving the user's
+/// `valid_tokens_since` forward, or by deleting their login session.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TokenClaims {
+    /// ID of the user the token belongs to.
+    pub id: String,
+    /// Seconds since the Unix epoch at which the token was issued.
+    pub iat: i64,
+    /// Login session the token was issued for. Tokens of the TypeScript
+    /// server have none.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sid: Option<String>,
+}
+
+fn now() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or_default()
+}
+
+/// Sign a new HS256 token for the user `id`, belonging to the login session
+/// `session_id`.
+pub fn generate_token(id: &str, session_id: Option<&str>, secret: &str) -> Result<String, Error> {
+    let iat = now();
+    let claims = TokenClaims {
+        id: id.to_string(),
+        iat,
+        sid: session_id.map(str::to_string),
+    };
+    encode(
+        &Header::new(Algorithm::HS256),
+        &claims,
+        &EncodingKey::from_secret(secret.as_bytes()),
+    )
+}
+
+/// Verify the signature of `token` and return its claims.
+pub fn decode_token(token: &str, secret: &str) -> Result<TokenClaims, Error> {
+    let mut validation = Validation::new(Algorithm::HS256);
+    validation.required_spec_claims.clear();
+    validation.validate_exp = false;
+    decode(
+        token,
+        &DecodingKey::from_secret(secret.as_bytes()),
+        &validation,
+    )
+    .map(|data| data.claims)
+}
+
+/// What a token sent by email may be used for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EmailTokenPurpose {
+    /// Confirm the email address of an account.
+    Verify,
+    /// Choose a new password.
+    Reset,
+}
+
+impl EmailTokenPurpose {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Verify => "verify",
+            Self::Reset => "reset",
+        }
+    }
+}
+
+/// Claims of a token sent by email.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EmailTokenClaims {
+    /// ID of the user the token belongs to.
+    pub id: String,
+    /// Address the token was sent to.
+    pub email: String,
+    /// The purpose doubles as the audience, which [`decode_token`] rejects,
+    /// so these tokens never authenticate requests.
+    pub aud: EmailTokenPurpose,
+    /// Seconds since the Unix epoch at which the token was issued.
+    pub iat: i64,
+    /// Seconds since the Unix epoch after which the token is rejected.
+    pub exp: i64,
+}
+
+/// Sign a token for `purpose` sent to `email`, valid for `lifetime` seconds.
+pub fn generate_email_token(
+    id: &str,
+    email: &str,
+    purpose: EmailTokenPurpose,
+    lifetime: i64,
+    secret: &str,
+) -> Result<String, Error> {
+    let iat = now();
+    let claims = EmailTokenClaims {
+        id: id.to_string(),
+        email: email.to_string(),
+        aud: purpose,
+        iat,
+        exp: iat + lifetime,
+    };
+    encode(
+        &Header::new(Algorithm::HS256),
+        &claims,
+        &EncodingKey::from_secret(secret.as_bytes()),
+    )
+}
+
+/// Verify the signature and expiry of an emailed `token` issued for
+/// `purpose` and return its claims.
+pub fn decode_email_token(
+    token: &str,
+    purpose: EmailTokenPurpose,
+    secret: &str,
+) -> Result<EmailTokenClaims, Error> {
+    let mut validation = Validation::new(Algorithm::HS256);
+    validation.leeway = 0;
+    validation.set_audience(&[purpose.as_str()]);
+    validation.set_required_spec_claims(&["exp", "aud"]);
+    decode(
+        token,
+        &DecodingKey::from_secret(secret.as_bytes()),
+        &validation,
+    )
+    .map(|data| data.claims)
+}
@@ -0,0 +1,115 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use anyhow::Result;
+use config::Config;
+use data_encoding::BASE32_NOPAD;
+use hmac::{Hmac, Mac};
+use serde::{de::IgnoredAny, Deserialize, Serialize};
+use sha1::Sha1;
+
+use crate::ticket::{open_ticket, sign_ticket};
+
+/// Length of a time step in seconds.
+const STEP: u64 = 30;
+
+/// Steps of clock drift [`Totp::check`] tolerates in either direction.
+pub const WINDOW: u64 = 1;
+
+/// Time-based one-time passwords (RFC 6238) as produced by authenticator
+/// apps: HMAC-SHA1, six digits and 30 second steps.
+pub struct Totp;
+
+impl Totp {
+    /// The code for `secret` during the step `counter`, or `None` if the
+    /// secret is not valid base32.
+    pub fn code(secret: &str, counter: u64) -> Option<String> {
+        let key = decode_secret(secret)?;
+        let mut mac = Hmac::<Sha1>::new_from_slice(&key).ok()?;
+        mac.update(&counter.to_be_bytes());
+        let hash = mac.finalize().into_bytes();
+
+        // Dynamic truncation, RFC 4226 section 5.3.
+        let offset = (hash[hash.len() - 1] & 0xf) as usize;
+        let binary = u32::from_be_bytes([
+            hash[offset] & 0x7f,
+            hash[offset + 1],
+            hash[offset + 2],
+            hash[offset + 3],
+        ]);
+        Some(format!("{:06}", binary % 1_000_000))
+    }
+
+    /// Check `code` against `secret` at `time` seconds since the Unix epoch,
+    /// accepting codes up to `window` steps early or late.
+    ///
+    /// Returns the step of the matching code. A code must not be accepted
+    /// twice, so callers keep the last step they accepted and reject any
+    /// code at or before it (RFC 6238 section 5.2).
+    pub fn verify(secret: &str, code: &str, time: u64, window: u64) -> Option<u64> {
+        let code = code.trim();
+        if code.len() != 6 || !code.bytes().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+        let current = time / STEP;
+        let first = current.saturating_sub(window);
+        (first..=current + window)
+            .find(|&counter| Self::code(secret, counter).is_some_and(|expected| expected == code))
+    }
+
+    /// Check `code` against `secret` at the current time, returning its step.
+    pub fn check(secret: &str, code: &str) -> Option<u64> {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or_default();
+        Self::verify(secret, code, now, WINDOW)
+    }
+
+    /// Ticket a login of `user_id` presents along with its code, valid for a
+    /// few minutes.
+    pub fn ticket(config: &Config, user_id: &str) -> Result<String> {
+        sign_ticket(config, user_id, ())
+    }
+
+    /// Whether `ticket` was issued to `user_id` and has not expired. Tickets
+    /// of a security-key login are accepted as well.
+    pub fn check_ticket(config: &Config, user_id: &str, ticket: &str) -> bool {
+        open_ticket::<IgnoredAny>(config, user_id, ticket).is_ok()
+    }
+
+    /// Nonce handed to `user_id` once it entered its password, letting it
+    /// list its backup codes, or replace them if `regenerate` is set, for a
+    /// few minutes.
+    pub fn backup_codes_nonce(config: &Config, user_id: &str, regenerate: bool) -> Result<String> {
+        sign_ticket(config, user_id, BackupCodesNonce { regenerate })
+    }
+
+    /// Whether `nonce` was handed to `user_id` for the same `regenerate`
+    /// and has not expired.
+    pub fn check_backup_codes_nonce(
+        config: &Config,
+        user_id: &str,
+        nonce: &str,
+        regenerate: bool,
+    ) -> bool {
+        open_ticket::<BackupCodesNonce>(config, user_id, nonce)
+            .is_ok_and(|nonce| nonce.regenerate == regenerate)
+    }
+}
+
+/// State of a [`Totp::backup_codes_nonce`].
+#[derive(Serialize, Deserialize)]
+struct BackupCodesNonce {
+    regenerate: bool,
+}
+
+/// Decode a base32 secret, ignoring case, spaces and padding.
+fn decode_secret(secret: &str) -> Option<Vec<u8>> {
+    let normalized: String = secret
+        .chars()
+        .filter(|c| !c.is_whitespace() && *c != '=')
+        .map(|c| c.to_ascii_uppercase())
+        .collect();
+    let key = BASE32_NOPAD.decode(normalized.as_bytes()).ok()?;
+    (!key.is_empty()).then_some(key)
+}
@@ -1,10 +1,36 @@
-use anyhow::Result;
+use anyhow::{anyhow, Result};
+use config::Config;
+use data_encoding::BASE64URL_NOPAD;
+use serde_json::Value;
 use url::Url;
 use webauthn_rs::prelude::*;
 
+use crate::ticket::{open_ticket, sign_ticket};
+
+/// Origin used when `general.frontPage` is not configured.
+const DEFAULT_ORIGIN: &str = "http://localhost:3001";
+
 /// WebAuthn helper functions.
 pub struct WebAuthn;
 
+/// A security key that completed registration.
+#[derive(Debug, Clone)]
+pub struct RegisteredKey {
+    /// base64url credential ID, as stored by the TypeScript server.
+    pub key_id: String,
+    /// Serialised credential to keep for later logins.
+    pub credential: String,
+}
+
+/// A security key that completed a login.
+#[derive(Debug, Clone)]
+pub struct UsedKey {
+    pub key_id: String,
+    /// The stored credential with its signature counter moved forward.
+    pub credential: String,
+    pub counter: u32,
+}
+
 impl WebAuthn {
     /// Initialise a WebAuthn instance.
     pub fn init(rp_id: &str, origin: &str, rp_name: &str) -> Result<Webauthn> {
@@ -13,4 +39,126 @@ impl WebAuthn {
             .rp_name(rp_name)
             .build()?)
     }
+
+    /// The relying party of this instance. The client is served from
+    /// `general.frontPage`, so that is the origin and its host the
+    /// relying-party ID.
+    pub fn from_config(config: &Config) -> Result<Webauthn> {
+        let origin = config
+            .general
+            .front_page
+            .as_deref()
+            .unwrap_or(DEFAULT_ORIGIN);
+        let rp_id = Url::parse(origin)?
+            .host_str()
+            .ok_or_else(|| anyhow!("general.frontPage {origin} has no host"))?
+            .to_string();
+        Self::init(&rp_id, origin, &config.general.instance_name)
+    }
+
+    /// Begin registering a security key for `user_id`.
+    ///
+    /// Returns the JSON options for `navigator.credentials.create()` and the
+    /// ticket to present with the new credential.
+    pub fn start_registration(
+        config: &Config,
+        user_id: &str,
+        username: &str,
+        credentials: &[String],
+    ) -> Result<(Value, String)> {
+        let exclude = parse_keys(credentials)
+            .iter()
+            .map(|key| key.cred_id().clone())
+            .collect();
+        let (challenge, state) = Self::from_config(config)?.start_securitykey_registration(
+            user_handle(user_id)?,
+            username,
+            username,
+            Some(exclude),
+            None,
+            None,
+        )?;
+        let ticket = sign_ticket(config, user_id, state)?;
+        Ok((serde_json::to_value(challenge)?, ticket))
+    }
+
+    /// Verify the JSON `credential` created for the registration `ticket`.
+    pub fn finish_registration(
+        config: &Config,
+        user_id: &str,
+        ticket: &str,
+        credential: &str,
+    ) -> Result<RegisteredKey> {
+        let state: SecurityKeyRegistration = open_ticket(config, user_id, ticket)?;
+        let credential: RegisterPublicKeyCredential = serde_json::from_str(credential)?;
+        let key =
+            Self::from_config(config)?.finish_securitykey_registration(&credential, &state)?;
+        Ok(RegisteredKey {
+            key_id: key_id(&key),
+            credential: serde_json::to_string(&key)?,
+        })
+    }
+
+    /// Begin a login of `user_id` with one of its stored `credentials`.
+    ///
+    /// Returns the JSON options for `navigator.credentials.get()` and the
+    /// ticket to present with the assertion, or `None` if none of the
+    /// credentials is usable, such as keys registered by the TypeScript
+    /// server.
+    pub fn start_authentication(
+        config: &Config,
+        user_id: &str,
+        credentials: &[String],
+    ) -> Result<Option<(Value, String)>> {
+        let keys = parse_keys(credentials);
+        if keys.is_empty() {
+            return Ok(None);
+        }
+        let (challenge, state) =
+            Self::from_config(config)?.start_securitykey_authentication(&keys)?;
+        let ticket = sign_ticket(config, user_id, state)?;
+        Ok(Some((serde_json::to_value(challenge)?, ticket)))
+    }
+
+    /// Verify the JSON `assertion` answering the login `ticket`, returning
+    /// the key it was made with out of `credentials`.
+    pub fn finish_authentication(
+        config: &Config,
+        user_id: &str,
+        ticket: &str,
+        assertion: &str,
+        credentials: &[String],
+    ) -> Result<UsedKey> {
+        let state: SecurityKeyAuthentication = open_ticket(config, user_id, ticket)?;
+        let assertion: PublicKeyCredential = serde_json::from_str(assertion)?;
+        let result =
+            Self::from_config(config)?.finish_securitykey_authentication(&assertion, &state)?;
+        let mut key = parse_keys(credentials)
+            .into_iter()
+            .find(|key| key.cred_id() == result.cred_id())
+            .ok_or_else(|| anyhow!("unknown security key"))?;
+        key.update_credential(&result);
+        Ok(UsedKey {
+            key_id: key_id(&key),
+            credential: serde_json::to_string(&key)?,
+            counter: result.counter(),
+        })
+    }
+}
+
+/// Stored credentials that can be used, skipping ones in other formats.
+fn parse_keys(credentials: &[String]) -> Vec<SecurityKey> {
+    credentials
+        .iter()
+        .filter_map(|credential| serde_json::from_str(credential).ok())
+        .collect()
+}
+
+fn key_id(key: &SecurityKey) -> String {
+    BASE64URL_NOPAD.encode(key.cred_id())
+}
+
+/// The WebAuthn user handle of a user, derived from its snowflake.
+fn user_handle(user_id: &str) -> Result<Uuid> {
+    Ok(Uuid::from_u64_pair(0, user_id.parse()?))
 }
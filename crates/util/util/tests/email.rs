@@ -0,0 +1,318 @@
+//! HTTP email providers against a local stub of their API.
+
+use config::{Config, EmailConfiguration};
+use data_encoding::BASE64;
+use serde_json::{json, Value};
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::TcpListener,
+    task::JoinHandle,
+};
+use util::{Email, Mail, Template};
+
+/// A request as received by [`stub`].
+struct Request {
+    /// Request line and headers, with header names lowercased.
+    head: String,
+    body: String,
+}
+
+impl Request {
+    fn header(&self, name: &str) -> Option<&str> {
+        self.head.lines().find_map(|line| {
+            let (key, value) = line.split_once(':')?;
+            (key == name).then(|| value.trim())
+        })
+    }
+
+    fn json(&self) -> Value {
+        serde_json::from_str(&self.body).unwrap()
+    }
+}
+
+/// Answer one request on a local port with `status` and `body`. Returns the
+/// base URL to use as endpoint and the request that was received.
+async fn stub(status: u16, body: &'static str) -> (String, JoinHandle<Request>) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let endpoint = format!("http://{}", listener.local_addr().unwrap());
+    let handle = tokio::spawn(async move {
+        let (mut stream, _) = listener.accept().await.unwrap();
+        let mut received = Vec::new();
+        let mut buffer = [0u8; 4096];
+        let (head, body_start) = loop {
+            let read = stream.read(&mut buffer).await.unwrap();
+            received.extend_from_slice(&buffer[..read]);
+            if let Some(end) = received.windows(4).position(|w| w == b"\r\n\r\n") {
+                break (
+                    String::from_utf8_lossy(&received[..end]).to_string(),
+                    end + 4,
+                );
+            }
+        };
+        let head: String = head
+            .lines()
+            .map(|line| match line.split_once(':') {
+                Some((key, value)) => format!("{}:{value}\n", key.to_ascii_lowercase()),
+                None => format!("{line}\n"),
+            })
+            .collect();
+        let length: usize = head
+            .lines()
+            .find_map(|line| line.strip_prefix("content-length:"))
+            .map_or(0, |value| value.trim().parse().unwrap());
+        while received.len() < body_start + length {
+            let read = stream.read(&mut buffer).await.unwrap();
+            received.extend_from_slice(&buffer[..read]);
+        }
+        let request_body = String::from_utf8_lossy(&received[body_start..]).to_string();
+
+        let response = format!(
+            "HTTP/1.1 {status} Stub\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{body}",
+            body.len()
+        );
+        stream.write_all(response.as_bytes()).await.unwrap();
+        Request {
+            head,
+            body: request_body,
+        }
+    });
+    (endpoint, handle)
+}
+
+fn mail() -> Mail {
+    Mail {
+        from: "noreply@spacebar.example".into(),
+        to: "user@example.com".into(),
+        subject: "Hello & welcome".into(),
+        html: "<p>Hi</p>".into(),
+    }
+}
+
+fn basic(user: &str, password: &str) -> String {
+    format!(
+        "Basic {}",
+        BASE64.encode(format!("{user}:{password}").as_bytes())
+    )
+}
+
+fn mailgun(endpoint: Option<String>) -> EmailConfiguration {
+    let mut cfg = EmailConfiguration {
+        provider: Some("mailgun".into()),
+        ..Default::default()
+    };
+    cfg.mailgun.api_key = Some("key-1".into());
+    cfg.mailgun.domain = Some("mg.example".into());
+    cfg.mailgun.endpoint = endpoint;
+    cfg
+}
+
+fn mailjet(endpoint: Option<String>) -> EmailConfiguration {
+    let mut cfg = EmailConfiguration {
+        provider: Some("mailjet".into()),
+        ..Default::default()
+    };
+    cfg.mailjet.api_key = Some("public".into());
+    cfg.mailjet.api_secret = Some("private".into());
+    cfg.mailjet.endpoint = endpoint;
+    cfg
+}
+
+fn sendgrid(endpoint: Option<String>) -> EmailConfiguration {
+    let mut cfg = EmailConfiguration {
+        provider: Some("sendgrid".into()),
+        ..Default::default()
+    };
+    cfg.sendgrid.api_key = Some("SG.key".into());
+    cfg.sendgrid.endpoint = endpoint;
+    cfg
+}
+
+#[tokio::test]
+async fn mailgun_sends_a_form() {
+    let (endpoint, request) = stub(200, r#"{"id":"1","message":"Queued"}"#).await;
+    // A trailing slash on the endpoint is tolerated.
+    let email = Email::init(&mailgun(Some(format!("{endpoint}/")))).unwrap();
+    email.send(&mail()).await.unwrap();
+
+    let request = request.await.unwrap();
+    assert!(request
+        .head
+        .starts_with("POST /v3/mg.example/messages HTTP/1.1"));
+    assert_eq!(
+        request.header("authorization"),
+        Some(&*basic("api", "key-1"))
+    );
+    assert_eq!(
+        request.header("content-type"),
+        Some("application/x-www-form-urlencoded")
+    );
+    let form: Vec<(String, String)> = url::form_urlencoded::parse(request.body.as_bytes())
+        .into_owned()
+        .collect();
+    for (name, value) in [
+        ("from", "noreply@spacebar.example"),
+        ("to", "user@example.com"),
+        ("subject", "Hello & welcome"),
+        ("html", "<p>Hi</p>"),
+    ] {
+        assert!(form.contains(&(name.into(), value.into())), "{name}");
+    }
+}
+
+#[tokio::test]
+async fn mailgun_errors_carry_the_reason() {
+    let (endpoint, request) = stub(401, "Forbidden").await;
+    let error = Email::init(&mailgun(Some(endpoint)))
+        .unwrap()
+        .send(&mail())
+        .await
+        .unwrap_err()
+        .to_string();
+    request.await.unwrap();
+    assert!(error.contains("Mailgun"), "{error}");
+    assert!(error.contains("401"), "{error}");
+    assert!(error.contains("Forbidden"), "{error}");
+}
+
+#[tokio::test]
+async fn mailjet_sends_json() {
+    let (endpoint, request) = stub(200, r#"{"Messages":[{"Status":"success"}]}"#).await;
+    Email::init(&mailjet(Some(endpoint)))
+        .unwrap()
+        .send(&mail())
+        .await
+        .unwrap();
+
+    let request = request.await.unwrap();
+    assert!(request.head.starts_with("POST /v3.1/send HTTP/1.1"));
+    assert_eq!(
+        request.header("authorization"),
+        Some(&*basic("public", "private"))
+    );
+    assert_eq!(
+        request.json(),
+        json!({
+            "Messages": [{
+                "From": { "Email": "noreply@spacebar.example" },
+                "To": [{ "Email": "user@example.com" }],
+                "Subject": "Hello & welcome",
+                "HTMLPart": "<p>Hi</p>",
+            }],
+        })
+    );
+}
+
+#[tokio::test]
+async fn mailjet_errors_carry_the_reason() {
+    let body = r#"{"ErrorMessage":"API key authentication/authorization failure"}"#;
+    let (endpoint, request) = stub(401, body).await;
+    let error = Email::init(&mailjet(Some(endpoint)))
+        .unwrap()
+        .send(&mail())
+        .await
+        .unwrap_err()
+        .to_string();
+    request.await.unwrap();
+    assert!(error.contains("Mailjet"), "{error}");
+    assert!(error.contains("401"), "{error}");
+    assert!(error.contains("authorization failure"), "{error}");
+}
+
+#[tokio::test]
+async fn sendgrid_sends_json() {
+    let (endpoint, request) = stub(202, "").await;
+    Email::init(&sendgrid(Some(endpoint)))
+        .unwrap()
+        .send(&mail())
+        .await
+        .unwrap();
+
+    let request = request.await.unwrap();
+    assert!(request.head.starts_with("POST /v3/mail/send HTTP/1.1"));
+    assert_eq!(request.header("authorization"), Some("Bearer SG.key"));
+    assert_eq!(
+        request.json(),
+        json!({
+            "personalizations": [{ "to": [{ "email": "user@example.com" }] }],
+            "from": { "email": "noreply@spacebar.example" },
+            "subject": "Hello & welcome",
+            "content": [{ "type": "text/html", "value": "<p>Hi</p>" }],
+        })
+    );
+}
+
+#[tokio::test]
+async fn sendgrid_errors_carry_the_reason() {
+    let body =
+        r#"{"errors":[{"message":"The from address does not match a verified Sender Identity"}]}"#;
+    let (endpoint, request) = stub(403, body).await;
+    let error = Email::init(&sendgrid(Some(endpoint)))
+        .unwrap()
+        .send(&mail())
+        .await
+        .unwrap_err()
+        .to_string();
+    request.await.unwrap();
+    assert!(error.contains("SendGrid"), "{error}");
+    assert!(error.contains("403"), "{error}");
+    assert!(error.contains("verified Sender Identity"), "{error}");
+}
+
+#[tokio::test]
+async fn unreachable_endpoints_fail() {
+    // Nothing listens on the port once the listener is dropped.
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let endpoint = format!("http://{}", listener.local_addr().unwrap());
+    drop(listener);
+    for cfg in [
+        mailgun(Some(endpoint.clone())),
+        mailjet(Some(endpoint.clone())),
+        sendgrid(Some(endpoint.clone())),
+    ] {
+        assert!(Email::init(&cfg).unwrap().send(&mail()).await.is_err());
+    }
+}
+
+#[test]
+fn missing_credentials_are_rejected() {
+    let mut cfg = mailgun(None);
+    cfg.mailgun.domain = None;
+    assert!(Email::init(&cfg).is_err());
+    let mut cfg = mailjet(None);
+    cfg.mailjet.api_secret = None;
+    assert!(Email::init(&cfg).is_err());
+    let mut cfg = sendgrid(None);
+    cfg.sendgrid.api_key = None;
+    assert!(Email::init(&cfg).is_err());
+}
+
+#[tokio::test]
+async fn templates_follow_configuration_reloads() {
+    let (endpoint, request) = stub(200, r#"{"id":"1","message":"Queued"}"#).await;
+    let mut config = Config {
+        email: mailgun(Some(endpoint)),
+        ..Default::default()
+    };
+    Config::set(config.clone());
+    tokio::spawn(Email::follow(Config::handle()));
+    Email::send_template(&config, "user@example.com", Template::VerifyEmail, &[])
+        .await
+        .unwrap();
+    let request = request.await.unwrap();
+    assert!(request
+        .head
+        .starts_with("POST /v3/mg.example/messages HTTP/1.1"));
+
+    // The Mailgun stub is gone, so only the new transport can succeed.
+    let (endpoint, request) = stub(202, "").await;
+    config.email = sendgrid(Some(endpoint));
+    Config::set(config.clone());
+    for _ in 0..10 {
+        tokio::task::yield_now().await;
+    }
+    Email::send_template(&config, "user@example.com", Template::VerifyEmail, &[])
+        .await
+        .unwrap();
+    let request = request.await.unwrap();
+    assert!(request.head.starts_with("POST /v3/mail/send HTTP/1.1"));
+}
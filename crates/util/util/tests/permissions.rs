@@ -0,0 +1,220 @@
+//! Permission resolution and the string form of bitfields.
+
+use serde_json::json;
+use util::{PermissionOverwrite, Permissions, OVERWRITE_MEMBER, OVERWRITE_ROLE};
+
+const GUILD: &str = "100";
+const USER: &str = "200";
+const ROLE: &str = "300";
+const OTHER_ROLE: &str = "301";
+
+fn overwrite(id: &str, kind: i32, allow: Permissions, deny: Permissions) -> PermissionOverwrite {
+    PermissionOverwrite {
+        allow,
+        deny,
+        id: id.into(),
+        kind,
+    }
+}
+
+#[test]
+fn owners_and_administrators_get_everything() {
+    let everyone = Permissions::VIEW_CHANNEL;
+    assert_eq!(Permissions::base(true, everyone, []), Permissions::all());
+    assert_eq!(
+        Permissions::base(false, everyone, [Permissions::ADMINISTRATOR]),
+        Permissions::all()
+    );
+    assert_eq!(
+        Permissions::base(false, everyone, [Permissions::SEND_MESSAGES]),
+        Permissions::VIEW_CHANNEL | Permissions::SEND_MESSAGES
+    );
+
+    // Overwrites do not apply to administrators.
+    let deny_all = [overwrite(
+        USER,
+        OVERWRITE_MEMBER,
+        Permissions::empty(),
+        Permissions::all(),
+    )];
+    let admin = Permissions::base(false, everyone, [Permissions::ADMINISTRATOR]);
+    assert_eq!(
+        admin.overwrite(GUILD, USER, &[ROLE], &deny_all),
+        Permissions::all()
+    );
+    assert!(admin.has(Permissions::MANAGE_GUILD | Permissions::BAN_MEMBERS));
+    assert!(Permissions::ADMINISTRATOR.any(Permissions::MANAGE_ROLES));
+    assert!(!Permissions::VIEW_CHANNEL.has(Permissions::VIEW_CHANNEL | Permissions::SEND_MESSAGES));
+}
+
+#[test]
+fn overwrites_apply_everyone_then_roles_then_member() {
+    let base = Permissions::VIEW_CHANNEL | Permissions::SEND_MESSAGES;
+    let everyone = overwrite(
+        GUILD,
+        OVERWRITE_ROLE,
+        Permissions::empty(),
+        Permissions::VIEW_CHANNEL | Permissions::SEND_MESSAGES,
+    );
+    let role = overwrite(
+        ROLE,
+        OVERWRITE_ROLE,
+        Permissions::VIEW_CHANNEL | Permissions::SEND_MESSAGES,
+        Permissions::empty(),
+    );
+    let other_role = overwrite(
+        OTHER_ROLE,
+        OVERWRITE_ROLE,
+        Permissions::ATTACH_FILES,
+        Permissions::SEND_MESSAGES,
+    );
+    let member = overwrite(
+        USER,
+        OVERWRITE_MEMBER,
+        Permissions::empty(),
+        Permissions::VIEW_CHANNEL,
+    );
+
+    // `@everyone` hides the channel, a role shows it again.
+    let overwrites = [role.clone(), everyone.clone()];
+    assert_eq!(
+        base.overwrite(GUILD, USER, &[] as &[&str], &overwrites),
+        Permissions::empty()
+    );
+    assert_eq!(base.overwrite(GUILD, USER, &[ROLE], &overwrites), base);
+
+    // Role overwrites are combined, and allowing wins over denying.
+    let overwrites = [everyone.clone(), role.clone(), other_role.clone()];
+    assert_eq!(
+        base.overwrite(GUILD, USER, &[ROLE, OTHER_ROLE], &overwrites),
+        base | Permissions::ATTACH_FILES
+    );
+    assert_eq!(
+        base.overwrite(GUILD, USER, &[OTHER_ROLE], &overwrites),
+        Permissions::ATTACH_FILES
+    );
+
+    // The member's own overwrite comes last.
+    let overwrites = [member, other_role, role, everyone];
+    assert_eq!(
+        base.overwrite(GUILD, USER, &[ROLE], &overwrites),
+        Permissions::SEND_MESSAGES
+    );
+    assert_eq!(base.overwrite(GUILD, "201", &[ROLE], &overwrites), base);
+}
+
+#[test]
+fn member_overwrites_match_by_type() {
+    // A role whose ID equals the user's ID is not the member overwrite.
+    let overwrites = [overwrite(
+        USER,
+        OVERWRITE_ROLE,
+        Permissions::empty(),
+        Permissions::VIEW_CHANNEL,
+    )];
+    assert_eq!(
+        Permissions::VIEW_CHANNEL.overwrite(GUILD, USER, &[] as &[&str], &overwrites),
+        Permissions::VIEW_CHANNEL
+    );
+}
+
+#[test]
+fn discord_examples() {
+    // The role object example of Discord's documentation.
+    let role: Permissions = "66321471".parse().unwrap();
+    assert_eq!(
+        role,
+        Permissions::CREATE_INSTANT_INVITE
+            | Permissions::KICK_MEMBERS
+            | Permissions::BAN_MEMBERS
+            | Permissions::ADMINISTRATOR
+            | Permissions::MANAGE_CHANNELS
+            | Permissions::MANAGE_GUILD
+            | Permissions::VIEW_CHANNEL
+            | Permissions::SEND_MESSAGES
+            | Permissions::SEND_TTS_MESSAGES
+            | Permissions::MANAGE_MESSAGES
+            | Permissions::EMBED_LINKS
+            | Permissions::ATTACH_FILES
+            | Permissions::READ_MESSAGE_HISTORY
+            | Permissions::MENTION_EVERYONE
+            | Permissions::CONNECT
+            | Permissions::SPEAK
+            | Permissions::MUTE_MEMBERS
+            | Permissions::DEAFEN_MEMBERS
+            | Permissions::MOVE_MEMBERS
+            | Permissions::USE_VAD
+    );
+    assert_eq!(Permissions::ADMINISTRATOR.bits(), 0x8);
+    assert_eq!(Permissions::MANAGE_GUILD.bits(), 0x20);
+    assert_eq!(Permissions::SEND_POLLS.bits(), 0x2000000000000);
+    assert_eq!(Permissions::USE_EXTERNAL_APPS.bits(), 0x4000000000000);
+    assert_eq!(
+        (Permissions::KICK_MEMBERS | Permissions::BAN_MEMBERS).names(),
+        "KICK_MEMBERS, BAN_MEMBERS"
+    );
+
+    // An overwrite as clients send it.
+    let overwrite: PermissionOverwrite = serde_json::from_value(json!({
+        "id": "41771983423143936",
+        "type": 0,
+        "allow": "1024",
+        "deny": 2048,
+    }))
+    .unwrap();
+    assert_eq!(overwrite.allow, Permissions::VIEW_CHANNEL);
+    assert_eq!(overwrite.deny, Permissions::SEND_MESSAGES);
+    assert_eq!(
+        serde_json::to_value(&overwrite).unwrap(),
+        json!({
+            "id": "41771983423143936",
+            "type": 0,
+            "allow": "1024",
+            "deny": "2048",
+        })
+    );
+}
+
+#[test]
+fn wide_values_round_trip_as_strings() {
+    // Above 2^53 a JSON number loses precision, so values are strings.
+    let permissions = Permissions::USE_EXTERNAL_APPS | Permissions::CREATE_INSTANT_INVITE;
+    assert_eq!(permissions.to_string(), "1125899906842625");
+    assert_eq!(
+        serde_json::to_value(permissions).unwrap(),
+        json!("1125899906842625")
+    );
+
+    // Unknown bits are kept.
+    let wide = Permissions::from_bits_retain(u64::MAX);
+    let string = serde_json::to_string(&wide).unwrap();
+    assert_eq!(string, r#""18446744073709551615""#);
+    assert_eq!(serde_json::from_str::<Permissions>(&string).unwrap(), wide);
+    let odd = (1u64 << 60) | 1;
+    assert_eq!(
+        serde_json::from_value::<Permissions>(json!(odd.to_string()))
+            .unwrap()
+            .bits(),
+        odd
+    );
+    assert_eq!(
+        " 8 ".parse::<Permissions>().unwrap(),
+        Permissions::ADMINISTRATOR
+    );
+}
+
+#[test]
+fn integers_are_accepted_when_deserialising() {
+    assert_eq!(
+        serde_json::from_value::<Permissions>(json!(1024)).unwrap(),
+        Permissions::VIEW_CHANNEL
+    );
+    assert_eq!(
+        serde_json::from_value::<Permissions>(json!(u64::MAX)).unwrap(),
+        Permissions::from_bits_retain(u64::MAX)
+    );
+    assert!(serde_json::from_value::<Permissions>(json!(-1)).is_err());
+    assert!(serde_json::from_value::<Permissions>(json!("-1")).is_err());
+    assert!(serde_json::from_value::<Permissions>(json!("admin")).is_err());
+    assert!(serde_json::from_value::<Permissions>(json!(1.5)).is_err());
+}
@@ -0,0 +1,132 @@
+//! Snowflake layout, ordering and string form.
+
+use serde_json::json;
+use util::{Snowflake, SnowflakeGenerator};
+
+/// 2020-09-13T12:26:40.000Z, an epoch other than Discord's.
+const EPOCH: u64 = 1_600_000_000_000;
+const WORKER: u64 = 3;
+const PROCESS: u64 = 7;
+
+/// Install the generator every test shares, before anything falls back to
+/// the default one.
+fn install() -> SnowflakeGenerator {
+    let _ = SnowflakeGenerator::new(EPOCH, WORKER, PROCESS).install();
+    SnowflakeGenerator::new(EPOCH, WORKER, PROCESS)
+}
+
+fn parts(id: Snowflake) -> (u64, u64, u64, u64) {
+    (
+        id.timestamp_millis(),
+        id.worker_id(),
+        id.process_id(),
+        id.increment(),
+    )
+}
+
+#[test]
+fn fields_are_extracted() {
+    let generator = install();
+    let id = generator.generate_at(EPOCH + 1234);
+    assert_eq!(id.0, 1234 << 22 | WORKER << 17 | PROCESS << 12);
+    assert_eq!(parts(id), (EPOCH + 1234, WORKER, PROCESS, 0));
+    assert_eq!(
+        id.timestamp(),
+        chrono::DateTime::from_timestamp_millis((EPOCH + 1234) as i64).unwrap()
+    );
+
+    // The example of Discord's documentation, 41944705796 ms past the epoch.
+    let id = Snowflake(175928847299117063);
+    assert_eq!(parts(id), (EPOCH + 41944705796, 1, 0, 7));
+
+    let id = Snowflake::generate();
+    assert_eq!((id.worker_id(), id.process_id()), (WORKER, PROCESS));
+    assert!(id.timestamp_millis() > EPOCH);
+}
+
+#[test]
+fn worker_and_process_ids_are_five_bits() {
+    let generator = SnowflakeGenerator::new(EPOCH, 33, 63);
+    let id = generator.generate_at(EPOCH);
+    assert_eq!((id.worker_id(), id.process_id()), (1, 31));
+    assert_eq!(id.0 >> 22, 0);
+}
+
+#[test]
+fn increments_within_a_millisecond() {
+    let generator = install();
+    let first = generator.generate_at(EPOCH + 10);
+    let second = generator.generate_at(EPOCH + 10);
+    let third = generator.generate_at(EPOCH + 11);
+    assert_eq!(parts(first), (EPOCH + 10, WORKER, PROCESS, 0));
+    assert_eq!(parts(second), (EPOCH + 10, WORKER, PROCESS, 1));
+    assert_eq!(parts(third), (EPOCH + 11, WORKER, PROCESS, 0));
+}
+
+#[test]
+fn exhausted_increment_carries_into_the_next_millisecond() {
+    let generator = install();
+    let ids: Vec<Snowflake> = (0..4096)
+        .map(|_| generator.generate_at(EPOCH + 10))
+        .collect();
+    assert!(ids.windows(2).all(|pair| pair[0] < pair[1]));
+    assert_eq!(parts(ids[4095]), (EPOCH + 10, WORKER, PROCESS, 4095));
+
+    let carried = generator.generate_at(EPOCH + 10);
+    assert_eq!(parts(carried), (EPOCH + 11, WORKER, PROCESS, 0));
+    // The borrowed millisecond goes on once the clock reaches it.
+    let next = generator.generate_at(EPOCH + 11);
+    assert_eq!(parts(next), (EPOCH + 11, WORKER, PROCESS, 1));
+    let later = generator.generate_at(EPOCH + 12);
+    assert_eq!(parts(later), (EPOCH + 12, WORKER, PROCESS, 0));
+}
+
+#[test]
+fn clock_going_backwards_keeps_ids_increasing() {
+    let generator = install();
+    let before = generator.generate_at(EPOCH + 5000);
+    let after = generator.generate_at(EPOCH + 1000);
+    assert!(after > before);
+    assert_eq!(parts(after), (EPOCH + 5000, WORKER, PROCESS, 1));
+
+    // A clock before the epoch does not wrap around either.
+    let generator = SnowflakeGenerator::new(EPOCH, WORKER, PROCESS);
+    assert_eq!(generator.generate_at(EPOCH - 1).timestamp_millis(), EPOCH);
+}
+
+#[test]
+fn generated_ids_increase() {
+    install();
+    let ids: Vec<Snowflake> = (0..10_000).map(|_| Snowflake::generate()).collect();
+    assert!(ids.windows(2).all(|pair| pair[0] < pair[1]));
+}
+
+#[test]
+fn serialised_as_strings() {
+    let id = Snowflake(175928847299117063);
+    assert_eq!(id.to_string(), "175928847299117063");
+    assert_eq!("175928847299117063".parse::<Snowflake>().unwrap(), id);
+    assert_eq!(
+        serde_json::to_value(id).unwrap(),
+        json!("175928847299117063")
+    );
+    assert_eq!(
+        serde_json::from_value::<Snowflake>(json!("175928847299117063")).unwrap(),
+        id
+    );
+
+    // Beyond 2^53 a JSON number would lose precision.
+    let wide = Snowflake(u64::MAX);
+    let string = serde_json::to_string(&wide).unwrap();
+    assert_eq!(string, r#""18446744073709551615""#);
+    assert_eq!(serde_json::from_str::<Snowflake>(&string).unwrap(), wide);
+
+    // Integers are accepted too, but not negative or malformed IDs.
+    assert_eq!(
+        serde_json::from_value::<Snowflake>(json!(175928847299117063u64)).unwrap(),
+        id
+    );
+    assert!(serde_json::from_value::<Snowflake>(json!(-1)).is_err());
+    assert!(serde_json::from_value::<Snowflake>(json!("12a")).is_err());
+    assert!("".parse::<Snowflake>().is_err());
+}
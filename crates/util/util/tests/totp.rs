@@ -0,0 +1,110 @@
+//! TOTP codes against the RFC 6238 test vectors, at fixed times.
+
+use config::Config;
+use util::Totp;
+
+/// The RFC 6238 SHA-1 seed, `12345678901234567890`, in base32.
+const SECRET: &str = "GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ";
+
+/// Times from RFC 6238 appendix B with the last six digits of their
+/// eight-digit SHA-1 codes.
+const VECTORS: &[(u64, &str)] = &[
+    (59, "287082"),
+    (1111111109, "081804"),
+    (1111111111, "050471"),
+    (1234567890, "005924"),
+    (2000000000, "279037"),
+    (20000000000, "353130"),
+];
+
+#[test]
+fn rfc_6238_vectors() {
+    for &(time, code) in VECTORS {
+        assert_eq!(
+            Totp::code(SECRET, time / 30).as_deref(),
+            Some(code),
+            "{time}"
+        );
+        assert_eq!(
+            Totp::verify(SECRET, code, time, 0),
+            Some(time / 30),
+            "{time}"
+        );
+    }
+}
+
+#[test]
+fn secrets_ignore_case_spaces_and_padding() {
+    let secret = "gezd gnbv gy3t qojq gezd gnbv gy3t qojq====";
+    assert_eq!(Totp::code(secret, 1).as_deref(), Some("287082"));
+    assert_eq!(Totp::code("not base32!", 1), None);
+    assert_eq!(Totp::code("", 1), None);
+}
+
+#[test]
+fn window_edges() {
+    // 287082 is the code of step 1, from 30 to 59 seconds.
+    let code = "287082";
+    assert_eq!(Totp::verify(SECRET, code, 30, 0), Some(1));
+    assert_eq!(Totp::verify(SECRET, code, 59, 0), Some(1));
+    assert_eq!(Totp::verify(SECRET, code, 29, 0), None);
+    assert_eq!(Totp::verify(SECRET, code, 60, 0), None);
+
+    // One step of drift either way.
+    assert_eq!(Totp::verify(SECRET, code, 0, 1), Some(1));
+    assert_eq!(Totp::verify(SECRET, code, 89, 1), Some(1));
+    assert_eq!(Totp::verify(SECRET, code, 90, 1), None);
+
+    // Two steps late is outside the default window.
+    assert_eq!(Totp::verify(SECRET, code, 90, 2), Some(1));
+}
+
+#[test]
+fn malformed_codes_are_rejected() {
+    assert_eq!(Totp::verify(SECRET, " 287082 ", 59, 0), Some(1));
+    for code in ["", "28708", "2870820", "28708a", "287 82", "+87082"] {
+        assert_eq!(Totp::verify(SECRET, code, 59, 1), None, "{code:?}");
+    }
+}
+
+#[test]
+fn tickets_belong_to_one_user() {
+    let mut config = Config::default();
+    config.security.jwt_secret = "secret".into();
+    let ticket = Totp::ticket(&config, "1").unwrap();
+    assert!(Totp::check_ticket(&config, "1", &ticket));
+    assert!(!Totp::check_ticket(&config, "2", &ticket));
+    assert!(!Totp::check_ticket(&config, "1", "not a ticket"));
+
+    config.security.jwt_secret = "another secret".into();
+    assert!(!Totp::check_ticket(&config, "1", &ticket));
+}
+
+#[test]
+fn backup_code_nonces_match_their_purpose() {
+    let mut config = Config::default();
+    config.security.jwt_secret = "secret".into();
+    let view = Totp::backup_codes_nonce(&config, "1", false).unwrap();
+    let regenerate = Totp::backup_codes_nonce(&config, "1", true).unwrap();
+    assert!(Totp::check_backup_codes_nonce(&config, "1", &view, false));
+    assert!(Totp::check_backup_codes_nonce(
+        &config,
+        "1",
+        &regenerate,
+        true
+    ));
+    assert!(!Totp::check_backup_codes_nonce(&config, "1", &view, true));
+    assert!(!Totp::check_backup_codes_nonce(
+        &config,
+        "1",
+        &regenerate,
+        false
+    ));
+    assert!(!Totp::check_backup_codes_nonce(&config, "2", &view, false));
+
+    // Login tickets are no nonces.
+    let ticket = Totp::ticket(&config, "1").unwrap();
+    assert!(!Totp::check_backup_codes_nonce(
+        &config, "1", &ticket, false
+    ));
+}
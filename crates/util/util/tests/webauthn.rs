@@ -0,0 +1,115 @@
+//! Security-key ceremonies against a software authenticator.
+
+use config::Config;
+use url::Url;
+use util::WebAuthn;
+use webauthn_authenticator_rs::{softpasskey::SoftPasskey, WebauthnAuthenticator};
+use webauthn_rs::prelude::{CreationChallengeResponse, RequestChallengeResponse};
+
+const ORIGIN: &str = "https://spacebar.example";
+
+fn config() -> Config {
+    let mut config = Config::default();
+    config.security.jwt_secret = "secret".into();
+    config.general.front_page = Some(ORIGIN.into());
+    config
+}
+
+fn origin() -> Url {
+    Url::parse(ORIGIN).unwrap()
+}
+
+/// Register a new key of `authenticator` for `user_id`, returning the
+/// stored credential.
+fn register(
+    config: &Config,
+    authenticator: &mut WebauthnAuthenticator<SoftPasskey>,
+    user_id: &str,
+) -> util::webauthn::RegisteredKey {
+    let (options, ticket) = WebAuthn::start_registration(config, user_id, "user", &[]).unwrap();
+    let options: CreationChallengeResponse = serde_json::from_value(options).unwrap();
+    let credential = authenticator.do_registration(origin(), options).unwrap();
+    let credential = serde_json::to_string(&credential).unwrap();
+    WebAuthn::finish_registration(config, user_id, &ticket, &credential).unwrap()
+}
+
+#[test]
+fn registration_and_login_round_trip() {
+    let config = config();
+    let mut authenticator = WebauthnAuthenticator::new(SoftPasskey::new(true));
+    let key = register(&config, &mut authenticator, "1234");
+    let credentials = [key.credential.clone()];
+
+    for _ in 0..2 {
+        let (options, ticket) = WebAuthn::start_authentication(&config, "1234", &credentials)
+            .unwrap()
+            .expect("the registered key is usable");
+        let options: RequestChallengeResponse = serde_json::from_value(options).unwrap();
+        let assertion = authenticator.do_authentication(origin(), options).unwrap();
+        let assertion = serde_json::to_string(&assertion).unwrap();
+
+        let used =
+            WebAuthn::finish_authentication(&config, "1234", &ticket, &assertion, &credentials)
+                .unwrap();
+        assert_eq!(used.key_id, key.key_id);
+
+        // The ticket belongs to the user it was issued to.
+        assert!(WebAuthn::finish_authentication(
+            &config,
+            "5678",
+            &ticket,
+            &assertion,
+            &credentials
+        )
+        .is_err());
+        // The key must be one of the user's.
+        assert!(
+            WebAuthn::finish_authentication(&config, "1234", &ticket, &assertion, &[]).is_err()
+        );
+    }
+}
+
+#[test]
+fn registration_ticket_is_bound_to_its_user() {
+    let config = config();
+    let mut authenticator = WebauthnAuthenticator::new(SoftPasskey::new(true));
+    let (options, ticket) = WebAuthn::start_registration(&config, "1234", "user", &[]).unwrap();
+    let options: CreationChallengeResponse = serde_json::from_value(options).unwrap();
+    let credential = authenticator.do_registration(origin(), options).unwrap();
+    let credential = serde_json::to_string(&credential).unwrap();
+
+    assert!(WebAuthn::finish_registration(&config, "5678", &ticket, &credential).is_err());
+    assert!(WebAuthn::finish_registration(&config, "1234", "not a ticket", &credential).is_err());
+    let mut other = config.clone();
+    other.security.jwt_secret = "another secret".into();
+    assert!(WebAuthn::finish_registration(&other, "1234", &ticket, &credential).is_err());
+}
+
+#[test]
+fn unparseable_stored_keys_are_skipped() {
+    let config = config();
+    // The TypeScript server stores keys as PEM.
+    let stored = [
+        "-----BEGIN PUBLIC KEY-----\nMFkwEwYHKoZIzj0CAQYIKoZIzj0DAQcDQgAE\n-----END PUBLIC KEY-----".to_string(),
+        "{}".to_string(),
+    ];
+    assert!(WebAuthn::start_authentication(&config, "1234", &stored)
+        .unwrap()
+        .is_none());
+    assert!(WebAuthn::start_authentication(&config, "1234", &[])
+        .unwrap()
+        .is_none());
+
+    // Alongside a usable key, only that one is offered.
+    let mut authenticator = WebauthnAuthenticator::new(SoftPasskey::new(true));
+    let key = register(&config, &mut authenticator, "1234");
+    let mixed = [stored[0].clone(), key.credential.clone(), stored[1].clone()];
+    let (options, _) = WebAuthn::start_authentication(&config, "1234", &mixed)
+        .unwrap()
+        .expect("the registered key is usable");
+    let options: RequestChallengeResponse = serde_json::from_value(options).unwrap();
+    assert_eq!(options.public_key.allow_credentials.len(), 1);
+
+    // Registration works with unusable keys on file too.
+    assert!(WebAuthn::start_registration(&config, "1234", "user", &stored).is_ok());
+}